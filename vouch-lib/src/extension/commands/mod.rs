@@ -5,8 +5,11 @@ use structopt::{self, StructOpt};
 mod common;
 pub mod identify_file_defined_dependencies;
 pub mod identify_package_dependencies;
+pub mod package_download_stats;
+pub mod package_license;
 pub mod registries_package_metadata;
 mod static_data;
+pub mod verify_archive_hash;
 
 #[derive(Debug, StructOpt, Clone)]
 enum Command {
@@ -25,6 +28,18 @@ enum Command {
     /// Get package metadata from registries.
     #[structopt(name = registries_package_metadata::COMMAND_NAME)]
     RegistriesPackageMetadata(registries_package_metadata::Arguments),
+
+    /// Get package license from registries.
+    #[structopt(name = package_license::COMMAND_NAME)]
+    PackageLicense(package_license::Arguments),
+
+    /// Get package download counts from registries.
+    #[structopt(name = package_download_stats::COMMAND_NAME)]
+    PackageDownloadStats(package_download_stats::Arguments),
+
+    /// Verify a downloaded package archive against the registry published hash.
+    #[structopt(name = verify_archive_hash::COMMAND_NAME)]
+    VerifyArchiveHash(verify_archive_hash::Arguments),
 }
 
 fn run_command<T: Extension + std::fmt::Debug>(command: Command, extension: &mut T) -> Result<()> {
@@ -44,6 +59,18 @@ fn run_command<T: Extension + std::fmt::Debug>(command: Command, extension: &mut
         Command::RegistriesPackageMetadata(args) => {
             registries_package_metadata::run_command(&args, extension)?;
         }
+
+        Command::PackageLicense(args) => {
+            package_license::run_command(&args, extension)?;
+        }
+
+        Command::PackageDownloadStats(args) => {
+            package_download_stats::run_command(&args, extension)?;
+        }
+
+        Command::VerifyArchiveHash(args) => {
+            verify_archive_hash::run_command(&args, extension)?;
+        }
     }
     Ok(())
 }
@@ -0,0 +1,32 @@
+use super::common;
+use crate::extension::common::Extension;
+use anyhow::Result;
+use structopt::{self, StructOpt};
+
+pub const COMMAND_NAME: &str = "package-download-stats";
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct Arguments {
+    /// Package name.
+    #[structopt(name = "package-name")]
+    pub package_name: String,
+
+    /// Package version.
+    #[structopt(name = "package-version")]
+    pub package_version: String,
+}
+
+pub fn run_command<T: Extension + std::fmt::Debug>(
+    args: &Arguments,
+    extension: &mut T,
+) -> Result<()> {
+    let download_stats =
+        extension.package_download_stats(&args.package_name, &args.package_version);
+    common::communicate_result(download_stats)?;
+    Ok(())
+}
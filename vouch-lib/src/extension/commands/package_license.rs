@@ -0,0 +1,31 @@
+use super::common;
+use crate::extension::common::Extension;
+use anyhow::Result;
+use structopt::{self, StructOpt};
+
+pub const COMMAND_NAME: &str = "package-license";
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct Arguments {
+    /// Package name.
+    #[structopt(name = "package-name")]
+    pub package_name: String,
+
+    /// Package version.
+    #[structopt(name = "package-version")]
+    pub package_version: String,
+}
+
+pub fn run_command<T: Extension + std::fmt::Debug>(
+    args: &Arguments,
+    extension: &mut T,
+) -> Result<()> {
+    let license = extension.package_license(&args.package_name, &args.package_version);
+    common::communicate_result(license)?;
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use super::common;
+use crate::extension::common::Extension;
+use anyhow::Result;
+use structopt::{self, StructOpt};
+
+pub const COMMAND_NAME: &str = "verify-archive-hash";
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct Arguments {
+    /// Downloaded package archive path.
+    #[structopt(name = "downloaded-path", parse(from_os_str))]
+    pub downloaded_path: std::path::PathBuf,
+
+    /// Registry published archive hash.
+    #[structopt(name = "expected-hash")]
+    pub expected_hash: String,
+}
+
+pub fn run_command<T: Extension + std::fmt::Debug>(
+    args: &Arguments,
+    extension: &mut T,
+) -> Result<()> {
+    let matches = extension.verify_archive_hash(&args.downloaded_path, &args.expected_hash);
+    common::communicate_result(matches)?;
+    Ok(())
+}
@@ -1,21 +1,43 @@
 use anyhow::Result;
 
+/// Reason for failing to identify a specific dependency version.
+///
+/// Untagged serialization: variants are distinguished by their field names. A plain string
+/// deserializes as `Unknown`, which matches the message-only format of older extensions.
 #[derive(
     Debug, Clone, Hash, Eq, PartialEq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
 )]
-pub struct VersionError(String);
+#[serde(untagged)]
+pub enum VersionError {
+    /// Version range rather than a pinned version. Example: ^1.2.0
+    Unpinned { constraint: String },
+
+    /// Dependency sourced from a git repository.
+    GitDependency { url: String },
+
+    /// Dependency sourced from a local path.
+    LocalPath { path: std::path::PathBuf },
+
+    /// Any other version error, described by a message.
+    Unknown(String),
+}
 
 impl VersionError {
     pub fn from_missing_version() -> Self {
-        Self("Missing version number".to_string())
+        Self::Unknown("Missing version number".to_string())
     }
 
     pub fn from_parse_error(raw_version_number: &str) -> Self {
-        Self(format!("Version parse error: {}", raw_version_number))
+        Self::Unknown(format!("Version parse error: {}", raw_version_number))
     }
 
     pub fn message(&self) -> String {
-        self.0.clone()
+        match self {
+            Self::Unpinned { constraint } => format!("Unpinned version: {}", constraint),
+            Self::GitDependency { url } => format!("Git dependency: {}", url),
+            Self::LocalPath { path } => format!("Local path dependency: {}", path.display()),
+            Self::Unknown(message) => message.clone(),
+        }
     }
 }
 
@@ -63,6 +85,11 @@ pub struct FileDefinedDependencies {
     /// Absolute file path for dependencies specification file.
     pub path: std::path::PathBuf,
 
+    /// Dependencies specification file path relative to the inspected working directory.
+    /// Set by Vouch from the absolute path. Extensions need not set this field.
+    #[serde(default)]
+    pub lock_file_path: std::path::PathBuf,
+
     /// Dependencies registry host name.
     pub registry_host_name: String,
 
@@ -79,6 +106,29 @@ impl DependenciesCollection for FileDefinedDependencies {
     }
 }
 
+/// Hash algorithm used for a package artifact checksum.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    Hash,
+    Eq,
+    PartialEq,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    #[default]
+    Blake3,
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
 #[derive(Debug, Clone, Hash, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct RegistryPackageMetadata {
     pub registry_host_name: String,
@@ -88,6 +138,22 @@ pub struct RegistryPackageMetadata {
     pub is_primary: bool,
     // Included here incase package version was not given but found.
     pub package_version: String,
+    // Package maintainer names as listed by the registry.
+    #[serde(default)]
+    pub maintainers: Vec<String>,
+    // Artifact checksum as published by the registry, if any.
+    #[serde(default)]
+    pub artifact_hash: Option<String>,
+    // Algorithm of the registry artifact checksum.
+    #[serde(default)]
+    pub hash_algorithm: HashAlgorithm,
+}
+
+/// Package download counts as reported by a registry.
+#[derive(Debug, Clone, Default, Hash, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DownloadStats {
+    pub weekly: Option<u64>,
+    pub total: Option<u64>,
 }
 
 pub trait FromLib: Extension + Send + Sync {
@@ -114,6 +180,14 @@ pub trait Extension: Send + Sync {
     // Returns supported registries host names.
     fn registries(&self) -> Vec<String>;
 
+    /// Returns the command names of optional methods which the extension implements.
+    ///
+    /// Process extensions only call optional methods which are listed here.
+    /// Example value: package-license
+    fn capabilities(&self) -> Vec<String> {
+        Vec::new()
+    }
+
     /// Identify specific package dependencies.
     fn identify_package_dependencies(
         &self,
@@ -135,4 +209,44 @@ pub trait Extension: Send + Sync {
         package_name: &str,
         package_version: &Option<&str>,
     ) -> Result<Vec<RegistryPackageMetadata>>;
+
+    /// Query package registries for package license.
+    ///
+    /// Returns None if the license is unknown or unsupported by the extension.
+    fn package_license(
+        &self,
+        _package_name: &str,
+        _package_version: &str,
+    ) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Query package registries for package download counts.
+    ///
+    /// Returns None if download counts are unavailable or unsupported by the extension.
+    fn package_download_stats(
+        &self,
+        _package_name: &str,
+        _package_version: &str,
+    ) -> Result<Option<DownloadStats>> {
+        Ok(None)
+    }
+
+    /// Verify a downloaded package archive against the registry published hash.
+    ///
+    /// The default implementation compares a hex encoded SHA-256 digest. Extensions for
+    /// registries which publish hashes using other algorithms should override this method.
+    fn verify_archive_hash(
+        &self,
+        downloaded_path: &std::path::PathBuf,
+        expected_hash: &str,
+    ) -> Result<bool> {
+        use sha2::Digest;
+
+        let mut file = std::fs::File::open(downloaded_path)?;
+        let mut hasher = sha2::Sha256::new();
+        std::io::copy(&mut file, &mut hasher)?;
+        let hash = format!("{:x}", hasher.finalize());
+        Ok(hash.eq_ignore_ascii_case(expected_hash.trim()))
+    }
 }
@@ -3,6 +3,7 @@ pub mod common;
 pub mod process;
 
 pub use common::{
-    DependenciesCollection, Dependency, Extension, FileDefinedDependencies, FromLib, FromProcess,
-    PackageDependencies, RegistryPackageMetadata, VersionParseResult,
+    DependenciesCollection, Dependency, DownloadStats, Extension, FileDefinedDependencies, FromLib,
+    FromProcess, HashAlgorithm, PackageDependencies, RegistryPackageMetadata, VersionError,
+    VersionParseResult,
 };
@@ -1,11 +1,18 @@
 use anyhow::{format_err, Context, Result};
+use std::io::Read;
 
 use super::common;
 
+/// Default maximum duration of an extension process call.
+pub static DEFAULT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct StaticData {
     pub name: String,
     pub registry_host_names: Vec<String>,
+    // Command names of supported optional methods.
+    #[serde(default)]
+    pub capabilities: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -13,6 +20,22 @@ pub struct ProcessExtension {
     process_path_: std::path::PathBuf,
     name_: String,
     registry_host_names_: Vec<String>,
+    capabilities_: Vec<String>,
+    timeout_: std::time::Duration,
+}
+
+impl ProcessExtension {
+    /// Set maximum duration of extension process calls.
+    pub fn set_timeout(&mut self, timeout: std::time::Duration) {
+        self.timeout_ = timeout;
+    }
+
+    /// Returns true if the extension process supports the given optional command.
+    fn has_capability(&self, command_name: &str) -> bool {
+        self.capabilities_
+            .iter()
+            .any(|capability| capability == command_name)
+    }
 }
 
 impl common::FromProcess for ProcessExtension {
@@ -28,7 +51,8 @@ impl common::FromProcess for ProcessExtension {
             let reader = std::io::BufReader::new(file);
             serde_yaml::from_reader(reader)?
         } else {
-            let static_data: Box<StaticData> = run_process(&process_path, &vec!["static-data"])?;
+            let static_data: Box<StaticData> =
+                run_process(&process_path, &vec!["static-data"], &DEFAULT_TIMEOUT)?;
             let static_data = *static_data;
 
             let file = std::fs::OpenOptions::new()
@@ -48,6 +72,8 @@ impl common::FromProcess for ProcessExtension {
             process_path_: process_path.clone(),
             name_: static_data.name,
             registry_host_names_: static_data.registry_host_names,
+            capabilities_: static_data.capabilities,
+            timeout_: DEFAULT_TIMEOUT,
         })
     }
 }
@@ -61,6 +87,10 @@ impl common::Extension for ProcessExtension {
         self.registry_host_names_.clone()
     }
 
+    fn capabilities(&self) -> Vec<String> {
+        self.capabilities_.clone()
+    }
+
     /// Returns a list of dependencies for the given package.
     ///
     /// Returns one package dependencies structure per registry.
@@ -84,7 +114,7 @@ impl common::Extension for ProcessExtension {
             args.push(extension_arg);
         }
         let output: Box<Vec<common::PackageDependencies>> =
-            run_process(&self.process_path_, &args)?;
+            run_process(&self.process_path_, &args, &self.timeout_)?;
         Ok(*output)
     }
 
@@ -108,7 +138,7 @@ impl common::Extension for ProcessExtension {
             args.push(extension_arg);
         }
         let output: Box<Vec<common::FileDefinedDependencies>> =
-            run_process(&self.process_path_, &args)?;
+            run_process(&self.process_path_, &args, &self.timeout_)?;
         Ok(*output)
     }
 
@@ -127,7 +157,60 @@ impl common::Extension for ProcessExtension {
         }
 
         let output: Box<Vec<common::RegistryPackageMetadata>> =
-            run_process(&self.process_path_, &args)?;
+            run_process(&self.process_path_, &args, &self.timeout_)?;
+        Ok(*output)
+    }
+
+    /// Query package registries for package license.
+    ///
+    /// The extension process is only called if it lists the capability in its static data.
+    fn package_license(&self, package_name: &str, package_version: &str) -> Result<Option<String>> {
+        let command_name = super::commands::package_license::COMMAND_NAME;
+        if !self.has_capability(&command_name) {
+            return Ok(None);
+        }
+        let args = vec![command_name, package_name, package_version];
+        let output: Box<Option<String>> = run_process(&self.process_path_, &args, &self.timeout_)?;
+        Ok(*output)
+    }
+
+    /// Query package registries for package download counts.
+    ///
+    /// The extension process is only called if it lists the capability in its static data.
+    fn package_download_stats(
+        &self,
+        package_name: &str,
+        package_version: &str,
+    ) -> Result<Option<common::DownloadStats>> {
+        let command_name = super::commands::package_download_stats::COMMAND_NAME;
+        if !self.has_capability(&command_name) {
+            return Ok(None);
+        }
+        let args = vec![command_name, package_name, package_version];
+        let output: Box<Option<common::DownloadStats>> =
+            run_process(&self.process_path_, &args, &self.timeout_)?;
+        Ok(*output)
+    }
+
+    /// Verify a downloaded package archive against the registry published hash.
+    ///
+    /// The extension process is only called if it lists the capability in its static data.
+    /// Otherwise returns true and verification is left to the registry checksum.
+    fn verify_archive_hash(
+        &self,
+        downloaded_path: &std::path::PathBuf,
+        expected_hash: &str,
+    ) -> Result<bool> {
+        let command_name = super::commands::verify_archive_hash::COMMAND_NAME;
+        if !self.has_capability(command_name) {
+            return Ok(true);
+        }
+        let downloaded_path = downloaded_path.to_str().ok_or(format_err!(
+            "Failed to parse string from archive path: {}",
+            downloaded_path.display()
+        ))?;
+        let args = vec![command_name, downloaded_path, expected_hash];
+        let output: Box<bool> = run_process(&self.process_path_, &args, &self.timeout_)?;
         Ok(*output)
     }
 }
@@ -138,7 +221,14 @@ pub struct ProcessResult<T> {
     pub err: Option<String>,
 }
 
-fn run_process<'a, T: ?Sized>(process_path: &std::path::PathBuf, args: &Vec<&str>) -> Result<Box<T>>
+/// Run extension process and parse its result.
+///
+/// The process is killed if it does not exit within the given timeout.
+fn run_process<'a, T: ?Sized>(
+    process_path: &std::path::PathBuf,
+    args: &Vec<&str>,
+    timeout: &std::time::Duration,
+) -> Result<Box<T>>
 where
     for<'de> T: serde::Deserialize<'de> + 'a,
 {
@@ -150,14 +240,42 @@ where
         "Failed to parse string from process path: {}",
         process_path.display()
     ))?;
-    let handle = std::process::Command::new(process)
+    let mut child = std::process::Command::new(process)
         .args(args)
         .stdin(std::process::Stdio::null())
-        .stderr(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
         .stdout(std::process::Stdio::piped())
-        .output()?;
+        .spawn()?;
+
+    // Read stdout concurrently so that the process does not block on a full pipe.
+    let mut child_stdout = child
+        .stdout
+        .take()
+        .ok_or(format_err!("Failed to capture extension process stdout."))?;
+    let stdout_reader = std::thread::spawn(move || -> std::io::Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        child_stdout.read_to_end(&mut buffer)?;
+        Ok(buffer)
+    });
+
+    let start = std::time::Instant::now();
+    while child.try_wait()?.is_none() {
+        if start.elapsed() > *timeout {
+            child.kill()?;
+            child.wait()?;
+            return Err(format_err!(
+                "Extension process timed out after {} seconds: {}",
+                timeout.as_secs(),
+                process_path.display()
+            ));
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
 
-    let stdout = String::from_utf8_lossy(&handle.stdout);
+    let stdout = stdout_reader
+        .join()
+        .map_err(|_| format_err!("Failed to read extension process stdout."))??;
+    let stdout = String::from_utf8_lossy(&stdout);
     let process_result: ProcessResult<T> = serde_json::from_str(&stdout)?;
 
     if let Some(result) = process_result.ok {
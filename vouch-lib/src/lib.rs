@@ -1,5 +1,10 @@
 pub mod api;
 pub mod extension;
 
+pub use extension::{
+    Dependency, DownloadStats, Extension, FileDefinedDependencies, FromLib, FromProcess,
+    PackageDependencies, RegistryPackageMetadata, VersionError,
+};
+
 #[macro_use]
 extern crate lazy_static;
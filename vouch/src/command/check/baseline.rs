@@ -0,0 +1,124 @@
+use anyhow::{Context, Result};
+
+use super::report;
+use crate::review;
+
+/// An accepted dependency finding.
+#[derive(Debug, Clone, Eq, Ord, PartialEq, PartialOrd, serde::Serialize, serde::Deserialize)]
+pub struct Entry {
+    pub name: String,
+    pub version: String,
+    pub registry: String,
+    pub summary: review::Summary,
+}
+
+/// A set of accepted dependency findings.
+#[derive(Debug, Default, Clone)]
+pub struct Baseline {
+    entries: std::collections::BTreeSet<Entry>,
+}
+
+impl Baseline {
+    pub fn load(path: &std::path::PathBuf) -> Result<Self> {
+        let file = std::fs::File::open(path)
+            .context(format!("Can't open baseline file: {}", path.display()))?;
+        let reader = std::io::BufReader::new(file);
+        let entries: Vec<Entry> = serde_json::from_reader(reader)
+            .context(format!("Failed to parse baseline file: {}", path.display()))?;
+        Ok(Self {
+            entries: entries.into_iter().collect(),
+        })
+    }
+
+    pub fn dump(&self, path: &std::path::PathBuf) -> Result<()> {
+        let entries: Vec<_> = self.entries.iter().collect();
+        std::fs::write(path, serde_json::to_string_pretty(&entries)?)
+            .context(format!("Can't write baseline file: {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Mark dependency reports which match baseline entries.
+    pub fn apply(
+        &self,
+        dependency_reports: &mut Vec<report::DependencyReport>,
+        registry_host_name: &str,
+    ) {
+        for dependency_report in dependency_reports {
+            if dependency_report.suppression.is_some() {
+                continue;
+            }
+            if let Some(entry) = get_entry(dependency_report, registry_host_name) {
+                if self.entries.contains(&entry) {
+                    dependency_report.suppression = Some(report::Suppression::Baseline);
+                }
+            }
+        }
+    }
+
+    /// Add all findings from dependency reports. Passing and ignored reports are skipped.
+    pub fn extend_from_reports(
+        &mut self,
+        dependency_reports: &Vec<report::DependencyReport>,
+        registry_host_name: &str,
+    ) {
+        for dependency_report in dependency_reports {
+            if dependency_report.summary == review::Summary::Pass
+                || dependency_report.suppression == Some(report::Suppression::Ignored)
+            {
+                continue;
+            }
+            if let Some(entry) = get_entry(dependency_report, registry_host_name) {
+                self.entries.insert(entry);
+            }
+        }
+    }
+}
+
+fn get_entry(
+    dependency_report: &report::DependencyReport,
+    registry_host_name: &str,
+) -> Option<Entry> {
+    Some(Entry {
+        name: dependency_report.name.clone(),
+        version: dependency_report.version.clone()?,
+        registry: registry_host_name.to_string(),
+        summary: dependency_report.summary.clone(),
+    })
+}
+
+/// Baseline options for a check run.
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// Accepted findings to mark in reports.
+    pub baseline: Option<Baseline>,
+
+    /// Findings collected during the run, to be written to the given path.
+    pub update: Option<(std::path::PathBuf, Baseline)>,
+}
+
+impl Options {
+    /// Mark accepted findings and collect findings for update.
+    pub fn process(
+        &mut self,
+        dependency_reports: &mut Vec<report::DependencyReport>,
+        registry_host_name: &str,
+    ) {
+        if let Some(baseline) = &self.baseline {
+            baseline.apply(dependency_reports, registry_host_name);
+        }
+        if let Some((_path, update_baseline)) = &mut self.update {
+            update_baseline.extend_from_reports(dependency_reports, registry_host_name);
+        }
+    }
+
+    /// Write collected findings if requested. Returns the written file path.
+    pub fn write_update(&self) -> Result<Option<&std::path::PathBuf>> {
+        match &self.update {
+            Some((path, update_baseline)) => {
+                update_baseline.dump(path)?;
+                Ok(Some(path))
+            }
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use anyhow::Result;
+use prettytable::{self, cell};
+
+use crate::common;
+use crate::common::StoreTransaction;
+use crate::extension;
+use crate::review;
+
+use super::table;
+
+/// Comment identity which is stable across package versions.
+///
+/// Line numbers are ignored and the version specific workspace root is stripped from the path.
+#[derive(Debug, Clone, Eq, Ord, PartialEq, PartialOrd)]
+struct CommentKey {
+    summary: review::Summary,
+    path: std::path::PathBuf,
+    message: String,
+}
+
+impl CommentKey {
+    fn new(comment: &review::comment::Comment, package_version: &str) -> Self {
+        Self {
+            summary: comment.summary.clone(),
+            path: normalize_path(&comment.path, package_version),
+            message: comment.message.trim().to_string(),
+        }
+    }
+}
+
+/// Prints warn and fail review comments of the new package version which are not found in the
+/// reviews of the old package version.
+///
+/// Returns false, without printing, if either version has no reviews.
+pub fn report(
+    package_name: &str,
+    old_package_version: &str,
+    new_package_version: &str,
+    extension_names: &std::collections::BTreeSet<String>,
+    color_enabled: bool,
+    config: &common::config::Config,
+    tx: &StoreTransaction,
+) -> Result<bool> {
+    let registry_host_names =
+        extension::manage::get_enabled_registry_host_names(extension_names, config)?;
+    let old_reviews = get_reviews(package_name, old_package_version, &registry_host_names, tx)?;
+    let new_reviews = get_reviews(package_name, new_package_version, &registry_host_names, tx)?;
+    if old_reviews.is_empty() || new_reviews.is_empty() {
+        return Ok(false);
+    }
+
+    let old_keys: std::collections::BTreeSet<_> = get_warn_fail_comments(&old_reviews)
+        .into_iter()
+        .map(|comment| CommentKey::new(&comment, old_package_version))
+        .collect();
+    let mut new_comments: Vec<_> = get_warn_fail_comments(&new_reviews)
+        .into_iter()
+        .filter(|comment| !old_keys.contains(&CommentKey::new(comment, new_package_version)))
+        .collect();
+    new_comments.sort();
+
+    println!(
+        "Comparing reviews of {name} {old} with {name} {new}",
+        name = package_name,
+        old = old_package_version,
+        new = new_package_version
+    );
+    if new_comments.is_empty() {
+        println!("No new warn or fail review comments.");
+        return Ok(true);
+    }
+
+    let mut table = prettytable::Table::new();
+    table.set_titles(prettytable::row![c => "  ", "file", "line", "comment"]);
+    table.set_format(*prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    for comment in &new_comments {
+        let line = match &comment.selection {
+            Some(selection) => (selection.start.line + 1).to_string(),
+            None => "".to_string(),
+        };
+        table.add_row(prettytable::Row::new(vec![
+            table::get_summary_cell(&comment.summary, color_enabled),
+            prettytable::Cell::new(
+                &normalize_path(&comment.path, new_package_version)
+                    .display()
+                    .to_string(),
+            ),
+            prettytable::Cell::new_align(&line, prettytable::format::Alignment::RIGHT),
+            prettytable::Cell::new(&comment.message),
+        ]));
+    }
+    table.printstd();
+    println!("New review comments: {}", new_comments.len());
+    Ok(true)
+}
+
+fn get_reviews(
+    package_name: &str,
+    package_version: &str,
+    registry_host_names: &std::collections::BTreeSet<String>,
+    tx: &StoreTransaction,
+) -> Result<Vec<review::Review>> {
+    review::index::get(
+        &review::index::Fields {
+            package_name: Some(package_name),
+            package_version: Some(package_version),
+            registry_host_names: Some(
+                registry_host_names
+                    .iter()
+                    .map(|host_name| host_name.as_str())
+                    .collect(),
+            ),
+            ..Default::default()
+        },
+        tx,
+    )
+}
+
+fn get_warn_fail_comments(reviews: &[review::Review]) -> Vec<review::comment::Comment> {
+    reviews
+        .iter()
+        .flat_map(|review| review.comments.iter())
+        .filter(|comment| {
+            comment.summary == review::Summary::Warn || comment.summary == review::Summary::Fail
+        })
+        .cloned()
+        .collect()
+}
+
+/// Strip path components up to and including the last component which contains the package
+/// version. Example: d3-4.10.0/src/index.js -> src/index.js
+fn normalize_path(path: &std::path::Path, package_version: &str) -> std::path::PathBuf {
+    let components: Vec<_> = path.components().collect();
+    let version_index = components.iter().rposition(|component| {
+        component
+            .as_os_str()
+            .to_str()
+            .is_some_and(|component| component.contains(package_version))
+    });
+    match version_index {
+        Some(index) => components[index + 1..].iter().collect(),
+        None => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comment_keys_match_across_versions() -> Result<()> {
+        let old_comment = review::comment::Comment {
+            id: 1,
+            summary: review::Summary::Warn,
+            path: std::path::PathBuf::from("d3-4.10.0/src/utils.js"),
+            message: "Unsafe eval.".to_string(),
+            selection: Some(review::comment::common::Selection {
+                start: review::comment::common::Position {
+                    line: 41,
+                    character: 0,
+                },
+                end: review::comment::common::Position {
+                    line: 41,
+                    character: 10,
+                },
+            }),
+        };
+        let new_comment = review::comment::Comment {
+            id: 2,
+            path: std::path::PathBuf::from("d3-4.11.0/src/utils.js"),
+            selection: None,
+            ..old_comment.clone()
+        };
+
+        assert_eq!(
+            CommentKey::new(&old_comment, "4.10.0"),
+            CommentKey::new(&new_comment, "4.11.0")
+        );
+        Ok(())
+    }
+}
@@ -4,25 +4,57 @@ use crate::common;
 use crate::common::StoreTransaction;
 use crate::extension;
 
+use super::baseline;
 use super::report;
 use super::table;
 
+/// Working directory report options.
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub working_directory: std::path::PathBuf,
+
+    /// Extensions were auto-detected rather than given by name.
+    pub auto_detect_extensions: bool,
+
+    /// Maximum number of dependencies to report.
+    pub limit: Option<usize>,
+
+    /// Show only dependencies with these summaries. Show all dependencies if empty.
+    pub show_only: Vec<crate::review::Summary>,
+}
+
+/// Report on dependencies defined in the working directory.
+///
+/// If extensions were auto-detected, extensions which find no dependency specification
+/// files are skipped before any registry queries are made.
 pub fn report(
-    extension_names: &std::collections::BTreeSet<String>,
-    extension_args: &Vec<String>,
+    fs_options: &Options,
+    report_options: &report::Options,
+    baseline_options: &mut baseline::Options,
     config: &common::config::Config,
     tx: &StoreTransaction,
-) -> Result<()> {
-    let extensions = extension::manage::get_enabled(&extension_names, &config)?;
-    let working_directory = std::env::current_dir()?;
-    log::debug!("Current working directory: {}", working_directory.display());
+) -> Result<report::Totals> {
+    let working_directory = &fs_options.working_directory;
+    let output_options = &report_options.output;
+    let extensions = extension::manage::get_enabled(&report_options.extension_names, &config)?;
+    log::debug!("Working directory: {}", working_directory.display());
 
-    let mut dependencies_found = false;
     let all_dependencies_specs = extension::identify_file_defined_dependencies(
         &extensions,
-        &extension_args,
+        &report_options.extension_args,
         &working_directory,
     )?;
+    let (all_dependencies_specs, limit_note) = match fs_options.limit {
+        Some(limit) => apply_limit(all_dependencies_specs, limit),
+        None => (all_dependencies_specs, None),
+    };
+
+    // Dependency reports grouped by registry host name and, unless shown in a source column,
+    // specification file path.
+    let mut dependency_groups = std::collections::BTreeMap::<
+        (String, Option<std::path::PathBuf>),
+        Vec<report::DependencyReport>,
+    >::new();
     for (extension, extension_all_dependencies) in
         extensions.iter().zip(all_dependencies_specs.into_iter())
     {
@@ -38,29 +70,188 @@ pub fn report(
                 continue;
             }
         };
-        for (index, fs_dependencies) in extension_all_dependencies.iter().enumerate() {
-            dependencies_found |= !fs_dependencies.dependencies.is_empty();
-            report_dependencies(&fs_dependencies, &tx)?;
-            let is_last = index == extension_all_dependencies.len() - 1;
-            if !is_last {
-                println!("");
+        if fs_options.auto_detect_extensions && extension_all_dependencies.is_empty() {
+            println!("No {} dependencies found", extension.name());
+            continue;
+        }
+        for fs_dependencies in extension_all_dependencies.iter() {
+            let mut dependency_reports: Vec<_> =
+                get_dependency_reports(&fs_dependencies, &report_options.status, &tx)?
+                    .into_iter()
+                    .filter_map(|dependency_report| report_options.ignore.apply(dependency_report))
+                    .collect();
+            report::add_license_notes(
+                &mut dependency_reports,
+                &extension,
+                &report_options.license,
+            )?;
+            if output_options.verbose {
+                report::add_maintainer_notes(&mut dependency_reports, &extension)?;
+                report::add_download_stats_notes(&mut dependency_reports, &extension)?;
+            }
+            baseline_options.process(&mut dependency_reports, &fs_dependencies.registry_host_name);
+            for dependency_report in &mut dependency_reports {
+                dependency_report.source_file = Some(fs_dependencies.lock_file_path.clone());
+            }
+            if dependency_reports.is_empty() {
+                continue;
             }
+
+            dependency_groups
+                .entry((
+                    fs_dependencies.registry_host_name.clone(),
+                    if output_options.show_source {
+                        None
+                    } else {
+                        Some(fs_dependencies.lock_file_path.clone())
+                    },
+                ))
+                .or_default()
+                .extend(dependency_reports);
         }
     }
 
-    if !dependencies_found {
+    if dependency_groups.is_empty() {
         println!(
             "No dependency specification files found in \
             working directory or parent directories."
+        );
+        return Ok(report::Totals::default());
+    }
+
+    let mut grand_totals = report::Totals::default();
+    let mut printed_table = false;
+    let mut count_all = 0;
+    let mut count_shown = 0;
+    for ((registry_host_name, lock_file_path), dependency_reports) in dependency_groups.iter() {
+        let totals = report::Totals::from_reports(&dependency_reports);
+        grand_totals.extend(&totals);
+
+        // Filter after totals are counted so that totals cover all dependencies.
+        count_all += dependency_reports.len();
+        let dependency_reports: Vec<_> = dependency_reports
+            .iter()
+            .filter(|dependency_report| {
+                fs_options.show_only.is_empty()
+                    || fs_options.show_only.contains(&dependency_report.summary)
+            })
+            .cloned()
+            .collect();
+        count_shown += dependency_reports.len();
+
+        let dependency_reports: Vec<_> = match output_options.format {
+            table::Format::Full => dependency_reports,
+            table::Format::Compact => dependency_reports
+                .into_iter()
+                .filter(|dependency_report| dependency_report.is_warn_or_fail())
+                .collect(),
+            table::Format::Minimal => continue,
+        };
+        if dependency_reports.is_empty() {
+            continue;
+        }
+
+        if printed_table {
+            println!("");
+        }
+        let label = match lock_file_path {
+            Some(lock_file_path) => {
+                format!("{} ({})", registry_host_name, lock_file_path.display())
+            }
+            None => registry_host_name.clone(),
+        };
+        let table = table::get(
+            &dependency_reports,
+            &output_options.get_table_options(&label),
+        )?;
+        table.printstd();
+        printed_table = true;
+        if output_options.format == table::Format::Full {
+            println!("{}", totals);
+        }
+    }
+
+    match output_options.format {
+        table::Format::Full => println!("\nGrand total: {}", grand_totals),
+        table::Format::Compact if printed_table => {
+            println!("\n{}", grand_totals.get_summary_line())
+        }
+        _ => println!("{}", grand_totals.get_summary_line()),
+    }
+    if !fs_options.show_only.is_empty() {
+        println!("(filtered: showing {} of {})", count_shown, count_all);
+    }
+    if let Some(limit_note) = limit_note {
+        println!("{}", limit_note);
+    }
+    Ok(grand_totals)
+}
+
+/// Keep only the first dependencies, in order of name, version, and registry.
+///
+/// Returns the limited dependency specifications and, if any dependencies were omitted, a note
+/// for display.
+fn apply_limit(
+    all_dependencies_specs: Vec<Result<Vec<vouch_lib::extension::FileDefinedDependencies>>>,
+    limit: usize,
+) -> (
+    Vec<Result<Vec<vouch_lib::extension::FileDefinedDependencies>>>,
+    Option<String>,
+) {
+    let get_key = |dependency: &vouch_lib::extension::Dependency, registry_host_name: &str| {
+        (
+            dependency.name.clone(),
+            dependency.version.clone().unwrap_or_default(),
+            registry_host_name.to_string(),
         )
+    };
+
+    let all_keys: std::collections::BTreeSet<_> = all_dependencies_specs
+        .iter()
+        .filter_map(|dependencies_specs| dependencies_specs.as_ref().ok())
+        .flatten()
+        .flat_map(|dependencies_spec| {
+            dependencies_spec
+                .dependencies
+                .iter()
+                .map(move |dependency| get_key(&dependency, &dependencies_spec.registry_host_name))
+        })
+        .collect();
+    if all_keys.len() <= limit {
+        return (all_dependencies_specs, None);
     }
-    Ok(())
+    let selected_keys: std::collections::BTreeSet<_> =
+        all_keys.iter().take(limit).cloned().collect();
+
+    let all_dependencies_specs = all_dependencies_specs
+        .into_iter()
+        .map(|dependencies_specs| {
+            Ok(dependencies_specs?
+                .into_iter()
+                .map(|mut dependencies_spec| {
+                    let registry_host_name = dependencies_spec.registry_host_name.clone();
+                    dependencies_spec.dependencies.retain(|dependency| {
+                        selected_keys.contains(&get_key(&dependency, &registry_host_name))
+                    });
+                    dependencies_spec
+                })
+                .collect())
+        })
+        .collect();
+    let note = format!(
+        "Showing first {} of {} total dependencies (use --limit to change)",
+        limit,
+        all_keys.len()
+    );
+    (all_dependencies_specs, Some(note))
 }
 
-fn report_dependencies(
+/// Generate reports for all dependencies defined within a dependencies specification file.
+fn get_dependency_reports(
     package_dependencies: &vouch_lib::extension::FileDefinedDependencies,
+    status_options: &report::StatusOptions,
     tx: &StoreTransaction,
-) -> Result<()> {
+) -> Result<Vec<report::DependencyReport>> {
     log::info!(
         "Generating report for dependencies specification file: {}",
         package_dependencies.path.display()
@@ -73,6 +264,7 @@ fn report_dependencies(
             Ok(report::get_dependency_report(
                 &dependency,
                 &package_dependencies.registry_host_name,
+                &status_options,
                 &tx,
             )?)
         })
@@ -80,16 +272,45 @@ fn report_dependencies(
     let dependency_reports = dependency_reports?;
 
     log::info!("Number of dependencies found: {}", dependency_reports.len());
-    if dependency_reports.is_empty() {
-        return Ok(());
-    }
+    Ok(dependency_reports)
+}
 
-    let table = table::get(&dependency_reports, false)?;
-    println!(
-        "Registry: {name}\n{path}",
-        name = package_dependencies.registry_host_name,
-        path = package_dependencies.path.display(),
-    );
-    table.printstd();
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_limit_selects_first_dependencies_by_name() -> Result<()> {
+        let get_dependency = |name: &str| vouch_lib::extension::Dependency {
+            name: name.to_string(),
+            version: Ok("1.0.0".to_string()),
+        };
+        let all_dependencies_specs =
+            vec![Ok(vec![vouch_lib::extension::FileDefinedDependencies {
+                path: std::path::PathBuf::from("/project/package-lock.json"),
+                lock_file_path: std::path::PathBuf::from("package-lock.json"),
+                registry_host_name: "npmjs.com".to_string(),
+                dependencies: vec![
+                    get_dependency("c"),
+                    get_dependency("a"),
+                    get_dependency("b"),
+                ],
+            }])];
+
+        let (result, note) = apply_limit(all_dependencies_specs, 2);
+        let names: Vec<_> = result
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .flat_map(|dependencies_spec| dependencies_spec.dependencies)
+            .map(|dependency| dependency.name)
+            .collect();
+        assert_eq!(names, vec!["a", "b"]);
+        assert_eq!(
+            note,
+            Some("Showing first 2 of 3 total dependencies (use --limit to change)".to_string())
+        );
+        Ok(())
+    }
 }
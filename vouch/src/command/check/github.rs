@@ -0,0 +1,379 @@
+//! Check dependencies added by a GitHub pull request.
+
+use anyhow::{format_err, Context, Result};
+use std::io::Write;
+
+use crate::common;
+use crate::common::StoreTransaction;
+use crate::extension;
+
+use super::report;
+use super::table;
+
+static GITHUB_API_URL: &str = "https://api.github.com";
+static GITHUB_TOKEN_ENV_VAR: &str = "GITHUB_TOKEN";
+static GITHUB_STEP_SUMMARY_ENV_VAR: &str = "GITHUB_STEP_SUMMARY";
+
+/// GitHub pull request reference. Example: vouch-dev/vouch#12
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PullRequest {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+}
+
+impl std::str::FromStr for PullRequest {
+    type Err = anyhow::Error;
+    fn from_str(input: &str) -> Result<PullRequest, Self::Err> {
+        let parse_error = || {
+            format_err!(
+                "Failed to parse pull request: {}\nExpected format: owner/repo#number",
+                input
+            )
+        };
+        let (repository, number) = input.split_at(input.find('#').ok_or_else(parse_error)?);
+        let mut repository_parts = repository.splitn(2, '/');
+        let owner = repository_parts.next().unwrap_or_default();
+        let repo = repository_parts.next().unwrap_or_default();
+        if owner.is_empty() || repo.is_empty() || repo.contains('/') {
+            return Err(parse_error());
+        }
+        let number = number[1..].parse().map_err(|_| parse_error())?;
+        Ok(PullRequest {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            number,
+        })
+    }
+}
+
+impl std::fmt::Display for PullRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}#{}", self.owner, self.repo, self.number)
+    }
+}
+
+/// Report on dependencies added by a GitHub pull request.
+///
+/// Dependencies from registries which are not handled by an enabled extension are skipped.
+/// If requested, the report is given as a markdown table suitable for a GitHub Actions step
+/// summary.
+pub fn report(
+    pull_request: &PullRequest,
+    report_options: &report::Options,
+    github_output: bool,
+    config: &common::config::Config,
+    tx: &StoreTransaction,
+) -> Result<report::Totals> {
+    let token = std::env::var(GITHUB_TOKEN_ENV_VAR).map_err(|_| {
+        format_err!(
+            "Environment variable not set: {}\n\
+            A GitHub token is required to query the dependency review API.",
+            GITHUB_TOKEN_ENV_VAR
+        )
+    })?;
+    let enabled_registry_host_names = extension::manage::get_enabled_registry_host_names(
+        &report_options.extension_names,
+        config,
+    )?;
+
+    let mut registry_groups =
+        std::collections::BTreeMap::<String, Vec<report::DependencyReport>>::new();
+    for (registry_host_name, dependency) in
+        get_added_dependencies(pull_request, GITHUB_API_URL, &token)?
+    {
+        if !enabled_registry_host_names.contains(&registry_host_name) {
+            log::debug!(
+                "Skipping dependency {} from registry without enabled extension: {}",
+                dependency.name,
+                registry_host_name
+            );
+            continue;
+        }
+        let dependency_report = report::get_dependency_report(
+            &dependency,
+            &registry_host_name,
+            &report_options.status,
+            tx,
+        )?;
+        if let Some(dependency_report) = report_options.ignore.apply(dependency_report) {
+            registry_groups
+                .entry(registry_host_name)
+                .or_default()
+                .push(dependency_report);
+        }
+    }
+
+    let mut grand_totals = report::Totals::default();
+    for dependency_reports in registry_groups.values() {
+        grand_totals.extend(&report::Totals::from_reports(dependency_reports));
+    }
+
+    if github_output {
+        let summary = get_markdown_summary(pull_request, &registry_groups, &grand_totals);
+        println!("{}", summary);
+        write_step_summary(&summary)?;
+        return Ok(grand_totals);
+    }
+
+    if registry_groups.is_empty() {
+        println!(
+            "No added dependencies found in pull request: {}",
+            pull_request
+        );
+        return Ok(grand_totals);
+    }
+    for (registry_host_name, dependency_reports) in registry_groups.iter() {
+        let dependency_reports: Vec<_> = match report_options.output.format {
+            table::Format::Full => dependency_reports.clone(),
+            table::Format::Compact => dependency_reports
+                .iter()
+                .filter(|dependency_report| dependency_report.is_warn_or_fail())
+                .cloned()
+                .collect(),
+            table::Format::Minimal => continue,
+        };
+        if dependency_reports.is_empty() {
+            continue;
+        }
+        let table = table::get(
+            &dependency_reports,
+            &table::TableOptions {
+                show_source: false,
+                ..report_options.output.get_table_options(registry_host_name)
+            },
+        )?;
+        table.printstd();
+        println!();
+    }
+    match report_options.output.format {
+        table::Format::Full => println!("Grand total: {}", grand_totals),
+        _ => println!("{}", grand_totals.get_summary_line()),
+    }
+    Ok(grand_totals)
+}
+
+/// Returns the registry host names and dependencies added by a pull request.
+fn get_added_dependencies(
+    pull_request: &PullRequest,
+    api_url: &str,
+    token: &str,
+) -> Result<Vec<(String, vouch_lib::extension::Dependency)>> {
+    let repository_url = format!(
+        "{api_url}/repos/{owner}/{repo}",
+        api_url = api_url.trim_end_matches('/'),
+        owner = pull_request.owner,
+        repo = pull_request.repo
+    );
+
+    let pull = get_json(
+        &format!("{}/pulls/{}", repository_url, pull_request.number),
+        token,
+    )?;
+    let get_sha = |name: &str| {
+        pull.get(name)
+            .and_then(|reference| reference.get("sha"))
+            .and_then(|sha| sha.as_str())
+            .ok_or(format_err!(
+                "Failed to find {} commit of pull request: {}",
+                name,
+                pull_request
+            ))
+    };
+    let basehead = format!("{}...{}", get_sha("base")?, get_sha("head")?);
+
+    let changes = get_json(
+        &format!("{}/dependency-graph/compare/{}", repository_url, basehead),
+        token,
+    )?;
+    let changes = changes.as_array().ok_or(format_err!(
+        "Unexpected dependency review API response for pull request: {}",
+        pull_request
+    ))?;
+    Ok(parse_added_dependencies(changes))
+}
+
+fn get_json(url: &str, token: &str) -> Result<serde_json::Value> {
+    let client = reqwest::blocking::Client::builder()
+        .user_agent(common::HTTP_USER_AGENT)
+        .build()?;
+    let response = client
+        .get(url)
+        .bearer_auth(token)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .send()
+        .context(format!("Failed to connect to GitHub API: {}", url))?;
+    if !response.status().is_success() {
+        return Err(format_err!(
+            "GitHub API request failed {}: HTTP status {}",
+            url,
+            response.status()
+        ));
+    }
+    let body = response.text()?;
+    serde_json::from_str(&body).context(format!("JSON was not well-formatted:\n{}", body))
+}
+
+/// Parse added dependencies from dependency review API changes.
+///
+/// Changes are mapped to registries using their package URL type. Changes from unknown
+/// ecosystems are skipped.
+fn parse_added_dependencies(
+    changes: &Vec<serde_json::Value>,
+) -> Vec<(String, vouch_lib::extension::Dependency)> {
+    let mut dependencies = vec![];
+    for change in changes {
+        let get_field = |name: &str| change.get(name).and_then(|value| value.as_str());
+        if get_field("change_type") != Some("added") {
+            continue;
+        }
+        let (name, registry_host_name) = match (
+            get_field("name"),
+            get_field("package_url").and_then(crate::registry::ecosystem::from_package_url),
+        ) {
+            (Some(name), Some(ecosystem)) => (name, ecosystem.registry_host_name),
+            _ => {
+                log::debug!("Skipping unsupported dependency change: {}", change);
+                continue;
+            }
+        };
+        let version = match get_field("version") {
+            Some(version) if !version.is_empty() => Ok(version.to_string()),
+            _ => Err(vouch_lib::extension::VersionError::from_missing_version()),
+        };
+        let dependency = (
+            registry_host_name.to_string(),
+            vouch_lib::extension::Dependency {
+                name: name.to_string(),
+                version,
+            },
+        );
+        if !dependencies.contains(&dependency) {
+            dependencies.push(dependency);
+        }
+    }
+    dependencies
+}
+
+/// Returns a markdown summary of dependency reports.
+fn get_markdown_summary(
+    pull_request: &PullRequest,
+    registry_groups: &std::collections::BTreeMap<String, Vec<report::DependencyReport>>,
+    totals: &report::Totals,
+) -> String {
+    let mut lines = vec![format!("### Vouch check: {}", pull_request), "".to_string()];
+    if registry_groups.is_empty() {
+        lines.push("No added dependencies found.".to_string());
+        return lines.join("\n");
+    }
+    lines.push("| summary | registry | name | version | reviews | notes |".to_string());
+    lines.push("| --- | --- | --- | --- | --- | --- |".to_string());
+    for (registry_host_name, dependency_reports) in registry_groups {
+        for dependency_report in dependency_reports {
+            let summary = match dependency_report.suppression {
+                Some(report::Suppression::Ignored) => "SKIP".to_string(),
+                Some(report::Suppression::Baseline) => "BASELINE".to_string(),
+                None => match dependency_report.summary {
+                    crate::review::Summary::Todo => "TODO".to_string(),
+                    _ => dependency_report.summary.to_string().to_uppercase(),
+                },
+            };
+            lines.push(format!(
+                "| {} | {} | {} | {} | {} | {} |",
+                summary,
+                registry_host_name,
+                dependency_report.name,
+                dependency_report.version.as_deref().unwrap_or_default(),
+                dependency_report
+                    .review_count
+                    .map(|count| count.to_string())
+                    .unwrap_or_default(),
+                dependency_report
+                    .note
+                    .as_deref()
+                    .unwrap_or_default()
+                    .replace('|', "\\|")
+            ));
+        }
+    }
+    lines.push("".to_string());
+    lines.push(totals.get_summary_line());
+    lines.join("\n")
+}
+
+/// Append the summary to the GitHub Actions step summary file, if running in GitHub Actions.
+fn write_step_summary(summary: &str) -> Result<()> {
+    let path = match std::env::var_os(GITHUB_STEP_SUMMARY_ENV_VAR) {
+        Some(path) => std::path::PathBuf::from(path),
+        None => return Ok(()),
+    };
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .context(format!(
+            "Can't open/create file for writing: {}",
+            path.display()
+        ))?;
+    writeln!(file, "{}", summary)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pull_request() -> Result<()> {
+        let expected = PullRequest {
+            owner: "vouch-dev".to_string(),
+            repo: "vouch".to_string(),
+            number: 12,
+        };
+        assert_eq!("vouch-dev/vouch#12".parse::<PullRequest>()?, expected);
+        assert!("vouch-dev/vouch".parse::<PullRequest>().is_err());
+        assert!("vouch#12".parse::<PullRequest>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_added_dependencies() -> Result<()> {
+        let pull = mockito::mock("GET", "/github/repos/vouch-dev/vouch/pulls/12")
+            .match_header("authorization", "Bearer token")
+            .with_status(200)
+            .with_body(r#"{"base": {"sha": "abc"}, "head": {"sha": "def"}}"#)
+            .create();
+        let compare = mockito::mock(
+            "GET",
+            "/github/repos/vouch-dev/vouch/dependency-graph/compare/abc...def",
+        )
+        .with_status(200)
+        .with_body(
+            r#"[
+                {"change_type": "added", "name": "d3", "version": "4.10.0",
+                 "package_url": "pkg:npm/d3@4.10.0"},
+                {"change_type": "removed", "name": "numpy", "version": "1.18.5",
+                 "package_url": "pkg:pypi/numpy@1.18.5"},
+                {"change_type": "added", "name": "rails", "version": "6.0.0",
+                 "package_url": "pkg:gem/rails@6.0.0"}
+            ]"#,
+        )
+        .create();
+
+        let pull_request = "vouch-dev/vouch#12".parse::<PullRequest>()?;
+        let api_url = format!("{}/github", mockito::server_url());
+        let result = get_added_dependencies(&pull_request, &api_url, "token")?;
+
+        pull.assert();
+        compare.assert();
+        let expected = vec![(
+            "npmjs.com".to_string(),
+            vouch_lib::extension::Dependency {
+                name: "d3".to_string(),
+                version: Ok("4.10.0".to_string()),
+            },
+        )];
+        assert_eq!(result, expected);
+        Ok(())
+    }
+}
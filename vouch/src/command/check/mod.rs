@@ -1,14 +1,21 @@
-use anyhow::Result;
+use anyhow::{format_err, Result};
 use structopt::{self, StructOpt};
 
 use crate::common;
 use crate::extension;
 use crate::store;
 
+mod baseline;
+mod compare;
 mod fs;
+mod github;
 mod package;
 mod report;
-mod table;
+pub mod table;
+mod watch;
+mod workspace;
+
+static EXTENSIONS_DOCUMENTATION_URL: &str = "https://github.com/vouch-dev/vouch#extensions";
 
 #[derive(Debug, StructOpt, Clone)]
 #[structopt(
@@ -25,36 +32,407 @@ pub struct Arguments {
     #[structopt(name = "package-version", requires("package-name"))]
     pub package_version: Option<String>,
 
+    /// Show only review comments of the given package version which are new compared to this
+    /// older version.
+    #[structopt(
+        long = "compare-version",
+        name = "old-version",
+        requires_all = &["package-name", "package-version"]
+    )]
+    pub compare_version: Option<String>,
+
     /// Specify an extension for handling the package or dependencies.
     /// Example values: py, js, rs
     #[structopt(long = "extension", short = "e", name = "name")]
     pub extension_names: Option<Vec<String>>,
+
+    /// Disable colored output.
+    #[structopt(long = "no-color")]
+    pub no_color: bool,
+
+    /// Output format. Compact shows only warn and fail packages. Minimal shows a summary line.
+    #[structopt(
+        long = "format",
+        name = "format",
+        default_value = "full",
+        possible_values = &["full", "compact", "minimal"]
+    )]
+    pub format: table::Format,
+
+    /// Show dependency licenses in the notes column.
+    #[structopt(long = "show-licenses")]
+    pub show_licenses: bool,
+
+    /// Warn on dependencies with a license outside of the given set.
+    /// Example values: MIT, Apache-2.0
+    #[structopt(long = "license-allow-list", name = "license")]
+    pub license_allow_list: Option<Vec<String>>,
+
+    /// Omit packages from the report. Supports glob patterns.
+    /// Example values: setuptools, @types/*
+    #[structopt(long = "ignore", name = "pattern", number_of_values = 1)]
+    pub ignore: Vec<String>,
+
+    /// Include ignored packages in the report.
+    #[structopt(long = "show-ignored")]
+    pub show_ignored: bool,
+
+    /// Show additional package information, such as maintainers, download counts,
+    /// reviewed line counts, and review quality scores.
+    #[structopt(long = "verbose", short = "v")]
+    pub verbose: bool,
+
+    /// Exit with a failure status if any dependency does not pass.
+    /// Unreviewed and warn dependencies are reported as fail.
+    #[structopt(long = "require-all-pass")]
+    pub require_all_pass: bool,
+
+    /// Minimum number of reviews required for a dependency to pass.
+    #[structopt(long = "min-reviews", name = "count", default_value = "0")]
+    pub min_reviews: usize,
+
+    /// Ignore reviews with a quality score below this value, between 0.0 and 1.0.
+    /// Reviews without a recorded quality score are also ignored.
+    #[structopt(long = "min-quality", name = "score", parse(try_from_str = parse_min_quality))]
+    pub min_quality: Option<f64>,
+
+    /// Show which peers have reviewed each dependency.
+    #[structopt(long = "show-reviewed-by")]
+    pub show_reviewed_by: bool,
+
+    /// Show one row per reviewing peer of each dependency. Not supported with compact format.
+    #[structopt(long = "group-by-peer")]
+    pub group_by_peer: bool,
+
+    /// Check dependencies for known vulnerabilities using the OSV database (https://osv.dev).
+    /// Vulnerable dependencies are reported as warn or fail, regardless of reviews.
+    #[structopt(long = "check-osv")]
+    pub check_osv: bool,
+
+    /// Show the review comments of each dependency which does not pass below its row.
+    #[structopt(long = "detail")]
+    pub detail: bool,
+
+    /// Check only the first given number of project dependencies, ordered by name.
+    #[structopt(
+        long = "limit",
+        name = "n",
+        conflicts_with_all = &["package-name", "pull-request", "update-baseline-path"]
+    )]
+    pub limit: Option<usize>,
+
+    /// Show only dependencies with the given review summary. May be given multiple times.
+    /// Totals and the exit status still account for all dependencies.
+    #[structopt(
+        long = "show-only",
+        name = "summary",
+        number_of_values = 1,
+        possible_values = &["pass", "warn", "fail", "todo"],
+        conflicts_with_all = &["package-name", "pull-request"]
+    )]
+    pub show_only: Vec<crate::review::Summary>,
+
+    /// Show the dependency specification file of each dependency, relative to the working
+    /// directory.
+    #[structopt(
+        long = "show-lock-file",
+        conflicts_with_all = &["package-name", "pull-request"]
+    )]
+    pub show_lock_file: bool,
+
+    /// Mark findings listed in the given baseline file as accepted.
+    #[structopt(long = "baseline", name = "baseline-path", parse(from_os_str))]
+    pub baseline: Option<std::path::PathBuf>,
+
+    /// Overwrite the given baseline file with all current findings.
+    #[structopt(
+        long = "update-baseline",
+        name = "update-baseline-path",
+        parse(from_os_str)
+    )]
+    pub update_baseline: Option<std::path::PathBuf>,
+
+    /// Check the ongoing review of the given package version using its review workspace.
+    /// The review is not committed.
+    #[structopt(
+        long = "workspace",
+        requires_all = &["package-name", "package-version"],
+        conflicts_with_all = &["old-version", "pull-request", "watch", "update-baseline-path"]
+    )]
+    pub workspace: bool,
+
+    /// Re-run the check whenever a dependency specification file changes.
+    #[structopt(
+        long = "watch",
+        conflicts_with_all = &["package-name", "update-baseline-path", "require-all-pass"]
+    )]
+    pub watch: bool,
+
+    /// Check only the dependencies added by a GitHub pull request.
+    /// Requires the GITHUB_TOKEN environment variable.
+    /// Example value: vouch-dev/vouch#12
+    #[structopt(
+        long = "github-pr",
+        name = "pull-request",
+        conflicts_with_all = &["package-name", "watch"]
+    )]
+    pub github_pr: Option<github::PullRequest>,
+
+    /// Output the GitHub pull request check as a markdown table.
+    /// The table is also appended to the GitHub Actions step summary, if available.
+    #[structopt(long = "github-output", requires = "pull-request")]
+    pub github_output: bool,
+
+    /// Check dependencies of the project in this directory instead of the current directory.
+    #[structopt(long = "working-directory", name = "path", parse(from_os_str))]
+    pub working_directory: Option<std::path::PathBuf>,
+}
+
+pub fn run_command(args: &Arguments, no_color: bool, extension_args: &Vec<String>) -> Result<()> {
+    if args.group_by_peer && args.format == table::Format::Compact {
+        return Err(format_err!(
+            "Option --group-by-peer can not be used with --format compact."
+        ));
+    }
+    if !has_enabled_extensions()? {
+        return Ok(());
+    }
+    if args.watch {
+        return watch::run(&args, no_color, &extension_args);
+    }
+    let totals = run_check(&args, no_color, &extension_args)?;
+    if args.require_all_pass && totals.has_non_pass() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Returns false and explains how to proceed if no extensions are enabled.
+fn has_enabled_extensions() -> Result<bool> {
+    let mut config = common::config::Config::load()?;
+    extension::manage::update_config(&mut config)?;
+    if !extension::manage::get_enabled_names(&config)?.is_empty() {
+        return Ok(true);
+    }
+
+    println!(
+        "No extensions are enabled. No dependencies were checked.\n\
+        Install one with: vouch extension add <url>\n\
+        Available extension documentation: {}",
+        EXTENSIONS_DOCUMENTATION_URL
+    );
+    let disabled_names = extension::manage::get_disabled_names(&config)?;
+    if !disabled_names.is_empty() {
+        println!(
+            "Known but disabled extensions: {names}\n\
+            Enable one with: vouch extension enable <name>",
+            names = disabled_names.into_iter().collect::<Vec<_>>().join(", ")
+        );
+    }
+    Ok(false)
 }
 
-pub fn run_command(args: &Arguments, extension_args: &Vec<String>) -> Result<()> {
+/// Run the check and print the report. Returns report totals.
+fn run_check(
+    args: &Arguments,
+    no_color: bool,
+    extension_args: &Vec<String>,
+) -> Result<report::Totals> {
     let mut config = common::config::Config::load()?;
     extension::manage::update_config(&mut config)?;
-    let config = config;
+
+    let working_directory = get_working_directory(args)?;
+    let project_config = common::config::project::ProjectConfig::load(&working_directory)?;
+    let config = match project_config
+        .as_ref()
+        .and_then(|project_config| project_config.settings.clone())
+    {
+        Some(settings) => common::config::Config::merge(config, settings)?,
+        None => config,
+    };
     let extension_names =
         extension::manage::handle_extension_names_arg(&args.extension_names, &config)?;
 
     let mut store = store::Store::from_root()?;
     let tx = store.get_transaction()?;
+    let color_enabled = is_color_enabled(no_color || args.no_color);
+    let license_options = report::LicenseOptions {
+        show: args.show_licenses,
+        allow_list: args
+            .license_allow_list
+            .as_ref()
+            .map(|licenses| licenses.iter().cloned().collect()),
+    };
+    let ignore_options = get_ignore_options(args, &project_config)?;
+    let mut baseline_options = baseline::Options {
+        baseline: match &args.baseline {
+            Some(path) => Some(baseline::Baseline::load(&path)?),
+            None => None,
+        },
+        update: args
+            .update_baseline
+            .clone()
+            .map(|path| (path, baseline::Baseline::default())),
+    };
 
-    match &args.package_name {
-        Some(package_name) => {
-            package::report(
-                &package_name,
-                &args.package_version.as_deref(),
-                &extension_names,
-                &extension_args,
-                &config,
-                &tx,
-            )?;
-        }
-        None => {
-            fs::report(&extension_names, &extension_args, &config, &tx)?;
+    let status_options = report::StatusOptions {
+        require_all_pass: args.require_all_pass,
+        min_reviews: args.min_reviews,
+        min_quality: args.min_quality,
+        check_osv: args.check_osv,
+    };
+    let report_options = report::Options {
+        extension_names: extension_names.clone(),
+        extension_args: extension_args.clone(),
+        license: license_options,
+        ignore: ignore_options,
+        status: status_options.clone(),
+        output: table::OutputOptions {
+            format: args.format,
+            verbose: args.verbose,
+            show_reviewed_by: args.show_reviewed_by,
+            group_by_peer: args.group_by_peer,
+            show_comments: args.detail,
+            show_source: args.show_lock_file,
+            review_freshness_days: config.core.review_freshness_days,
+            color_enabled,
+        },
+    };
+
+    if let (Some(old_package_version), Some(package_name), Some(package_version)) = (
+        &args.compare_version,
+        &args.package_name,
+        &args.package_version,
+    ) {
+        if compare::report(
+            &package_name,
+            &old_package_version,
+            &package_version,
+            &extension_names,
+            color_enabled,
+            &config,
+            &tx,
+        )? {
+            return Ok(report::Totals::default());
         }
+        println!("Reviews not found for both package versions. Showing standard report.");
     }
-    Ok(())
+
+    if let (true, Some(package_name), Some(package_version)) =
+        (args.workspace, &args.package_name, &args.package_version)
+    {
+        return workspace::report(
+            &package_name,
+            &package_version,
+            &status_options,
+            args.detail,
+            color_enabled,
+            config.core.review_freshness_days,
+            &tx,
+        );
+    }
+
+    if let Some(pull_request) = &args.github_pr {
+        return github::report(
+            &pull_request,
+            &report_options,
+            args.github_output,
+            &config,
+            &tx,
+        );
+    }
+
+    let totals = match &args.package_name {
+        Some(package_name) => package::report(
+            &package_name,
+            &args.package_version.as_deref(),
+            &report_options,
+            &mut baseline_options,
+            &config,
+            &tx,
+        )?,
+        None => fs::report(
+            &fs::Options {
+                working_directory,
+                auto_detect_extensions: args.extension_names.is_none(),
+                limit: args.limit,
+                show_only: args.show_only.clone(),
+            },
+            &report_options,
+            &mut baseline_options,
+            &config,
+            &tx,
+        )?,
+    };
+
+    if let Some(path) = baseline_options.write_update()? {
+        println!("Baseline updated: {}", path.display());
+    }
+    Ok(totals)
+}
+
+/// Returns the canonical project directory to check.
+fn get_working_directory(args: &Arguments) -> Result<std::path::PathBuf> {
+    let working_directory = match &args.working_directory {
+        Some(working_directory) => working_directory.clone(),
+        None => return Ok(std::env::current_dir()?),
+    };
+    if !working_directory.exists() {
+        return Err(format_err!(
+            "Working directory does not exist: {}",
+            working_directory.display()
+        ));
+    }
+    if !working_directory.is_dir() {
+        return Err(format_err!(
+            "Working directory is not a directory: {}",
+            working_directory.display()
+        ));
+    }
+    Ok(std::fs::canonicalize(&working_directory)?)
+}
+
+/// Returns ignore options from arguments and project config.
+fn get_ignore_options(
+    args: &Arguments,
+    project_config: &Option<common::config::project::ProjectConfig>,
+) -> Result<report::IgnoreOptions> {
+    let mut patterns = args.ignore.clone();
+    if let Some(project_config) = project_config {
+        patterns.extend(project_config.check.ignore.iter().cloned());
+    }
+
+    let patterns: Result<Vec<glob::Pattern>> = patterns
+        .iter()
+        .map(|pattern| {
+            glob::Pattern::new(&pattern)
+                .map_err(|error| format_err!("Invalid ignore pattern: {}\n{}", pattern, error))
+        })
+        .collect();
+    Ok(report::IgnoreOptions {
+        patterns: patterns?,
+        show: args.show_ignored,
+    })
+}
+
+fn parse_min_quality(value: &str) -> Result<f64> {
+    let min_quality: f64 = value
+        .parse()
+        .map_err(|_| format_err!("Failed to parse quality score: {}", value))?;
+    if !(0.0..=1.0).contains(&min_quality) {
+        return Err(format_err!(
+            "Quality score must be between 0.0 and 1.0: {}",
+            value
+        ));
+    }
+    Ok(min_quality)
+}
+
+/// Returns true if output should be colored.
+///
+/// Color is disabled by flag, by a non-empty NO_COLOR environment variable
+/// (see https://no-color.org), or if stdout is not a terminal.
+pub fn is_color_enabled(no_color: bool) -> bool {
+    let no_color_env = std::env::var_os("NO_COLOR").map_or(false, |value| !value.is_empty());
+    !no_color && !no_color_env && atty::is(atty::Stream::Stdout)
 }
@@ -4,6 +4,7 @@ use crate::common;
 use crate::common::StoreTransaction;
 use crate::extension;
 
+use super::baseline;
 use super::report;
 use super::table;
 
@@ -11,45 +12,39 @@ use super::table;
 pub fn report(
     package_name: &str,
     package_version: &Option<&str>,
-    extension_names: &std::collections::BTreeSet<String>,
-    extension_args: &Vec<String>,
+    report_options: &report::Options,
+    baseline_options: &mut baseline::Options,
     config: &common::config::Config,
     tx: &StoreTransaction,
-) -> Result<()> {
-    let extensions = extension::manage::get_enabled(&extension_names, &config)?;
+) -> Result<report::Totals> {
+    let extensions = extension::manage::get_enabled(&report_options.extension_names, &config)?;
+    let format = report_options.output.format;
 
     let mut dependencies_found = false;
+    let mut grand_totals = report::Totals::default();
     let all_extensions_results = extension::identify_package_dependencies(
         &package_name,
         &package_version,
         &extensions,
-        &extension_args,
+        &report_options.extension_args,
     )?;
 
-    let mut extensions_results = vec![];
-    for (extension, extension_all_dependencies) in
-        extensions.iter().zip(all_extensions_results.iter())
-    {
-        let all_dependencies = match extension_all_dependencies {
-            Ok(d) => d,
-            Err(error) => {
-                log::error!(
-                    "Extension {name} error: {error}",
-                    name = extension.name(),
-                    error = error
-                );
-                continue;
-            }
-        };
-        extensions_results.push((extension, all_dependencies));
-    }
-
-    let all_dependencies = extensions_results
+    let all_dependencies = all_extensions_results
         .iter()
-        .map(|(_ext, deps)| deps.clone())
+        .filter_map(|result| result.as_ref().ok())
         .flatten()
         .collect();
-    let official_reviews = crate::review::official::get(&all_dependencies, &config.core.api_key)?;
+    let official_reviews = match crate::review::official::get(
+        &all_dependencies,
+        &config.core.api_key,
+        &config.core.api_base_url,
+    ) {
+        Ok(official_reviews) => official_reviews,
+        Err(error) => {
+            log::warn!("Failed to retrieve official reviews: {}", error);
+            std::collections::HashMap::new()
+        }
+    };
 
     for (extension, extension_all_dependencies) in
         extensions.iter().zip(all_extensions_results.into_iter())
@@ -69,9 +64,18 @@ pub fn report(
 
         for (index, package_dependencies) in extension_all_package_dependencies.iter().enumerate() {
             dependencies_found |= !package_dependencies.dependencies.is_empty();
-            report_dependencies(&package_name, &package_dependencies, &tx)?;
+            let totals = report_dependencies(
+                &package_name,
+                &package_dependencies,
+                &extension,
+                &report_options,
+                baseline_options,
+                &official_reviews,
+                &tx,
+            )?;
+            grand_totals.extend(&totals);
             let is_last = index == extension_all_package_dependencies.len() - 1;
-            if !is_last {
+            if !is_last && format != table::Format::Minimal {
                 println!("");
             }
         }
@@ -79,15 +83,24 @@ pub fn report(
 
     if !dependencies_found {
         println!("No dependencies found.")
+    } else if format != table::Format::Full {
+        println!("{}", grand_totals.get_summary_line());
     }
-    Ok(())
+    Ok(grand_totals)
 }
 
 fn report_dependencies(
     package_name: &str,
     package_dependencies: &vouch_lib::extension::PackageDependencies,
+    extension: &Box<dyn vouch_lib::extension::Extension>,
+    report_options: &report::Options,
+    baseline_options: &mut baseline::Options,
+    official_reviews: &std::collections::HashMap<
+        crate::review::official::PackageKey,
+        crate::review::official::OfficialReview,
+    >,
     tx: &StoreTransaction,
-) -> Result<()> {
+) -> Result<report::Totals> {
     log::info!("Generating report for package dependencies.");
     let dependencies = &package_dependencies.dependencies;
 
@@ -98,6 +111,7 @@ fn report_dependencies(
             version: package_dependencies.package_version.clone(),
         },
         &package_dependencies.registry_host_name,
+        &report_options.status,
         &tx,
     )?;
     dependency_reports.push(target_package_dependency_report);
@@ -105,22 +119,60 @@ fn report_dependencies(
         let dependency_report = report::get_dependency_report(
             &dependency,
             &package_dependencies.registry_host_name,
+            &report_options.status,
             &tx,
         )?;
-        dependency_reports.push(dependency_report);
+        if let Some(dependency_report) = report_options.ignore.apply(dependency_report) {
+            dependency_reports.push(dependency_report);
+        }
     }
 
     log::info!("Number of dependencies found: {}", dependency_reports.len());
     if dependency_reports.is_empty() {
-        return Ok(());
+        return Ok(report::Totals::default());
     }
-
-    println!(
-        "Registry: {name}",
-        name = package_dependencies.registry_host_name
+    report::add_license_notes(&mut dependency_reports, &extension, &report_options.license)?;
+    report::add_official_review_notes(
+        &mut dependency_reports,
+        &package_dependencies.registry_host_name,
+        &official_reviews,
+    );
+    if report_options.output.verbose {
+        report::add_maintainer_notes(&mut dependency_reports, &extension)?;
+        report::add_download_stats_notes(&mut dependency_reports, &extension)?;
+    }
+    baseline_options.process(
+        &mut dependency_reports,
+        &package_dependencies.registry_host_name,
     );
 
-    let table = table::get(&dependency_reports, true)?;
+    let totals = report::Totals::from_reports(&dependency_reports);
+
+    // The first report describes the target package and is always shown.
+    let dependency_reports: Vec<_> = match report_options.output.format {
+        table::Format::Full => dependency_reports,
+        table::Format::Compact => dependency_reports
+            .into_iter()
+            .enumerate()
+            .filter(|(index, dependency_report)| *index == 0 || dependency_report.is_warn_or_fail())
+            .map(|(_index, dependency_report)| dependency_report)
+            .collect(),
+        table::Format::Minimal => return Ok(totals),
+    };
+
+    let table = table::get(
+        &dependency_reports,
+        &table::TableOptions {
+            first_row_separate: true,
+            show_source: false,
+            ..report_options
+                .output
+                .get_table_options(&package_dependencies.registry_host_name)
+        },
+    )?;
     table.printstd();
-    Ok(())
+    if report_options.output.format == table::Format::Full {
+        println!("{}", totals);
+    }
+    Ok(totals)
 }
@@ -1,8 +1,12 @@
 use anyhow::Result;
 
 use crate::common::StoreTransaction;
+use crate::osv;
+use crate::peer;
 use crate::review;
 
+use super::table;
+
 #[derive(Debug, Clone, Eq, Ord, PartialEq, PartialOrd)]
 pub struct DependencyReport {
     pub summary: review::Summary,
@@ -10,23 +14,102 @@ pub struct DependencyReport {
     pub version: Option<String>,
     pub review_count: Option<usize>,
     pub note: Option<String>,
+    pub last_reviewed: Option<chrono::NaiveDate>,
+    pub reviewed_by: Vec<String>,
+    pub total_line_count: Option<usize>,
+    pub quality_score: Option<review::QualityScore>,
+    pub suppression: Option<Suppression>,
+
+    /// Reviewing peer label, set if the report covers the reviews of a single peer.
+    pub peer: Option<String>,
+
+    /// Reports covering the reviews of each reviewing peer. The root peer is first.
+    pub peer_reports: Vec<DependencyReport>,
+
+    /// Comments of all reviews covered by the report.
+    pub comments: Vec<review::comment::Comment>,
+
+    /// Dependency specification file path relative to the working directory, if known.
+    pub source_file: Option<std::path::PathBuf>,
+}
+
+impl DependencyReport {
+    /// Returns true if the report is included in totals with a warn or fail summary.
+    pub fn is_warn_or_fail(&self) -> bool {
+        self.suppression.is_none()
+            && (self.summary == review::Summary::Warn || self.summary == review::Summary::Fail)
+    }
+}
+
+/// Reason for excluding a dependency report from totals.
+#[derive(Debug, Clone, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Suppression {
+    Ignored,
+    Baseline,
+}
+
+/// Options for determining dependency report summaries.
+#[derive(Debug, Default, Clone)]
+pub struct StatusOptions {
+    /// Report every dependency without a pass summary as a failure.
+    pub require_all_pass: bool,
+
+    /// Minimum number of reviews required for a dependency to pass.
+    pub min_reviews: usize,
+
+    /// Minimum review quality score. Reviews without a recorded score are not counted.
+    pub min_quality: Option<f64>,
+
+    /// Query the OSV database for known vulnerabilities. Affected dependencies do not pass.
+    pub check_osv: bool,
+}
+
+impl StatusOptions {
+    /// Returns the summary to report for a dependency which would otherwise not pass.
+    fn escalate(&self, summary: review::Summary) -> review::Summary {
+        match summary {
+            review::Summary::Todo | review::Summary::Warn if self.require_all_pass => {
+                review::Summary::Fail
+            }
+            _ => summary,
+        }
+    }
+
+    /// Returns true if the review meets the minimum quality score, if any.
+    fn is_sufficient_quality(&self, review: &review::Review) -> bool {
+        match (self.min_quality, review.quality_score) {
+            (None, _) => true,
+            (Some(min_quality), Some(quality_score)) => quality_score.0 >= min_quality,
+            (Some(_), None) => false,
+        }
+    }
 }
 
 /// Given a local project dependency, create a corresponding review report from known reviews.
 pub fn get_dependency_report(
     dependency: &vouch_lib::extension::Dependency,
     registry_host_name: &str,
+    status_options: &StatusOptions,
     tx: &StoreTransaction,
 ) -> Result<DependencyReport> {
     let package_version = match &dependency.version {
         Ok(version) => version.clone(),
         Err(error) => {
             return Ok(DependencyReport {
-                summary: review::Summary::Warn,
+                summary: status_options.escalate(get_version_error_summary(&error)),
                 name: dependency.name.clone(),
                 version: None,
                 review_count: None,
                 note: Some(error.message()),
+                last_reviewed: None,
+                reviewed_by: vec![],
+                total_line_count: None,
+                quality_score: None,
+                suppression: None,
+                peer: None,
+                peer_reports: vec![],
+                comments: vec![],
+                source_file: None,
             });
         }
     };
@@ -40,31 +123,271 @@ pub fn get_dependency_report(
         },
         &tx,
     )?;
+    let reviews: Vec<_> = add_file_fields(reviews, &tx)?
+        .into_iter()
+        .filter(|review| status_options.is_sufficient_quality(&review))
+        .collect();
 
     if reviews.is_empty() {
         // Report no reviews found for dependency.
-        return Ok(DependencyReport {
-            summary: review::Summary::Todo,
+        let mut dependency_report = DependencyReport {
+            summary: status_options.escalate(review::Summary::Todo),
             name: dependency.name.clone(),
             version: Some(package_version.clone()),
             review_count: Some(0),
             note: None,
-        });
+            last_reviewed: None,
+            reviewed_by: vec![],
+            total_line_count: None,
+            quality_score: None,
+            suppression: None,
+            peer: None,
+            peer_reports: vec![],
+            comments: vec![],
+            source_file: None,
+        };
+        add_vulnerability_notes(&mut dependency_report, &registry_host_name, &status_options)?;
+        return Ok(dependency_report);
     }
 
+    let mut dependency_report = get_reviews_report(
+        &dependency.name,
+        &package_version,
+        &reviews,
+        &status_options,
+        &tx,
+    )?;
+    dependency_report.peer_reports = get_peer_reports(
+        &dependency.name,
+        &package_version,
+        &reviews,
+        &status_options,
+        &tx,
+    )?;
+    add_vulnerability_notes(&mut dependency_report, &registry_host_name, &status_options)?;
+    Ok(dependency_report)
+}
+
+/// Returns a report for a review which has not yet been committed to the index.
+pub fn get_ongoing_review_report(
+    review: &review::Review,
+    status_options: &StatusOptions,
+) -> Result<DependencyReport> {
+    let analysis = review::analyse(&review)?;
+    let summary = status_options.escalate(review::get_summary(&review)?);
+    Ok(DependencyReport {
+        summary,
+        name: review.package.name.clone(),
+        version: Some(review.package.version.clone()),
+        review_count: Some(1),
+        note: Some(format!(
+            "ongoing review: {} comments ({} fail, {} warn)",
+            review.comments.len(),
+            analysis.count_fail_comments,
+            analysis.count_warn_comments
+        )),
+        last_reviewed: None,
+        reviewed_by: vec![review.peer.alias.clone()],
+        total_line_count: review.total_line_count,
+        quality_score: review.quality_score,
+        suppression: None,
+        peer: None,
+        peer_reports: vec![],
+        comments: review.comments.iter().cloned().collect(),
+        source_file: None,
+    })
+}
+
+/// Add known vulnerability notes to a dependency report and its peer reports, if enabled.
+///
+/// Reports for affected dependencies are escalated to at least warn, regardless of reviews.
+fn add_vulnerability_notes(
+    dependency_report: &mut DependencyReport,
+    registry_host_name: &str,
+    status_options: &StatusOptions,
+) -> Result<()> {
+    if !status_options.check_osv {
+        return Ok(());
+    }
+    let package_version = match &dependency_report.version {
+        Some(version) => version,
+        None => return Ok(()),
+    };
+    let vulnerabilities = osv::query(
+        &dependency_report.name,
+        &package_version,
+        &registry_host_name,
+    )?;
+    if vulnerabilities.is_empty() {
+        return Ok(());
+    }
+
+    let note_parts: Vec<_> = vulnerabilities
+        .iter()
+        .map(|vulnerability| vulnerability.get_label())
+        .collect();
+    for peer_report in &mut dependency_report.peer_reports {
+        apply_vulnerability_notes(peer_report, &note_parts, &status_options);
+    }
+    apply_vulnerability_notes(dependency_report, &note_parts, &status_options);
+    Ok(())
+}
+
+/// Append vulnerability notes to a dependency report and escalate its summary to at least warn.
+fn apply_vulnerability_notes(
+    dependency_report: &mut DependencyReport,
+    note_parts: &Vec<String>,
+    status_options: &StatusOptions,
+) {
+    append_note(dependency_report, note_parts.clone());
+    if dependency_report.summary != review::Summary::Fail {
+        dependency_report.summary = status_options.escalate(review::Summary::Warn);
+    }
+}
+
+/// Returns a report summarising the given reviews of a package version.
+fn get_reviews_report(
+    package_name: &str,
+    package_version: &str,
+    reviews: &Vec<review::Review>,
+    status_options: &StatusOptions,
+    tx: &StoreTransaction,
+) -> Result<DependencyReport> {
     let stats = get_dependency_stats(&reviews)?;
-    let status = get_dependency_status(&stats)?;
+    let status = get_dependency_status(&stats, &status_options)?;
     let note = get_dependency_note(&stats)?;
+    let last_reviewed = get_last_reviewed_date(&reviews, &tx)?;
+    // Reviews of the same package version are expected to record the same line count.
+    let total_line_count = reviews
+        .iter()
+        .filter_map(|review| review.total_line_count)
+        .max();
+    let quality_score = reviews
+        .iter()
+        .filter_map(|review| review.quality_score)
+        .max();
+    // Identical comments are shared between reviews.
+    let comments: std::collections::BTreeSet<_> = reviews
+        .iter()
+        .flat_map(|review| review.comments.iter().cloned())
+        .collect();
 
     Ok(DependencyReport {
         summary: status,
-        name: dependency.name.clone(),
-        version: Some(package_version.clone()),
+        name: package_name.to_string(),
+        version: Some(package_version.to_string()),
         review_count: Some(reviews.len()),
         note: Some(note),
+        last_reviewed,
+        reviewed_by: get_reviewed_by(&reviews),
+        total_line_count,
+        quality_score,
+        suppression: None,
+        peer: None,
+        peer_reports: vec![],
+        comments: comments.into_iter().collect(),
+        source_file: None,
     })
 }
 
+/// Returns one report per reviewing peer. The root peer report is first.
+///
+/// The minimum review count applies to a dependency as a whole, not to individual peers.
+fn get_peer_reports(
+    package_name: &str,
+    package_version: &str,
+    reviews: &Vec<review::Review>,
+    status_options: &StatusOptions,
+    tx: &StoreTransaction,
+) -> Result<Vec<DependencyReport>> {
+    let mut peer_reviews = std::collections::BTreeMap::<_, Vec<_>>::new();
+    for review in reviews {
+        peer_reviews
+            .entry(review.peer.clone())
+            .or_default()
+            .push(review.clone());
+    }
+
+    let status_options = StatusOptions {
+        min_reviews: 0,
+        ..status_options.clone()
+    };
+    let mut peer_reports = vec![];
+    for (peer, reviews) in peer_reviews {
+        let mut peer_report = get_reviews_report(
+            &package_name,
+            &package_version,
+            &reviews,
+            &status_options,
+            &tx,
+        )?;
+        peer_report.peer = Some(get_peer_label(&peer));
+        peer_reports.push(peer_report);
+    }
+    Ok(peer_reports)
+}
+
+/// Returns the report summary for a dependency without an identifiable version.
+///
+/// Git and local path dependencies are not expected to have registry reviews.
+fn get_version_error_summary(error: &vouch_lib::extension::VersionError) -> review::Summary {
+    match error {
+        vouch_lib::extension::VersionError::GitDependency { .. }
+        | vouch_lib::extension::VersionError::LocalPath { .. } => review::Summary::Info,
+        vouch_lib::extension::VersionError::Unpinned { .. }
+        | vouch_lib::extension::VersionError::Unknown(_) => review::Summary::Warn,
+    }
+}
+
+/// Returns the most recent review update date.
+fn get_last_reviewed_date(
+    reviews: &Vec<review::Review>,
+    tx: &StoreTransaction,
+) -> Result<Option<chrono::NaiveDate>> {
+    let mut last_reviewed = None;
+    for review in reviews {
+        let peer_branch = peer::index::get_peer_branch(&review.peer, &tx)?;
+        let date = review::fs::get_last_updated_date(&review, &peer_branch)?;
+        last_reviewed = std::cmp::max(last_reviewed, date);
+    }
+    Ok(last_reviewed)
+}
+
+/// Returns the reviews with fields which are only recorded in review files.
+fn add_file_fields(
+    reviews: Vec<review::Review>,
+    tx: &StoreTransaction,
+) -> Result<Vec<review::Review>> {
+    let mut updated_reviews = Vec::new();
+    for mut review in reviews {
+        let peer_branch = peer::index::get_peer_branch(&review.peer, &tx)?;
+        if let Some(file_review) = review::fs::get_file_review(&review, &peer_branch)? {
+            review.total_line_count = file_review.total_line_count;
+            review.quality_score = file_review.quality_score;
+        }
+        updated_reviews.push(review);
+    }
+    Ok(updated_reviews)
+}
+
+/// Returns sorted unique aliases of reviewing peers. The root peer is shown as "(you)".
+fn get_reviewed_by(reviews: &Vec<review::Review>) -> Vec<String> {
+    let aliases: std::collections::BTreeSet<_> = reviews
+        .iter()
+        .map(|review| get_peer_label(&review.peer))
+        .collect();
+    aliases.into_iter().collect()
+}
+
+/// Returns the peer alias. The root peer is shown as "(you)".
+fn get_peer_label(peer: &peer::Peer) -> String {
+    if peer.is_root() {
+        "(you)".to_string()
+    } else {
+        peer.alias.clone()
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 struct DependencyStats {
     pub total_review_count: usize,
@@ -84,12 +407,18 @@ fn get_dependency_stats(reviews: &Vec<review::Review>) -> Result<DependencyStats
     Ok(stats)
 }
 
-fn get_dependency_status(stats: &DependencyStats) -> Result<review::Summary> {
+fn get_dependency_status(
+    stats: &DependencyStats,
+    status_options: &StatusOptions,
+) -> Result<review::Summary> {
     if stats.count_fail_comments > 0 {
         return Ok(review::Summary::Fail);
     }
-    if stats.total_review_count == 0 || stats.count_warn_comments > 0 {
-        return Ok(review::Summary::Warn);
+    if stats.total_review_count == 0
+        || stats.total_review_count < status_options.min_reviews
+        || stats.count_warn_comments > 0
+    {
+        return Ok(status_options.escalate(review::Summary::Warn));
     }
     Ok(review::Summary::Pass)
 }
@@ -106,3 +435,312 @@ fn get_dependency_note(stats: &DependencyStats) -> Result<String> {
 
     Ok(note_parts.join("; "))
 }
+
+/// Options for omitting packages from reports.
+#[derive(Debug, Default, Clone)]
+pub struct IgnoreOptions {
+    /// Package name patterns to ignore.
+    pub patterns: Vec<glob::Pattern>,
+
+    /// Include ignored packages in reports, marked as ignored.
+    pub show: bool,
+}
+
+impl IgnoreOptions {
+    /// Returns None if the report should be omitted.
+    pub fn apply(&self, mut dependency_report: DependencyReport) -> Option<DependencyReport> {
+        if !self
+            .patterns
+            .iter()
+            .any(|pattern| pattern.matches(&dependency_report.name))
+        {
+            return Some(dependency_report);
+        }
+        if !self.show {
+            return None;
+        }
+        dependency_report.suppression = Some(Suppression::Ignored);
+        Some(dependency_report)
+    }
+}
+
+/// Dependency license reporting options.
+#[derive(Debug, Default, Clone)]
+pub struct LicenseOptions {
+    /// Show licenses in report notes.
+    pub show: bool,
+
+    /// Warn on licenses outside of this set.
+    pub allow_list: Option<std::collections::BTreeSet<String>>,
+}
+
+impl LicenseOptions {
+    fn is_enabled(&self) -> bool {
+        self.show || self.allow_list.is_some()
+    }
+}
+
+/// Options shared by the dependency reports of the check command.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// Names of the extensions used to identify dependencies.
+    pub extension_names: std::collections::BTreeSet<String>,
+
+    /// Arguments passed through to extensions.
+    pub extension_args: Vec<String>,
+
+    pub license: LicenseOptions,
+    pub ignore: IgnoreOptions,
+    pub status: StatusOptions,
+    pub output: table::OutputOptions,
+}
+
+/// Add package license notes to dependency reports using the given extension.
+///
+/// Reports for packages with a known license outside of the allow list are escalated to warn.
+/// Unknown licenses are noted without escalation.
+pub fn add_license_notes(
+    dependency_reports: &mut Vec<DependencyReport>,
+    extension: &Box<dyn vouch_lib::extension::Extension>,
+    license_options: &LicenseOptions,
+) -> Result<()> {
+    if !license_options.is_enabled() {
+        return Ok(());
+    }
+
+    for dependency_report in dependency_reports {
+        let package_version = match &dependency_report.version {
+            Some(version) => version,
+            None => continue,
+        };
+        let license = match extension.package_license(&dependency_report.name, &package_version) {
+            Ok(license) => license,
+            Err(error) => {
+                log::warn!(
+                    "Failed to get license for package {name}: {error}",
+                    name = dependency_report.name,
+                    error = error
+                );
+                None
+            }
+        };
+
+        let mut note_parts = Vec::<_>::new();
+        if license_options.show {
+            note_parts.push(format!(
+                "license: {}",
+                license.as_deref().unwrap_or("unknown")
+            ));
+        }
+        if let Some(allow_list) = &license_options.allow_list {
+            match &license {
+                Some(license) if !allow_list.contains(license) => {
+                    note_parts.push(format!("license not allowed: {}", license));
+                    if dependency_report.summary != review::Summary::Fail {
+                        dependency_report.summary = review::Summary::Warn;
+                    }
+                }
+                // The extension may not support license queries. Not escalated.
+                None if !license_options.show => note_parts.push("unknown license".to_string()),
+                _ => {}
+            }
+        }
+
+        append_note(dependency_report, note_parts);
+    }
+    Ok(())
+}
+
+/// Add package maintainer notes to dependency reports using the given extension.
+pub fn add_maintainer_notes(
+    dependency_reports: &mut Vec<DependencyReport>,
+    extension: &Box<dyn vouch_lib::extension::Extension>,
+) -> Result<()> {
+    for dependency_report in dependency_reports {
+        let package_version = match &dependency_report.version {
+            Some(version) => version,
+            None => continue,
+        };
+        let registries_metadata = match extension
+            .registries_package_metadata(&dependency_report.name, &Some(&package_version))
+        {
+            Ok(registries_metadata) => registries_metadata,
+            Err(error) => {
+                log::warn!(
+                    "Failed to get registry metadata for package {name}: {error}",
+                    name = dependency_report.name,
+                    error = error
+                );
+                continue;
+            }
+        };
+
+        let maintainers = registries_metadata
+            .iter()
+            .find(|registry_metadata| registry_metadata.is_primary)
+            .map(|registry_metadata| registry_metadata.maintainers.clone())
+            .unwrap_or_default();
+        if maintainers.is_empty() {
+            continue;
+        }
+        append_note(
+            dependency_report,
+            vec![format!("maintainers: {}", maintainers.join(", "))],
+        );
+    }
+    Ok(())
+}
+
+/// Add package download count notes to dependency reports using the given extension.
+pub fn add_download_stats_notes(
+    dependency_reports: &mut Vec<DependencyReport>,
+    extension: &Box<dyn vouch_lib::extension::Extension>,
+) -> Result<()> {
+    for dependency_report in dependency_reports {
+        let package_version = match &dependency_report.version {
+            Some(version) => version,
+            None => continue,
+        };
+        let download_stats =
+            match extension.package_download_stats(&dependency_report.name, &package_version) {
+                Ok(Some(download_stats)) => download_stats,
+                Ok(None) => continue,
+                Err(error) => {
+                    log::warn!(
+                        "Failed to get download stats for package {name}: {error}",
+                        name = dependency_report.name,
+                        error = error
+                    );
+                    continue;
+                }
+            };
+
+        let mut note_parts = Vec::<_>::new();
+        if let Some(weekly) = download_stats.weekly {
+            note_parts.push(format!("weekly downloads: {}", weekly));
+        }
+        if let Some(total) = download_stats.total {
+            note_parts.push(format!("total downloads: {}", total));
+        }
+        if note_parts.is_empty() {
+            continue;
+        }
+        append_note(dependency_report, note_parts);
+    }
+    Ok(())
+}
+
+/// Add official review notes to dependency reports.
+///
+/// Official reviews are keyed on registry host name, package name and package version.
+pub fn add_official_review_notes(
+    dependency_reports: &mut Vec<DependencyReport>,
+    registry_host_name: &str,
+    official_reviews: &std::collections::HashMap<
+        review::official::PackageKey,
+        review::official::OfficialReview,
+    >,
+) {
+    for dependency_report in dependency_reports {
+        let package_version = match &dependency_report.version {
+            Some(version) => version,
+            None => continue,
+        };
+        let key = (
+            registry_host_name.to_string(),
+            dependency_report.name.clone(),
+            package_version.clone(),
+        );
+        let official_review = match official_reviews.get(&key) {
+            Some(official_review) => official_review,
+            None => continue,
+        };
+
+        let mut note = format!(
+            "official review: {summary} ({count} reviewers)",
+            summary = official_review.summary,
+            count = official_review.reviewer_count
+        );
+        if let Some(official_note) = &official_review.note {
+            note = format!("{}: {}", note, official_note);
+        }
+        append_note(dependency_report, vec![note]);
+    }
+}
+
+/// Append note parts to any existing dependency report note.
+fn append_note(dependency_report: &mut DependencyReport, mut note_parts: Vec<String>) {
+    if let Some(note) = &dependency_report.note {
+        if !note.is_empty() {
+            note_parts.insert(0, note.clone());
+        }
+    }
+    dependency_report.note = Some(note_parts.join("; "));
+}
+
+/// Dependency report counts per review summary.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct Totals {
+    pub pass: usize,
+    pub warn: usize,
+    pub fail: usize,
+    pub info: usize,
+    pub todo: usize,
+}
+
+impl Totals {
+    pub fn from_reports(dependency_reports: &Vec<DependencyReport>) -> Self {
+        let mut totals = Self::default();
+        for dependency_report in dependency_reports {
+            if dependency_report.suppression.is_some() {
+                continue;
+            }
+            match dependency_report.summary {
+                review::Summary::Pass => totals.pass += 1,
+                review::Summary::Warn => totals.warn += 1,
+                review::Summary::Fail => totals.fail += 1,
+                review::Summary::Info => totals.info += 1,
+                review::Summary::Todo => totals.todo += 1,
+            }
+        }
+        totals
+    }
+
+    /// Returns a one line summary of all counted packages.
+    pub fn get_summary_line(&self) -> String {
+        format!(
+            "Checked {count} packages: {pass} pass, {warn} warn, {fail} fail",
+            count = self.pass + self.warn + self.fail + self.info + self.todo,
+            pass = self.pass,
+            warn = self.warn,
+            fail = self.fail
+        )
+    }
+
+    /// Returns true if any counted package does not have a pass or info summary.
+    pub fn has_non_pass(&self) -> bool {
+        self.warn + self.fail + self.todo > 0
+    }
+
+    pub fn extend(&mut self, other: &Self) {
+        self.pass += other.pass;
+        self.warn += other.warn;
+        self.fail += other.fail;
+        self.info += other.info;
+        self.todo += other.todo;
+    }
+}
+
+impl std::fmt::Display for Totals {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "pass: {pass}, warn: {warn}, fail: {fail}, info: {info}, todo: {todo}",
+            pass = self.pass,
+            warn = self.warn,
+            fail = self.fail,
+            info = self.info,
+            todo = self.todo
+        )
+    }
+}
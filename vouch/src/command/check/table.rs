@@ -1,10 +1,142 @@
 use super::report;
 use crate::review;
 use anyhow::Result;
-use prettytable::{self, cell};
+use prettytable;
 
-fn get_row(dependency_report: &report::DependencyReport) -> prettytable::Row {
-    let summary: prettytable::Cell = dependency_report.summary.clone().into();
+/// Check report output format.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Format {
+    /// Table of all packages.
+    Full,
+
+    /// Table of packages with warn or fail summaries only, followed by a summary line.
+    Compact,
+
+    /// Summary line only.
+    Minimal,
+}
+
+impl std::str::FromStr for Format {
+    type Err = anyhow::Error;
+    fn from_str(input: &str) -> Result<Format, Self::Err> {
+        match input {
+            "full" => Ok(Format::Full),
+            "compact" => Ok(Format::Compact),
+            "minimal" => Ok(Format::Minimal),
+            _ => Err(anyhow::format_err!(
+                "Failed to parse output format from string: {}",
+                input
+            )),
+        }
+    }
+}
+
+/// Check report output options.
+#[derive(Debug, Clone)]
+pub struct OutputOptions {
+    pub format: Format,
+
+    /// Include maintainer and download notes, and the LOC and quality columns.
+    pub verbose: bool,
+
+    pub show_reviewed_by: bool,
+
+    /// Show one row per reviewing peer.
+    pub group_by_peer: bool,
+
+    /// Show the review comments of non-passing dependencies.
+    pub show_comments: bool,
+
+    /// Show the dependency specification file of each dependency.
+    pub show_source: bool,
+
+    /// Reviews older than this number of days are highlighted as stale.
+    pub review_freshness_days: u32,
+
+    pub color_enabled: bool,
+}
+
+impl OutputOptions {
+    /// Returns the options for a table headed by the given registry label.
+    pub fn get_table_options<'a>(&self, registry_label: &'a str) -> TableOptions<'a> {
+        TableOptions {
+            first_row_separate: false,
+            registry_label: Some(registry_label),
+            review_freshness_days: self.review_freshness_days,
+            show_reviewed_by: self.show_reviewed_by,
+            show_details: self.verbose,
+            group_by_peer: self.group_by_peer,
+            show_comments: self.show_comments,
+            show_source: self.show_source,
+            color_enabled: self.color_enabled,
+        }
+    }
+}
+
+/// Dependency reports table options.
+#[derive(Debug, Default, Clone)]
+pub struct TableOptions<'a> {
+    /// Separate the first row from the rest of the table with an empty row.
+    pub first_row_separate: bool,
+
+    /// Bold header row above the column titles.
+    pub registry_label: Option<&'a str>,
+
+    /// Last reviewed dates older than this number of days are colored.
+    pub review_freshness_days: u32,
+
+    pub show_reviewed_by: bool,
+
+    /// Include the LOC and quality columns.
+    pub show_details: bool,
+
+    /// Include a peer column and show one row per reviewing peer.
+    pub group_by_peer: bool,
+
+    /// List the review comments of non-passing dependencies below their rows.
+    pub show_comments: bool,
+
+    /// Include a column giving the dependency specification file of each dependency.
+    pub show_source: bool,
+
+    pub color_enabled: bool,
+}
+
+/// Returns the table rows for a dependency report.
+///
+/// If grouping by peer, one row is given per reviewing peer. Unreviewed dependencies have a
+/// single row.
+fn get_rows(
+    dependency_report: &report::DependencyReport,
+    options: &TableOptions,
+) -> Vec<prettytable::Row> {
+    if !options.group_by_peer || dependency_report.peer_reports.is_empty() {
+        return vec![get_row(&dependency_report, &options)];
+    }
+    dependency_report
+        .peer_reports
+        .iter()
+        .map(|peer_report| {
+            let peer_report = report::DependencyReport {
+                suppression: dependency_report.suppression.clone(),
+                source_file: dependency_report.source_file.clone(),
+                ..peer_report.clone()
+            };
+            get_row(&peer_report, &options)
+        })
+        .collect()
+}
+
+fn get_row(
+    dependency_report: &report::DependencyReport,
+    options: &TableOptions,
+) -> prettytable::Row {
+    let color_enabled = options.color_enabled;
+    let summary = match &dependency_report.suppression {
+        Some(report::Suppression::Ignored) => get_suppressed_cell(" SKIP ", color_enabled),
+        Some(report::Suppression::Baseline) => get_suppressed_cell(" BASELINE ", color_enabled),
+        None => get_summary_cell(&dependency_report.summary, color_enabled),
+    };
     let package_version = match &dependency_report.version {
         Some(v) => v.as_str(),
         None => "",
@@ -13,52 +145,197 @@ fn get_row(dependency_report: &report::DependencyReport) -> prettytable::Row {
         Some(v) => v.to_string(),
         None => "".to_string(),
     };
-    let note = get_note_cell(&dependency_report);
-    prettytable::Row::new(vec![
+    let note = get_note_cell(&dependency_report, color_enabled);
+    let last_reviewed = get_last_reviewed_cell(
+        &dependency_report,
+        options.review_freshness_days,
+        color_enabled,
+    );
+    let mut cells = vec![
         summary,
         prettytable::Cell::new_align(
             &dependency_report.name,
             prettytable::format::Alignment::LEFT,
         ),
         prettytable::Cell::new_align(&package_version, prettytable::format::Alignment::RIGHT),
-        prettytable::Cell::new_align(&review_count, prettytable::format::Alignment::RIGHT),
-        note,
-    ])
+    ];
+    if options.group_by_peer {
+        cells.push(prettytable::Cell::new_align(
+            dependency_report.peer.as_deref().unwrap_or(""),
+            prettytable::format::Alignment::LEFT,
+        ));
+    }
+    if options.show_source {
+        let source_file = match &dependency_report.source_file {
+            Some(v) => v.display().to_string(),
+            None => "".to_string(),
+        };
+        cells.push(prettytable::Cell::new_align(
+            &source_file,
+            prettytable::format::Alignment::LEFT,
+        ));
+    }
+    cells.push(prettytable::Cell::new_align(
+        &review_count,
+        prettytable::format::Alignment::RIGHT,
+    ));
+    cells.push(last_reviewed);
+    if options.show_reviewed_by {
+        cells.push(prettytable::Cell::new_align(
+            &dependency_report.reviewed_by.join(", "),
+            prettytable::format::Alignment::LEFT,
+        ));
+    }
+    if options.show_details {
+        let line_count = match dependency_report.total_line_count {
+            Some(v) => v.to_string(),
+            None => "".to_string(),
+        };
+        cells.push(prettytable::Cell::new_align(
+            &line_count,
+            prettytable::format::Alignment::RIGHT,
+        ));
+        let quality_score = match dependency_report.quality_score {
+            Some(v) => v.to_string(),
+            None => "".to_string(),
+        };
+        cells.push(prettytable::Cell::new_align(
+            &quality_score,
+            prettytable::format::Alignment::RIGHT,
+        ));
+    }
+    cells.push(note);
+    prettytable::Row::new(cells)
+}
+
+/// Returns a row which lists review comments below a dependency's rows.
+///
+/// Returns None for passing dependencies and dependencies without review comments.
+fn get_comments_row(
+    dependency_report: &report::DependencyReport,
+    column_count: usize,
+) -> Option<prettytable::Row> {
+    if dependency_report.summary == review::Summary::Pass || dependency_report.comments.is_empty() {
+        return None;
+    }
+    let lines: Vec<_> = dependency_report
+        .comments
+        .iter()
+        .map(|comment| {
+            let location = match &comment.selection {
+                Some(selection) => format!(
+                    "{}:{}-{}",
+                    comment.path.display(),
+                    selection.start.line + 1,
+                    selection.end.line + 1
+                ),
+                None => comment.path.display().to_string(),
+            };
+            format!(
+                "    {} {}\n        {}",
+                comment.summary.to_string().to_uppercase(),
+                location,
+                comment.message.replace('\n', "\n        ")
+            )
+        })
+        .collect();
+    Some(prettytable::Row::new(vec![prettytable::Cell::new(
+        &lines.join("\n"),
+    )
+    .with_hspan(column_count)]))
 }
 
 /// Generates and returns a table from a given vector of dependency review reports.
+///
+/// Optional columns and rows are only included as requested by the given options.
+/// Color styling is only applied if color is enabled.
 pub fn get(
     dependency_reports: &Vec<report::DependencyReport>,
-    first_row_separate: bool,
+    options: &TableOptions,
 ) -> Result<prettytable::Table> {
     let mut table = prettytable::Table::new();
-    table.set_titles(prettytable::row![c => "  ", "name", "version", "reviews", "notes"]);
+    let mut column_titles = vec!["  ", "name", "version"];
+    if options.group_by_peer {
+        column_titles.push("peer");
+    }
+    if options.show_source {
+        column_titles.push("source");
+    }
+    column_titles.extend(vec!["reviews", "reviewed"]);
+    if options.show_reviewed_by {
+        column_titles.push("reviewed-by");
+    }
+    if options.show_details {
+        column_titles.push("LOC");
+        column_titles.push("quality");
+    }
+    column_titles.push("notes");
+    let column_titles = prettytable::Row::new(
+        column_titles
+            .iter()
+            .map(|title| {
+                prettytable::Cell::new_align(title, prettytable::format::Alignment::CENTER)
+            })
+            .collect(),
+    );
+    let column_count = column_titles.len();
+    match options.registry_label {
+        Some(registry_label) => {
+            table.set_titles(prettytable::Row::new(vec![prettytable::Cell::new(
+                registry_label,
+            )
+            .with_style(prettytable::Attr::Bold)
+            .with_hspan(column_titles.len())]));
+            table.add_row(column_titles);
+        }
+        None => {
+            table.set_titles(column_titles);
+        }
+    }
     table.set_format(*prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
 
     let mut dependency_reports_iter = dependency_reports.iter();
-    if first_row_separate {
+    if options.first_row_separate {
         if let Some(dependency_report) = dependency_reports_iter.next() {
-            let row = get_row(&dependency_report);
-            table.add_row(row);
-            table.add_row(prettytable::row![c => "  ", "", "", "", ""]);
+            for row in get_rows(&dependency_report, &options) {
+                table.add_row(row);
+            }
+            if options.show_comments {
+                if let Some(row) = get_comments_row(&dependency_report, column_count) {
+                    table.add_row(row);
+                }
+            }
+            table.add_row(prettytable::Row::new(vec![
+                prettytable::Cell::new("");
+                column_count
+            ]));
         }
     }
 
     for dependency_report in dependency_reports_iter {
-        let row = get_row(&dependency_report);
-        table.add_row(row);
+        for row in get_rows(&dependency_report, &options) {
+            table.add_row(row);
+        }
+        if options.show_comments {
+            if let Some(row) = get_comments_row(&dependency_report, column_count) {
+                table.add_row(row);
+            }
+        }
     }
     Ok(table)
 }
 
-fn get_note_cell(dependency_report: &report::DependencyReport) -> prettytable::Cell {
+fn get_note_cell(
+    dependency_report: &report::DependencyReport,
+    color_enabled: bool,
+) -> prettytable::Cell {
     let note = match &dependency_report.note {
         Some(v) => v.as_str(),
         None => "",
     };
     let mut note = prettytable::Cell::new_align(&note, prettytable::format::Alignment::LEFT);
 
-    if dependency_report.summary == review::Summary::Fail {
+    if color_enabled && dependency_report.summary == review::Summary::Fail {
         note = note
             .with_style(prettytable::Attr::BackgroundColor(
                 prettytable::color::BRIGHT_RED,
@@ -70,30 +347,79 @@ fn get_note_cell(dependency_report: &report::DependencyReport) -> prettytable::C
     note
 }
 
-impl From<review::Summary> for prettytable::Cell {
-    fn from(summary: review::Summary) -> Self {
-        let label = match summary {
-            review::Summary::Todo => "      ",
-            review::Summary::Pass => " PASS ",
-            review::Summary::Warn => " WARN ",
-            review::Summary::Fail => " FAIL ",
-        };
+/// Returns the last reviewed date cell. Stale review dates are colored.
+///
+/// Dates older than the freshness period are yellow. Dates older than twice the period are red.
+fn get_last_reviewed_cell(
+    dependency_report: &report::DependencyReport,
+    review_freshness_days: u32,
+    color_enabled: bool,
+) -> prettytable::Cell {
+    let last_reviewed = match &dependency_report.last_reviewed {
+        Some(last_reviewed) => last_reviewed,
+        None => return prettytable::Cell::new(""),
+    };
+    let cell = prettytable::Cell::new_align(
+        &last_reviewed.to_string(),
+        prettytable::format::Alignment::RIGHT,
+    );
+    if !color_enabled {
+        return cell;
+    }
 
-        let background_color = match summary {
-            review::Summary::Todo => None,
-            review::Summary::Pass => Some(prettytable::color::BRIGHT_GREEN),
-            review::Summary::Warn => Some(prettytable::color::YELLOW),
-            review::Summary::Fail => Some(prettytable::color::BRIGHT_RED),
-        };
+    let age = chrono::Local::now().naive_local().date() - *last_reviewed;
+    let freshness_period = chrono::Duration::days(review_freshness_days.into());
+    if age > freshness_period * 2 {
+        cell.with_style(prettytable::Attr::ForegroundColor(
+            prettytable::color::BRIGHT_RED,
+        ))
+    } else if age > freshness_period {
+        cell.with_style(prettytable::Attr::ForegroundColor(
+            prettytable::color::YELLOW,
+        ))
+    } else {
+        cell
+    }
+}
 
-        if let Some(background_color) = background_color {
+fn get_suppressed_cell(label: &str, color_enabled: bool) -> prettytable::Cell {
+    let cell = prettytable::Cell::new_align(label, prettytable::format::Alignment::CENTER);
+    if !color_enabled {
+        return cell;
+    }
+    cell.with_style(prettytable::Attr::BackgroundColor(
+        prettytable::color::BRIGHT_BLACK,
+    ))
+    .with_style(prettytable::Attr::ForegroundColor(
+        prettytable::color::WHITE,
+    ))
+}
+
+pub fn get_summary_cell(summary: &review::Summary, color_enabled: bool) -> prettytable::Cell {
+    let label = match summary {
+        review::Summary::Todo => "      ",
+        review::Summary::Pass => " PASS ",
+        review::Summary::Info => " INFO ",
+        review::Summary::Warn => " WARN ",
+        review::Summary::Fail => " FAIL ",
+    };
+
+    let background_color = match summary {
+        review::Summary::Todo => None,
+        review::Summary::Pass => Some(prettytable::color::BRIGHT_GREEN),
+        review::Summary::Info => Some(prettytable::color::BRIGHT_BLUE),
+        review::Summary::Warn => Some(prettytable::color::YELLOW),
+        review::Summary::Fail => Some(prettytable::color::BRIGHT_RED),
+    };
+
+    match background_color {
+        Some(background_color) if color_enabled => {
             prettytable::Cell::new_align(label, prettytable::format::Alignment::CENTER)
                 .with_style(prettytable::Attr::BackgroundColor(background_color))
                 .with_style(prettytable::Attr::ForegroundColor(
                     prettytable::color::BLACK,
                 ))
-        } else {
-            prettytable::Cell::new_align(label, prettytable::format::Alignment::CENTER)
         }
+        _ => prettytable::Cell::new_align(label, prettytable::format::Alignment::CENTER),
     }
 }
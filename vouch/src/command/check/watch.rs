@@ -0,0 +1,142 @@
+use anyhow::Result;
+use notify::Watcher;
+use std::collections::BTreeSet;
+
+use crate::common;
+use crate::extension;
+
+/// Delay which allows file system changes to settle before re-running the check.
+static SETTLE_DELAY: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Re-run the check whenever a dependency specification file changes. Runs until interrupted.
+pub fn run(args: &super::Arguments, no_color: bool, extension_args: &Vec<String>) -> Result<()> {
+    let working_directory = super::get_working_directory(&args)?;
+    let mut file_paths = get_dependency_file_paths(&args, &working_directory, &extension_args)?;
+    run_check(&args, no_color, &extension_args);
+
+    loop {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut watcher = notify::watcher(sender, SETTLE_DELAY)?;
+        for directory in get_watch_directories(&file_paths, &working_directory) {
+            watcher.watch(&directory, notify::RecursiveMode::NonRecursive)?;
+        }
+        println!("Watching for changes... (Ctrl-C to stop)");
+
+        loop {
+            let event = receiver.recv()?;
+            log::debug!("File system event: {:?}", event);
+            if is_relevant_event(&event, &file_paths) {
+                break;
+            }
+        }
+
+        // Clear the terminal before printing the new report.
+        print!("\x1B[2J\x1B[1;1H");
+        // Dependency specification files may have been added or removed.
+        file_paths = get_dependency_file_paths(&args, &working_directory, &extension_args)?;
+        run_check(&args, no_color, &extension_args);
+    }
+}
+
+fn run_check(args: &super::Arguments, no_color: bool, extension_args: &Vec<String>) {
+    println!(
+        "Checked at: {}",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+    );
+    if let Err(error) = super::run_check(&args, no_color, &extension_args) {
+        eprintln!("Check failed: {:#}", error);
+    }
+}
+
+/// Returns the paths of dependency specification files found in the working directory.
+fn get_dependency_file_paths(
+    args: &super::Arguments,
+    working_directory: &std::path::PathBuf,
+    extension_args: &Vec<String>,
+) -> Result<BTreeSet<std::path::PathBuf>> {
+    let mut config = common::config::Config::load()?;
+    extension::manage::update_config(&mut config)?;
+    let extension_names =
+        extension::manage::handle_extension_names_arg(&args.extension_names, &config)?;
+    let extensions = extension::manage::get_enabled(&extension_names, &config)?;
+
+    let mut file_paths = BTreeSet::new();
+    for extension_all_dependencies in extension::identify_file_defined_dependencies(
+        &extensions,
+        &extension_args,
+        &working_directory,
+    )? {
+        match extension_all_dependencies {
+            Ok(all_dependencies) => {
+                file_paths.extend(all_dependencies.into_iter().map(|d| d.path));
+            }
+            Err(error) => log::error!("Extension error: {}", error),
+        }
+    }
+    Ok(file_paths)
+}
+
+/// Returns the directories which contain the given files.
+///
+/// Directories are watched rather than files so that files which are replaced,
+/// or deleted and recreated, continue to be watched.
+/// The working directory is watched if no files are given.
+fn get_watch_directories(
+    file_paths: &BTreeSet<std::path::PathBuf>,
+    working_directory: &std::path::PathBuf,
+) -> BTreeSet<std::path::PathBuf> {
+    if file_paths.is_empty() {
+        return maplit::btreeset! {working_directory.clone()};
+    }
+    file_paths
+        .iter()
+        .filter_map(|path| path.parent().map(|parent| parent.to_path_buf()))
+        .collect()
+}
+
+/// Returns true if the event creates, modifies, or deletes a watched file.
+///
+/// Any file change is relevant if there are no watched files.
+fn is_relevant_event(
+    event: &notify::DebouncedEvent,
+    file_paths: &BTreeSet<std::path::PathBuf>,
+) -> bool {
+    let is_watched = |path: &std::path::PathBuf| file_paths.is_empty() || file_paths.contains(path);
+    match event {
+        notify::DebouncedEvent::Create(path)
+        | notify::DebouncedEvent::Write(path)
+        | notify::DebouncedEvent::Chmod(path)
+        | notify::DebouncedEvent::Remove(path) => is_watched(path),
+        notify::DebouncedEvent::Rename(source_path, destination_path) => {
+            is_watched(source_path) || is_watched(destination_path)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_watched_file_events_are_relevant() {
+        let lock_file = std::path::PathBuf::from("/project/package-lock.json");
+        let file_paths = maplit::btreeset! {lock_file.clone()};
+
+        assert!(is_relevant_event(
+            &notify::DebouncedEvent::Write(lock_file.clone()),
+            &file_paths
+        ));
+        assert!(is_relevant_event(
+            &notify::DebouncedEvent::Rename(
+                std::path::PathBuf::from("/project/package-lock.json.tmp"),
+                lock_file.clone()
+            ),
+            &file_paths
+        ));
+        assert!(!is_relevant_event(
+            &notify::DebouncedEvent::Write(std::path::PathBuf::from("/project/README.md")),
+            &file_paths
+        ));
+    }
+}
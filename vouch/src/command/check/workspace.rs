@@ -0,0 +1,122 @@
+use anyhow::{format_err, Result};
+
+use super::{report, table};
+use crate::common::StoreTransaction;
+use crate::package;
+use crate::peer;
+use crate::review;
+
+/// Report the ongoing review of a package as if it were committed.
+///
+/// Comments are read from the active review file of each matching review workspace. Reviews are
+/// not inserted into the index.
+pub fn report(
+    package_name: &str,
+    package_version: &str,
+    status_options: &report::StatusOptions,
+    show_comments: bool,
+    color_enabled: bool,
+    review_freshness_days: u32,
+    tx: &StoreTransaction,
+) -> Result<report::Totals> {
+    let root_peer =
+        peer::index::get_root(&tx)?.ok_or(format_err!("Cant find root peer. Index corrupt."))?;
+
+    let mut grand_totals = report::Totals::default();
+    let mut found_workspace = false;
+    for (registry_host_name, name, version) in review::workspace::get_all_existing()? {
+        if name != package_name || version != package_version {
+            continue;
+        }
+        let workspace_manifest = match review::workspace::get_existing(
+            &package_name,
+            &package_version,
+            &registry_host_name,
+        )? {
+            Some(workspace_manifest) => workspace_manifest,
+            None => continue,
+        };
+        found_workspace = true;
+
+        let reviews_directory =
+            review::tool::get_reviews_directory(&workspace_manifest.workspace_path);
+        let active_review_file = match review::active::find(&reviews_directory) {
+            Some(active_review_file) => active_review_file,
+            None => {
+                println!(
+                    "No active review file found in workspace: {}",
+                    workspace_manifest.workspace_path.display()
+                );
+                continue;
+            }
+        };
+
+        let review = review::Review {
+            id: 0,
+            peer: root_peer.clone(),
+            package: get_package(
+                &package_name,
+                &package_version,
+                &registry_host_name,
+                &workspace_manifest.artifact_hash,
+                &tx,
+            )?,
+            comments: review::active::parse(&active_review_file)?,
+            version_constraint: None,
+            total_line_count: None,
+            quality_score: None,
+        };
+        let dependency_reports = vec![report::get_ongoing_review_report(&review, &status_options)?];
+        let totals = report::Totals::from_reports(&dependency_reports);
+        grand_totals.extend(&totals);
+
+        let table = table::get(
+            &dependency_reports,
+            &table::TableOptions {
+                registry_label: Some(&registry_host_name),
+                review_freshness_days,
+                show_comments,
+                color_enabled,
+                ..Default::default()
+            },
+        )?;
+        table.printstd();
+    }
+
+    if !found_workspace {
+        return Err(format_err!(
+            "No review workspace found for package: {} {}",
+            package_name,
+            package_version
+        ));
+    }
+    Ok(grand_totals)
+}
+
+/// Returns the indexed package, or an unindexed package if the package is not in the index.
+fn get_package(
+    package_name: &str,
+    package_version: &str,
+    registry_host_name: &str,
+    artifact_hash: &str,
+    tx: &StoreTransaction,
+) -> Result<package::Package> {
+    let package = package::index::get(
+        &package::index::Fields {
+            package_name: Some(&package_name),
+            package_version: Some(&package_version),
+            registry_host_names: Some(maplit::btreeset! {registry_host_name}),
+            ..Default::default()
+        },
+        &tx,
+    )?
+    .into_iter()
+    .next();
+    Ok(package.unwrap_or(package::Package {
+        id: 0,
+        name: package_name.to_string(),
+        version: package_version.to_string(),
+        registries: std::collections::BTreeSet::new(),
+        artifact_hash: artifact_hash.to_string(),
+    }))
+}
@@ -1,6 +1,31 @@
-use anyhow::Result;
+use anyhow::{format_err, Result};
 use structopt::{self, StructOpt};
 
+static SECRET_MASK: &str = "****";
+
+#[derive(Debug, StructOpt, Clone)]
+pub enum Subcommands {
+    /// List all config setting field names and values.
+    List(ListArguments),
+
+    /// Reset a config setting field to its default value.
+    Reset(ResetArguments),
+}
+
+pub fn run_subcommand(subcommand: &Subcommands) -> Result<()> {
+    match subcommand {
+        Subcommands::List(args) => {
+            log::info!("Running command: config list");
+            list(&args)?;
+        }
+        Subcommands::Reset(args) => {
+            log::info!("Running command: config reset");
+            reset(&args)?;
+        }
+    }
+    Ok(())
+}
+
 #[derive(Debug, StructOpt, Clone)]
 #[structopt(
     name = "no_version",
@@ -8,6 +33,9 @@ use structopt::{self, StructOpt};
     global_settings = &[structopt::clap::AppSettings::DisableVersion]
 )]
 pub struct Arguments {
+    #[structopt(subcommand)]
+    pub subcommand: Option<Subcommands>,
+
     /// Config setting field name.
     pub name: Option<String>,
 
@@ -16,6 +44,10 @@ pub struct Arguments {
 }
 
 pub fn run_command(args: &Arguments) -> Result<()> {
+    if let Some(subcommand) = &args.subcommand {
+        return run_subcommand(&subcommand);
+    }
+
     let mut config = crate::common::config::Config::load()?;
     if let Some(name) = &args.name {
         if let Some(value) = &args.value {
@@ -30,3 +62,107 @@ pub fn run_command(args: &Arguments) -> Result<()> {
     }
     Ok(())
 }
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct ListArguments {
+    /// Show secret values such as the API key.
+    #[structopt(long = "show-secrets")]
+    pub show_secrets: bool,
+
+    /// Output config as JSON.
+    #[structopt(long = "json", conflicts_with = "keys-only")]
+    pub json: bool,
+
+    /// List setting field names only.
+    #[structopt(long = "keys-only")]
+    pub keys_only: bool,
+}
+
+fn list(args: &ListArguments) -> Result<()> {
+    let config = crate::common::config::Config::load()?;
+
+    if args.json {
+        let mut value = serde_json::to_value(&config)?;
+        if !args.show_secrets {
+            for name in crate::common::config::SECRET_FIELD_NAMES {
+                let pointer = format!("/{}", name.replace(".", "/"));
+                if let Some(secret) = value.pointer_mut(&pointer) {
+                    *secret = serde_json::Value::String(SECRET_MASK.to_string());
+                }
+            }
+        }
+        println!("{}", serde_json::to_string_pretty(&value)?);
+        return Ok(());
+    }
+
+    let fields = config.list()?;
+    if args.keys_only {
+        for (name, _) in &fields {
+            println!("{}", name);
+        }
+        return Ok(());
+    }
+
+    let mut table = prettytable::Table::new();
+    table.set_format(*prettytable::format::consts::FORMAT_CLEAN);
+    for (name, value) in &fields {
+        let is_secret = crate::common::config::SECRET_FIELD_NAMES.contains(&name.as_str());
+        let value = if is_secret && !args.show_secrets && !value.is_empty() {
+            SECRET_MASK
+        } else {
+            value.as_str()
+        };
+        table.add_row(prettytable::Row::new(vec![
+            prettytable::Cell::new(&name),
+            prettytable::Cell::new(&value),
+        ]));
+    }
+    table.printstd();
+    Ok(())
+}
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct ResetArguments {
+    /// Config setting field name.
+    #[structopt(name = "name", required_unless = "all")]
+    pub name: Option<String>,
+
+    /// Reset all config settings to their default values.
+    #[structopt(long = "all", conflicts_with = "name")]
+    pub all: bool,
+}
+
+fn reset(args: &ResetArguments) -> Result<()> {
+    if args.all {
+        if !dialoguer::Confirm::new()
+            .with_prompt("Reset all config settings to their default values?")
+            .interact()?
+        {
+            println!("Config unchanged.");
+            return Ok(());
+        }
+        crate::common::config::Config::default().dump()?;
+        println!("Reset all config settings.");
+        return Ok(());
+    }
+
+    let name = args
+        .name
+        .as_ref()
+        .ok_or(format_err!("No config setting field name given."))?;
+    let mut config = crate::common::config::Config::load()?;
+    let value = config.reset(&name)?;
+    config.dump()?;
+    println!("set {name}: {value}", name = name, value = value);
+    Ok(())
+}
@@ -3,6 +3,8 @@ use structopt::{self, StructOpt};
 
 use crate::common;
 use crate::extension;
+use crate::review;
+use crate::store;
 
 #[derive(Debug, StructOpt, Clone)]
 pub enum Subcommands {
@@ -20,6 +22,21 @@ pub enum Subcommands {
 
     /// List installed extensions.
     List(ListArguments),
+
+    /// Check an extension binary for protocol compliance.
+    Check(CheckArguments),
+
+    /// Verify an installed extension binary against known good checksums.
+    Verify(VerifyArguments),
+
+    /// Update extensions to their latest releases. Pinned extensions are skipped.
+    Update(UpdateArguments),
+
+    /// Pin extension to a release version, excluding it from updates.
+    Pin(PinArguments),
+
+    /// Remove an extension's version pin.
+    Unpin(UnpinArguments),
 }
 
 pub fn run_subcommand(subcommand: &Subcommands) -> Result<()> {
@@ -44,6 +61,26 @@ pub fn run_subcommand(subcommand: &Subcommands) -> Result<()> {
             log::info!("Running command: extension list");
             list(&args)?;
         }
+        Subcommands::Check(args) => {
+            log::info!("Running command: extension check");
+            check(&args)?;
+        }
+        Subcommands::Verify(args) => {
+            log::info!("Running command: extension verify");
+            verify(&args)?;
+        }
+        Subcommands::Update(args) => {
+            log::info!("Running command: extension update");
+            update(&args)?;
+        }
+        Subcommands::Pin(args) => {
+            log::info!("Running command: extension pin");
+            pin(&args)?;
+        }
+        Subcommands::Unpin(args) => {
+            log::info!("Running command: extension unpin");
+            unpin(&args)?;
+        }
     }
     Ok(())
 }
@@ -62,6 +99,11 @@ pub struct AddArguments {
     // Optional installation directory path.
     #[structopt(long = "install-directory", short = "d", name = "install-directory")]
     pub install_directory: Option<String>,
+
+    /// Release tag name to install. The extension is pinned to this version.
+    /// Example value: v0.3.2
+    #[structopt(long = "version", name = "version")]
+    pub version: Option<String>,
 }
 
 fn add(args: &AddArguments) -> Result<()> {
@@ -91,7 +133,7 @@ fn add(args: &AddArguments) -> Result<()> {
         let url = args.name_or_url.clone();
         if let Some(url) = try_parse_user_url(&url)? {
             log::debug!("Sanitized URL: {}", url);
-            extension::manage::add_from_url(&url, &bin_directory)?
+            extension::manage::add_from_url(&url, &bin_directory, args.version.as_deref())?
         } else {
             return Err(format_err!("Failed to parse URL: {}", url));
         }
@@ -99,13 +141,17 @@ fn add(args: &AddArguments) -> Result<()> {
         log::debug!("Identified argument as name.");
         let name = extension::manage::clean_name(&args.name_or_url);
         let url = get_url_from_name(&name)?;
-        extension::manage::add_from_url(&url, &bin_directory)?
+        extension::manage::add_from_url(&url, &bin_directory, args.version.as_deref())?
     };
 
     let mut config = common::config::Config::load()?;
     extension::manage::update_config(&mut config)?;
 
     println!("Added extension: {}", extension_name);
+    if let Some(version) = &args.version {
+        extension::manage::pin(&extension_name, &version, &mut config)?;
+        println!("Pinned extension {} to {}", extension_name, version);
+    }
     Ok(())
 }
 
@@ -157,6 +203,11 @@ fn get_url_from_name(name: &str) -> Result<url::Url> {
 pub struct RemoveArguments {
     /// Extension name.
     pub name: String,
+
+    /// Remove the extension even if the index contains reviews of packages from its registries.
+    /// Reviews are kept.
+    #[structopt(long = "force")]
+    pub force: bool,
 }
 
 fn remove(args: &RemoveArguments) -> Result<()> {
@@ -164,11 +215,47 @@ fn remove(args: &RemoveArguments) -> Result<()> {
     extension::manage::update_config(&mut config)?;
 
     let name = extension::manage::clean_name(&args.name);
-    extension::manage::remove(&name)?;
+    if !args.force {
+        let review_count = count_registry_reviews(&name, &config)?;
+        if review_count > 0 {
+            return Err(format_err!(
+                "Found {count} reviews of packages handled by extension: {name}\n\
+                Remove the extension and keep the reviews with: vouch extension remove --force {name}",
+                count = review_count,
+                name = name
+            ));
+        }
+    }
+
+    let bin_path = extension::manage::remove(&name)?;
     println!("Removed extension: {}", name);
+    println!("Deleted binary: {}", bin_path.display());
     Ok(())
 }
 
+/// Returns the number of indexed reviews of packages from the extension's registries.
+fn count_registry_reviews(name: &str, config: &common::config::Config) -> Result<usize> {
+    let registry_host_names = extension::manage::get_registry_host_names(&name, &config);
+    if registry_host_names.is_empty() {
+        return Ok(0);
+    }
+    let mut store = store::Store::from_root()?;
+    let tx = store.get_transaction()?;
+    let reviews = review::index::get(
+        &review::index::Fields {
+            registry_host_names: Some(
+                registry_host_names
+                    .iter()
+                    .map(|registry_host_name| registry_host_name.as_str())
+                    .collect(),
+            ),
+            ..Default::default()
+        },
+        &tx,
+    )?;
+    Ok(reviews.len())
+}
+
 #[derive(Debug, StructOpt, Clone)]
 #[structopt(
     name = "no_version",
@@ -196,6 +283,14 @@ fn enable(args: &EnableArguments) -> Result<()> {
         ));
     }
 
+    if !extension::manage::is_installed(&name)? {
+        println!(
+            "WARNING: extension binary not found: {}\n\
+            Extension enabled in config but may not be usable.",
+            name
+        );
+    }
+
     extension::manage::enable(&name, &mut config)?;
     println!("Enabled extension: {}", name);
     Ok(())
@@ -249,3 +344,205 @@ fn list(_args: &ListArguments) -> Result<()> {
     }
     Ok(())
 }
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct CheckArguments {
+    /// Extension binary path.
+    #[structopt(name = "path", parse(from_os_str))]
+    pub path: std::path::PathBuf,
+}
+
+fn check(args: &CheckArguments) -> Result<()> {
+    let color_enabled = super::check::is_color_enabled(false);
+    let results = extension::protocol::check(&args.path)?;
+
+    let mut table = prettytable::Table::new();
+    table.set_format(*prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    for result in &results {
+        table.add_row(prettytable::Row::new(vec![
+            super::check::table::get_summary_cell(&result.summary, color_enabled),
+            prettytable::Cell::new(&result.command),
+            prettytable::Cell::new(&result.message),
+        ]));
+    }
+    table.printstd();
+
+    for result in &results {
+        if let Some(raw_output) = &result.raw_output {
+            println!(
+                "\n{summary}: {command}\n{message}\nRaw output:\n{raw_output}",
+                summary = result.summary.to_string().to_uppercase(),
+                command = result.command,
+                message = result.message,
+                raw_output = raw_output
+            );
+        }
+    }
+
+    if results
+        .iter()
+        .any(|result| result.summary == crate::review::Summary::Fail)
+    {
+        return Err(format_err!("Extension failed protocol checks."));
+    }
+    Ok(())
+}
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct VerifyArguments {
+    /// Extension name.
+    pub name: String,
+
+    /// Checksums manifest URL.
+    #[structopt(
+        long = "manifest-url",
+        name = "url",
+        default_value = extension::manage::checksum::DEFAULT_MANIFEST_URL
+    )]
+    pub manifest_url: url::Url,
+}
+
+fn verify(args: &VerifyArguments) -> Result<()> {
+    let name = extension::manage::clean_name(&args.name);
+    let bin_path = extension::manage::get_bin_path(&name)?.ok_or(format_err!(
+        "Failed to find extension binary: {}\n\
+        Built-in extensions are verified as part of Vouch.",
+        name
+    ))?;
+    let (actual_hash, _) = common::fs::hash(&bin_path)?;
+
+    let known_hashes = extension::manage::checksum::get_known_hashes(&args.manifest_url, &name)?;
+    if known_hashes.is_empty() {
+        return Err(format_err!(
+            "No known checksums for extension {} on this platform in manifest: {}",
+            name,
+            args.manifest_url
+        ));
+    }
+
+    if let Some((version, _hash)) = known_hashes.iter().find(|(_, hash)| **hash == actual_hash) {
+        println!("OK: {} (version {})", bin_path.display(), version);
+        return Ok(());
+    }
+
+    println!("WARNING: EXTENSION BINARY DOES NOT MATCH ANY KNOWN CHECKSUM");
+    println!("Binary: {}", bin_path.display());
+    println!("Actual hash: {}", actual_hash);
+    for (version, hash) in &known_hashes {
+        println!("Expected hash (version {}): {}", version, hash);
+    }
+    Err(format_err!("Extension verification failed: {}", name))
+}
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct UpdateArguments {
+    /// Extension name. All installed extensions are updated if not given.
+    pub name: Option<String>,
+}
+
+fn update(args: &UpdateArguments) -> Result<()> {
+    let mut config = common::config::Config::load()?;
+    extension::manage::update_config(&mut config)?;
+
+    let names = match &args.name {
+        Some(name) => vec![extension::manage::clean_name(&name)],
+        None => config.extensions.enabled.keys().cloned().collect(),
+    };
+    for name in names {
+        if let Some(version) = config.extensions.pinned_versions.get(&name) {
+            println!("Extension {} is pinned to {}, skipping", name, version);
+            continue;
+        }
+        // Built-in extensions are updated with Vouch itself.
+        let bin_path = match extension::manage::get_bin_path(&name)? {
+            Some(bin_path) => bin_path,
+            None => {
+                log::debug!("Skipping extension without binary: {}", name);
+                continue;
+            }
+        };
+        let bin_directory = bin_path.parent().ok_or(format_err!(
+            "Failed to find extension bin directory: {}",
+            bin_path.display()
+        ))?;
+
+        let url = get_url_from_name(&name)?;
+        extension::manage::add_from_url(&url, &bin_directory.to_path_buf(), None)?;
+        println!("Updated extension: {}", name);
+    }
+    Ok(())
+}
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct PinArguments {
+    /// Extension name.
+    pub name: String,
+
+    /// Release tag name.
+    /// Example value: v0.3.2
+    pub version: String,
+}
+
+fn pin(args: &PinArguments) -> Result<()> {
+    let mut config = common::config::Config::load()?;
+    extension::manage::update_config(&mut config)?;
+
+    let name = extension::manage::clean_name(&args.name);
+    let all_extension_names = extension::manage::get_all_names(&config)?;
+    if !all_extension_names.contains(&name) {
+        return Err(format_err!(
+            "Failed to find extension. Known extensions: {}",
+            all_extension_names
+                .into_iter()
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    extension::manage::pin(&name, &args.version, &mut config)?;
+    println!("Pinned extension {} to {}", name, args.version);
+    Ok(())
+}
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct UnpinArguments {
+    /// Extension name.
+    pub name: String,
+}
+
+fn unpin(args: &UnpinArguments) -> Result<()> {
+    let mut config = common::config::Config::load()?;
+    extension::manage::update_config(&mut config)?;
+
+    let name = extension::manage::clean_name(&args.name);
+    match extension::manage::unpin(&name, &mut config)? {
+        Some(version) => println!("Unpinned extension {} from {}", name, version),
+        None => println!("Extension {} is not pinned.", name),
+    }
+    Ok(())
+}
@@ -1,16 +1,18 @@
 use anyhow::Result;
 use structopt::{self, StructOpt};
 
-mod check;
+pub mod check;
 mod config;
 mod extension;
 mod peer;
 mod review;
 mod setup;
+mod store;
 mod sync;
+mod workspace;
 
-pub fn run_command(command: Command, extension_args: &Vec<String>) -> Result<()> {
-    match command {
+pub fn run_command(opts: Opts, extension_args: &Vec<String>) -> Result<()> {
+    match opts.command {
         Command::Setup(args) => {
             log::info!("Running command: setup");
             setup::run_command(&args)?;
@@ -20,15 +22,15 @@ pub fn run_command(command: Command, extension_args: &Vec<String>) -> Result<()>
             setup::is_complete()?;
             peer::run_subcommand(&subcommand)?;
         }
-        Command::Review(args) => {
+        Command::Review(subcommand) => {
             log::info!("Running command: review");
             setup::is_complete()?;
-            review::run_command(&args)?;
+            review::run_subcommand(&subcommand)?;
         }
         Command::Check(args) => {
             log::info!("Running command: check");
             setup::is_complete()?;
-            check::run_command(&args, &extension_args)?;
+            check::run_command(&args, opts.no_color, &extension_args)?;
         }
         Command::Sync(args) => {
             log::info!("Running command: sync");
@@ -45,6 +47,15 @@ pub fn run_command(command: Command, extension_args: &Vec<String>) -> Result<()>
             setup::is_complete()?;
             extension::run_subcommand(&args)?;
         }
+        Command::Store(subcommand) => {
+            log::info!("Running command: store");
+            store::run_subcommand(&subcommand)?;
+        }
+        Command::Workspace(subcommand) => {
+            log::info!("Running command: workspace");
+            setup::is_complete()?;
+            workspace::run_subcommand(&subcommand)?;
+        }
     }
     Ok(())
 }
@@ -61,9 +72,9 @@ pub enum Command {
     #[structopt(name = "peer")]
     Peer(peer::Subcommands),
 
-    /// Review a package.
+    /// Review packages and manage reviews.
     #[structopt(name = "review")]
-    Review(review::Arguments),
+    Review(review::Subcommands),
 
     /// Check dependencies against reviews.
     #[structopt(name = "check")]
@@ -80,6 +91,14 @@ pub enum Command {
     /// Manage extensions.
     #[structopt(name = "extension")]
     Extension(extension::Subcommands),
+
+    /// Backup and restore local data.
+    #[structopt(name = "store")]
+    Store(store::Subcommands),
+
+    /// Manage ongoing review workspaces.
+    #[structopt(name = "workspace")]
+    Workspace(workspace::Subcommands),
 }
 
 #[derive(Debug, StructOpt, Clone)]
@@ -87,6 +106,10 @@ pub enum Command {
 #[structopt(global_setting = structopt::clap::AppSettings::ColoredHelp)]
 #[structopt(global_setting = structopt::clap::AppSettings::DeriveDisplayOrder)]
 pub struct Opts {
+    /// Disable colored output.
+    #[structopt(long = "no-color")]
+    pub no_color: bool,
+
     #[structopt(subcommand)]
     pub command: Command,
 }
@@ -1,4 +1,5 @@
 use anyhow::{format_err, Result};
+use prettytable::{self, cell};
 use std::convert::TryFrom;
 use structopt::{self, StructOpt};
 
@@ -9,15 +10,28 @@ use crate::store;
 
 #[derive(Debug, StructOpt, Clone)]
 pub enum Subcommands {
+    /// List peers.
+    List(ListArguments),
+
     /// Add peer.
     Add(AddArguments),
 
     /// Remove peer.
     Remove(RemoveArguments),
+
+    /// Check a peer's review store for inconsistencies.
+    Check(CheckArguments),
+
+    /// Verify each review in a peer's review store and the peer's git objects.
+    Verify(VerifyArguments),
 }
 
 pub fn run_subcommand(subcommand: &Subcommands) -> Result<()> {
     match subcommand {
+        Subcommands::List(args) => {
+            log::info!("Running command: peer list");
+            list(&args)?;
+        }
         Subcommands::Add(args) => {
             log::info!("Running command: peer add");
             add(&args)?;
@@ -26,10 +40,161 @@ pub fn run_subcommand(subcommand: &Subcommands) -> Result<()> {
             log::info!("Running command: peer remove");
             remove(&args)?;
         }
+        Subcommands::Check(args) => {
+            log::info!("Running command: peer check");
+            check(&args)?;
+        }
+        Subcommands::Verify(args) => {
+            log::info!("Running command: peer verify");
+            verify(&args)?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct ListArguments {
+    /// Show peers as a tree, starting from the root peer.
+    #[structopt(long = "tree")]
+    pub tree: bool,
+
+    /// Maximum displayed tree depth. Deeper peers are summarised.
+    #[structopt(
+        long = "max-depth",
+        name = "depth",
+        default_value = "3",
+        requires = "tree"
+    )]
+    pub max_depth: usize,
+}
+
+fn list(args: &ListArguments) -> Result<()> {
+    let mut store = store::Store::from_root()?;
+    let tx = store.get_transaction()?;
+
+    let root_peer =
+        peer::index::get_root(&tx)?.ok_or(format_err!("Cant find root peer. Index corrupt."))?;
+    let breadth_layers = peer::index::get_breadth_first_child_peers(&root_peer, &tx)?;
+
+    let mut peers = std::collections::BTreeMap::new();
+    for peer in breadth_layers.iter().flatten() {
+        let review_count = review::index::get(
+            &review::index::Fields {
+                peer: Some(&peer),
+                ..Default::default()
+            },
+            &tx,
+        )?
+        .len();
+        peers.insert(peer.id, (peer.clone(), review_count));
+    }
+
+    if args.tree {
+        let tree = get_peer_tree(&root_peer, &peers);
+        for line in get_tree_lines(&tree, args.max_depth) {
+            println!("{}", line);
+        }
+        return Ok(());
+    }
+
+    let mut table = prettytable::Table::new();
+    table.set_titles(prettytable::row!["alias", "git URL", "reviews"]);
+    table.set_format(*prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    for (peer, review_count) in peers.values() {
+        table.add_row(prettytable::Row::new(vec![
+            prettytable::Cell::new(&peer.alias),
+            prettytable::Cell::new(&peer.git_url.to_string()),
+            prettytable::Cell::new_align(
+                &review_count.to_string(),
+                prettytable::format::Alignment::RIGHT,
+            ),
+        ]));
     }
+    table.printstd();
     Ok(())
 }
 
+/// Peer tree display node.
+#[derive(Debug, Clone)]
+struct PeerTreeNode {
+    label: String,
+    children: Vec<PeerTreeNode>,
+}
+
+/// Returns the display tree of the given peer and its descendants.
+fn get_peer_tree(
+    peer: &peer::Peer,
+    peers: &std::collections::BTreeMap<common::index::ID, (peer::Peer, usize)>,
+) -> PeerTreeNode {
+    let review_count = peers
+        .get(&peer.id)
+        .map(|(_, review_count)| *review_count)
+        .unwrap_or_default();
+    let mut children: Vec<_> = peers
+        .values()
+        .filter(|(child, _)| child.parent_id == Some(peer.id))
+        .map(|(child, _)| get_peer_tree(&child, &peers))
+        .collect();
+    children.sort_by(|a, b| a.label.cmp(&b.label));
+    PeerTreeNode {
+        label: format!(
+            "{} ({}) reviews: {}",
+            peer.alias, peer.git_url, review_count
+        ),
+        children,
+    }
+}
+
+/// Returns tree diagram lines. Children beyond the maximum depth are counted, not shown.
+fn get_tree_lines(root: &PeerTreeNode, max_depth: usize) -> Vec<String> {
+    let mut lines = vec![root.label.clone()];
+    if max_depth == 0 {
+        if !root.children.is_empty() {
+            lines.push(format!("└── ... {} more children", root.children.len()));
+        }
+        return lines;
+    }
+    add_tree_lines(&root, "", 1, max_depth, &mut lines);
+    lines
+}
+
+fn add_tree_lines(
+    node: &PeerTreeNode,
+    prefix: &str,
+    depth: usize,
+    max_depth: usize,
+    lines: &mut Vec<String>,
+) {
+    for (index, child) in node.children.iter().enumerate() {
+        let is_last = index + 1 == node.children.len();
+        let (connector, child_prefix) = if is_last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        lines.push(format!("{}{}{}", prefix, connector, child.label));
+
+        let child_prefix = format!("{}{}", prefix, child_prefix);
+        if child.children.is_empty() {
+            continue;
+        }
+        if depth >= max_depth {
+            lines.push(format!(
+                "{}└── ... {} more children",
+                child_prefix,
+                child.children.len()
+            ));
+        } else {
+            add_tree_lines(&child, &child_prefix, depth + 1, max_depth, lines);
+        }
+    }
+}
+
 #[derive(Debug, StructOpt, Clone)]
 #[structopt(
     name = "no_version",
@@ -40,6 +205,19 @@ pub struct AddArguments {
     /// Git repository URL.
     #[structopt(name = "git-url", parse(try_from_str = crate::common::GitUrl::try_from))]
     pub git_url: crate::common::GitUrl,
+
+    /// Maximum depth of imported peers. The added peer is at depth 1, its own peers at depth 2.
+    /// Default: unlimited
+    #[structopt(long = "depth", name = "depth", parse(try_from_str = parse_depth))]
+    pub depth: Option<usize>,
+}
+
+fn parse_depth(value: &str) -> Result<usize> {
+    let depth: usize = value.parse()?;
+    if depth == 0 {
+        return Err(format_err!("Depth must be at least 1."));
+    }
+    Ok(depth)
 }
 
 fn add(args: &AddArguments) -> Result<()> {
@@ -85,11 +263,12 @@ fn add(args: &AddArguments) -> Result<()> {
 
     peer::fs::add(&args.git_url, &mut tx)?;
 
-    let alias = peer::index::get_new_alias(&args.git_url, &mut tx)?;
-    let peer = peer::index::insert(&alias, &args.git_url, Some(&mut root_peer), &tx)?;
+    let alias = peer::index::get_new_alias_with_host_check(&args.git_url, &tx)?;
+    let mut peer = peer::index::insert(&alias, &args.git_url, Some(&mut root_peer), &tx)?;
+    peer::index::set_max_depth(&mut peer, args.depth, &tx)?;
     let mut peer_store = store::Store::from_peer(&vec![root_peer, peer])?;
     let peer_index_tx = peer_store.get_transaction()?;
-    store::index::merge(&args.git_url, &peer_index_tx, &tx)?;
+    store::index::merge(&args.git_url, args.depth, &peer_index_tx, &tx)?;
 
     tx.commit(format!("Add peer: {}", &args.git_url).as_str())?;
     Ok(())
@@ -102,27 +281,18 @@ fn add(args: &AddArguments) -> Result<()> {
     global_settings = &[structopt::clap::AppSettings::DisableVersion]
 )]
 pub struct RemoveArguments {
-    /// Git repository URL.
-    #[structopt(name = "git-url", parse(try_from_str = crate::common::GitUrl::try_from))]
-    pub git_url: crate::common::GitUrl,
+    /// Peer alias or git repository URL.
+    #[structopt(name = "peer")]
+    pub peer: String,
 }
 
 fn remove(args: &RemoveArguments) -> Result<()> {
     let mut store = store::Store::from_root()?;
     let mut tx = store.get_transaction()?;
 
-    let target_peer = peer::index::get(
-        &peer::index::Fields {
-            git_url: Some(&args.git_url),
-            ..Default::default()
-        },
-        &tx,
-    )?
-    .into_iter()
-    .next()
-    .ok_or(format_err!(
+    let target_peer = peer::index::get_by_alias_or_git_url(&args.peer, &tx)?.ok_or(format_err!(
         "Failed to find peer for removal: {}",
-        &args.git_url
+        &args.peer
     ))?;
 
     remove_peer_subtree(&target_peer, &mut tx)?;
@@ -138,6 +308,114 @@ fn remove(args: &RemoveArguments) -> Result<()> {
     Ok(())
 }
 
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct CheckArguments {
+    /// Peer alias or git repository URL.
+    #[structopt(name = "peer")]
+    pub peer: String,
+}
+
+fn check(args: &CheckArguments) -> Result<()> {
+    let mut store = store::Store::from_root()?;
+    let tx = store.get_transaction()?;
+
+    let target_peer = peer::index::get_by_alias_or_git_url(&args.peer, &tx)?
+        .ok_or(format_err!("Failed to find peer: {}", &args.peer))?;
+    if target_peer.is_root() {
+        return Err(format_err!("The given peer is the root peer."));
+    }
+
+    let peer_branch = peer::index::get_peer_branch(&target_peer, &tx)?;
+    let warnings = peer::check::check(&peer_branch)?;
+    if warnings.is_empty() {
+        println!("No problems found.");
+        return Ok(());
+    }
+
+    for warning in &warnings {
+        println!("WARNING: {}", warning);
+    }
+    println!("Problems found: {}", warnings.len());
+    Ok(())
+}
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct VerifyArguments {
+    /// Peer alias or git repository URL.
+    #[structopt(name = "peer")]
+    pub peer: String,
+}
+
+fn verify(args: &VerifyArguments) -> Result<()> {
+    let mut store = store::Store::from_root()?;
+    let tx = store.get_transaction()?;
+
+    let target_peer = peer::index::get_by_alias_or_git_url(&args.peer, &tx)?
+        .ok_or(format_err!("Failed to find peer: {}", &args.peer))?;
+    if target_peer.is_root() {
+        return Err(format_err!("The given peer is the root peer."));
+    }
+
+    let peer_branch = peer::index::get_peer_branch(&target_peer, &tx)?;
+    let verifications = peer::check::verify_reviews(&peer_branch)?;
+
+    let mut table = prettytable::Table::new();
+    table.set_titles(prettytable::row![
+        "review", "package", "version", "result", "problems"
+    ]);
+    table.set_format(*prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    for verification in &verifications {
+        let result = if verification.is_pass() {
+            "PASS"
+        } else {
+            "FAIL"
+        };
+        table.add_row(prettytable::Row::new(vec![
+            prettytable::Cell::new(&verification.review_id.to_string()),
+            prettytable::Cell::new(&verification.package_name.clone().unwrap_or_default()),
+            prettytable::Cell::new(&verification.package_version.clone().unwrap_or_default()),
+            prettytable::Cell::new(result),
+            prettytable::Cell::new(&verification.problems.join("; ")),
+        ]));
+    }
+    if !verifications.is_empty() {
+        table.printstd();
+    }
+
+    let failed_count = verifications.iter().filter(|v| !v.is_pass()).count();
+    println!(
+        "Reviews verified: {total}, failed: {failed}",
+        total = verifications.len(),
+        failed = failed_count
+    );
+
+    let fsck_output = peer::check::git_fsck(&peer_branch)?;
+    match &fsck_output {
+        Some(output) => println!("git fsck: FAIL\n{}", output),
+        None => println!("git fsck: PASS"),
+    }
+
+    if failed_count > 0 || fsck_output.is_some() {
+        println!(
+            "\nThe peer's review store may be corrupt. Consider removing and re-adding the peer:\n\
+            \tvouch peer remove {git_url}\n\
+            \tvouch peer add {git_url}",
+            git_url = target_peer.git_url
+        );
+    }
+    Ok(())
+}
+
 /// Remove peer and its subtree.
 fn remove_peer_subtree(target_peer: &peer::Peer, tx: &mut common::StoreTransaction) -> Result<()> {
     let peer_branch = peer::index::get_peer_branch(&target_peer, &tx)?;
@@ -166,3 +444,42 @@ fn remove_peer_subtree(target_peer: &peer::Peer, tx: &mut common::StoreTransacti
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_node(label: &str, children: Vec<PeerTreeNode>) -> PeerTreeNode {
+        PeerTreeNode {
+            label: label.to_string(),
+            children,
+        }
+    }
+
+    #[test]
+    fn test_get_tree_lines_truncates_at_max_depth() {
+        let tree = get_node(
+            "root",
+            vec![
+                get_node(
+                    "a",
+                    vec![get_node(
+                        "a1",
+                        vec![get_node("a1x", vec![]), get_node("a1y", vec![])],
+                    )],
+                ),
+                get_node("b", vec![]),
+            ],
+        );
+
+        let result = get_tree_lines(&tree, 2);
+        let expected = vec![
+            "root",
+            "├── a",
+            "│   └── a1",
+            "│       └── ... 2 more children",
+            "└── b",
+        ];
+        assert_eq!(result, expected);
+    }
+}
@@ -0,0 +1,151 @@
+use anyhow::{format_err, Result};
+use structopt::{self, StructOpt};
+
+use crate::common::StoreTransaction;
+use crate::peer;
+use crate::review;
+use crate::store;
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct Arguments {
+    /// Package name.
+    #[structopt(name = "package-name", required_unless = "all")]
+    pub package_name: Option<String>,
+
+    /// Package version.
+    #[structopt(name = "package-version", required_unless = "all")]
+    pub package_version: Option<String>,
+
+    /// Abort all ongoing reviews.
+    #[structopt(long = "all", conflicts_with = "package-name")]
+    pub all: bool,
+}
+
+pub fn run_command(args: &Arguments) -> Result<()> {
+    let mut store = store::Store::from_root()?;
+    let tx = store.get_transaction()?;
+    let root_peer =
+        peer::index::get_root(&tx)?.ok_or(format_err!("Cant find root peer. Index corrupt."))?;
+
+    let packages = if args.all {
+        get_all_ongoing_packages(&root_peer, &tx)?
+    } else {
+        let package_name = args
+            .package_name
+            .clone()
+            .ok_or(format_err!("No package name given."))?;
+        let package_version = args
+            .package_version
+            .clone()
+            .ok_or(format_err!("No package version given."))?;
+        maplit::btreeset! {(package_name, package_version)}
+    };
+
+    let mut commit_messages = vec![];
+    for (package_name, package_version) in &packages {
+        commit_messages.extend(abort(&package_name, &package_version, &root_peer, &tx)?);
+    }
+
+    if commit_messages.is_empty() {
+        println!("No ongoing review found.");
+        return Ok(());
+    }
+
+    tx.commit(&commit_messages.join("\n"))?;
+    for message in commit_messages {
+        println!("{}", message);
+    }
+    Ok(())
+}
+
+/// Returns the name and version of every package with an ongoing workspace or an empty root peer review.
+fn get_all_ongoing_packages(
+    root_peer: &peer::Peer,
+    tx: &StoreTransaction,
+) -> Result<std::collections::BTreeSet<(String, String)>> {
+    let mut packages = std::collections::BTreeSet::new();
+    for (_registry_host_name, package_name, package_version) in
+        review::workspace::get_all_existing()?
+    {
+        packages.insert((package_name, package_version));
+    }
+
+    let reviews = review::index::get(
+        &review::index::Fields {
+            peer: Some(&root_peer),
+            ..Default::default()
+        },
+        &tx,
+    )?;
+    for review in reviews {
+        if review.comments.is_empty() {
+            packages.insert((review.package.name, review.package.version));
+        }
+    }
+    Ok(packages)
+}
+
+/// Abort an ongoing review. Returns a commit message line for each aborted registry review.
+fn abort(
+    package_name: &str,
+    package_version: &str,
+    root_peer: &peer::Peer,
+    tx: &StoreTransaction,
+) -> Result<Vec<String>> {
+    let mut registry_host_names = std::collections::BTreeSet::new();
+
+    let reviews = review::index::get(
+        &review::index::Fields {
+            package_name: Some(&package_name),
+            package_version: Some(&package_version),
+            peer: Some(&root_peer),
+            ..Default::default()
+        },
+        &tx,
+    )?;
+    for review in reviews {
+        // Committed reviews and constraint reviews for other versions are left untouched.
+        if !review.comments.is_empty() || review.package.version != package_version {
+            continue;
+        }
+        review::index::remove(
+            &review::index::Fields {
+                id: Some(review.id),
+                ..Default::default()
+            },
+            &tx,
+        )?;
+        for registry in &review.package.registries {
+            registry_host_names.insert(registry.host_name.clone());
+        }
+    }
+
+    for (registry_host_name, name, version) in review::workspace::get_all_existing()? {
+        if name != package_name || version != package_version {
+            continue;
+        }
+        if let Some(workspace_manifest) =
+            review::workspace::get_existing(&package_name, &package_version, &registry_host_name)?
+        {
+            review::workspace::remove(&workspace_manifest)?;
+            registry_host_names.insert(registry_host_name);
+        }
+    }
+
+    Ok(registry_host_names
+        .into_iter()
+        .map(|registry_host_name| {
+            format!(
+                "Aborting review: {registry}/{name}/{version}",
+                registry = registry_host_name,
+                name = package_name,
+                version = package_version
+            )
+        })
+        .collect())
+}
@@ -0,0 +1,122 @@
+use anyhow::{format_err, Result};
+use structopt::{self, StructOpt};
+
+use crate::common;
+use crate::peer;
+use crate::review;
+use crate::store;
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct Arguments {
+    /// Peer alias or git repository URL.
+    #[structopt(long = "peer", name = "peer")]
+    pub peer: String,
+}
+
+pub fn run_command(args: &Arguments) -> Result<()> {
+    let config = common::config::Config::load()?;
+    let mut store = store::Store::from_root()?;
+    let tx = store.get_transaction()?;
+
+    let peer = peer::index::get_by_alias_or_git_url(&args.peer, &tx)?
+        .ok_or(format_err!("Failed to find peer: {}", &args.peer))?;
+    let root_peer =
+        peer::index::get_root(&tx)?.ok_or(format_err!("Cant find root peer. Index corrupt."))?;
+
+    let unreviewed = get_unreviewed(&peer, &root_peer, &tx)?;
+    if unreviewed.is_empty() {
+        println!("No packages found which are reviewed by the peer but not locally.");
+        return Ok(());
+    }
+
+    println!(
+        "Packages reviewed by {} but not locally: {}",
+        peer.alias,
+        unreviewed.len()
+    );
+    for peer_review in unreviewed {
+        let registry = peer_review
+            .package
+            .registries
+            .iter()
+            .next()
+            .ok_or(format_err!("Package does not have associated registries."))?;
+        println!(
+            "{summary}\t{registry_host_name}/{name}/{version}",
+            summary = review::get_summary(&peer_review)?,
+            registry_host_name = registry.host_name,
+            name = peer_review.package.name,
+            version = peer_review.package.version
+        );
+
+        if dialoguer::Confirm::new()
+            .with_prompt("Add to review queue?")
+            .interact()?
+        {
+            review::workspace::ensure(
+                &peer_review.package.name,
+                &peer_review.package.version,
+                &registry.host_name,
+                &registry.artifact_url,
+                &None,
+                None,
+                false,
+                config.core.workspace_max_size_mb,
+            )?;
+            println!(
+                "Added to review queue. Start reviewing with: vouch review open {} {}",
+                peer_review.package.name, peer_review.package.version
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Returns reviews by the given peer for packages which have not been reviewed by the root peer.
+fn get_unreviewed(
+    peer: &peer::Peer,
+    root_peer: &peer::Peer,
+    tx: &common::StoreTransaction,
+) -> Result<Vec<review::Review>> {
+    let peer_reviews = review::index::get(
+        &review::index::Fields {
+            peer: Some(&peer),
+            ..Default::default()
+        },
+        &tx,
+    )?;
+    let root_reviews = review::index::get(
+        &review::index::Fields {
+            peer: Some(&root_peer),
+            ..Default::default()
+        },
+        &tx,
+    )?;
+
+    let reviewed_packages: std::collections::HashSet<_> = root_reviews
+        .iter()
+        .map(|review| get_package_key(&review.package))
+        .collect();
+    Ok(peer_reviews
+        .into_iter()
+        .filter(|review| !reviewed_packages.contains(&get_package_key(&review.package)))
+        .collect())
+}
+
+/// Returns a package identifier which is independent of index IDs.
+fn get_package_key(package: &crate::package::Package) -> (String, String, Vec<String>) {
+    (
+        package.name.clone(),
+        package.version.clone(),
+        package
+            .registries
+            .iter()
+            .map(|registry| registry.host_name.clone())
+            .collect(),
+    )
+}
@@ -0,0 +1,126 @@
+use anyhow::{format_err, Result};
+use structopt::{self, StructOpt};
+
+use crate::common;
+use crate::extension;
+use crate::package;
+use crate::store;
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct Arguments {
+    /// Package name.
+    #[structopt(name = "package-name")]
+    pub package_name: String,
+
+    /// Package version.
+    #[structopt(name = "package-version")]
+    pub package_version: String,
+
+    /// Registry host name. The user is prompted if multiple registries match.
+    /// Example value: pypi.org
+    #[structopt(long = "registry", name = "host-name")]
+    pub registry_host_name: Option<String>,
+
+    /// Specify an extension for handling the package.
+    /// Example values: py, js, rs
+    #[structopt(long = "extension", short = "e", name = "name")]
+    pub extension_names: Option<Vec<String>>,
+}
+
+pub fn run_command(args: &Arguments) -> Result<()> {
+    let mut registry_urls = get_indexed_registry_urls(&args)?;
+    if registry_urls.is_empty() {
+        registry_urls = get_extension_registry_urls(&args)?;
+    }
+    if let Some(registry_host_name) = &args.registry_host_name {
+        registry_urls.retain(|(host_name, _)| host_name == registry_host_name);
+    }
+
+    let human_url = match registry_urls.len() {
+        0 => {
+            return Err(format_err!(
+                "Failed to find registry page for package: {} {}",
+                args.package_name,
+                args.package_version
+            ))
+        }
+        1 => registry_urls.remove(0).1,
+        _ => select_registry_url(registry_urls)?,
+    };
+
+    println!("{}", human_url);
+    if let Err(error) = open::that(&human_url) {
+        log::warn!("Failed to open browser: {}", error);
+    }
+    Ok(())
+}
+
+/// Returns registry host names and package URLs from the index.
+fn get_indexed_registry_urls(args: &Arguments) -> Result<Vec<(String, String)>> {
+    let mut store = store::Store::from_root()?;
+    let tx = store.get_transaction()?;
+    let packages = package::index::get(
+        &package::index::Fields {
+            package_name: Some(&args.package_name),
+            package_version: Some(&args.package_version),
+            ..Default::default()
+        },
+        &tx,
+    )?;
+
+    let mut registry_urls = vec![];
+    for package in packages {
+        for registry in package.registries {
+            let registry_url = (registry.host_name, registry.human_url.to_string());
+            if !registry_urls.contains(&registry_url) {
+                registry_urls.push(registry_url);
+            }
+        }
+    }
+    Ok(registry_urls)
+}
+
+/// Returns registry host names and package URLs found by extensions.
+///
+/// The primary registry is listed first.
+fn get_extension_registry_urls(args: &Arguments) -> Result<Vec<(String, String)>> {
+    let mut config = common::config::Config::load()?;
+    extension::manage::update_config(&mut config)?;
+    let extension_names =
+        extension::manage::handle_extension_names_arg(&args.extension_names, &config)?;
+    let extensions = extension::manage::get_enabled(&extension_names, &config)?;
+
+    let mut registries_metadata = extension::search_registries(
+        &args.package_name,
+        &Some(&args.package_version),
+        &extensions,
+    )?;
+    registries_metadata.sort_by_key(|registry_metadata| !registry_metadata.is_primary);
+    Ok(registries_metadata
+        .into_iter()
+        .map(|registry_metadata| {
+            (
+                registry_metadata.registry_host_name,
+                registry_metadata.human_url,
+            )
+        })
+        .collect())
+}
+
+fn select_registry_url(registry_urls: Vec<(String, String)>) -> Result<String> {
+    let host_names: Vec<_> = registry_urls
+        .iter()
+        .map(|(host_name, _)| host_name.as_str())
+        .collect();
+    let index = dialoguer::Select::new()
+        .with_prompt("Select registry")
+        .items(&host_names)
+        .default(0)
+        .interact()?;
+    Ok(registry_urls[index].1.clone())
+}
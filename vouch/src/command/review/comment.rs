@@ -0,0 +1,161 @@
+use anyhow::{format_err, Context, Result};
+use structopt::{self, StructOpt};
+
+use crate::common;
+use crate::peer;
+use crate::review;
+use crate::store;
+
+#[derive(Debug, StructOpt, Clone)]
+pub enum Subcommands {
+    /// Edit the message or summary of a committed review comment.
+    Edit(EditArguments),
+}
+
+pub fn run_subcommand(subcommand: &Subcommands) -> Result<()> {
+    match subcommand {
+        Subcommands::Edit(args) => {
+            log::info!("Running command: review comment edit");
+            edit(&args)?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct EditArguments {
+    /// Package name.
+    #[structopt(name = "package-name")]
+    pub package_name: String,
+
+    /// Package version.
+    #[structopt(name = "package-version")]
+    pub package_version: String,
+
+    /// Comment ID.
+    #[structopt(name = "comment-id")]
+    pub comment_id: common::index::ID,
+}
+
+/// User editable comment fields.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct EditableComment {
+    summary: review::Summary,
+    message: String,
+}
+
+fn edit(args: &EditArguments) -> Result<()> {
+    let mut store = store::Store::from_root()?;
+    let tx = store.get_transaction()?;
+
+    let root_peer =
+        peer::index::get_root(&tx)?.ok_or(format_err!("Cant find root peer. Index corrupt."))?;
+    let mut review = review::index::get(
+        &review::index::Fields {
+            package_name: Some(&args.package_name),
+            package_version: Some(&args.package_version),
+            peer: Some(&root_peer),
+            ..Default::default()
+        },
+        &tx,
+    )?
+    .into_iter()
+    .find(|review| {
+        review
+            .comments
+            .iter()
+            .any(|comment| comment.id == args.comment_id)
+    })
+    .ok_or(format_err!(
+        "Failed to find review for package {} {} with comment ID: {}",
+        args.package_name,
+        args.package_version,
+        args.comment_id
+    ))?;
+
+    let comment = review
+        .comments
+        .iter()
+        .find(|comment| comment.id == args.comment_id)
+        .cloned()
+        .ok_or(format_err!("Failed to find comment: {}", args.comment_id))?;
+
+    let registry = review
+        .package
+        .registries
+        .iter()
+        .next()
+        .ok_or(format_err!("Package does not have associated registries."))?
+        .clone();
+
+    let editable_comment = format!(
+        "# File: {}\n{}",
+        get_display_path(&comment, &review, &registry.host_name)?.display(),
+        serde_yaml::to_string(&EditableComment {
+            summary: comment.summary.clone(),
+            message: comment.message.clone(),
+        })?
+    );
+    let edited_comment = common::fs::edit_in_editor(&editable_comment, "comment.yaml")?;
+    let edited_comment: EditableComment =
+        serde_yaml::from_str(&edited_comment).context("Failed to parse edited comment.")?;
+
+    if edited_comment.summary == comment.summary && edited_comment.message == comment.message {
+        println!("Comment unchanged.");
+        return Ok(());
+    }
+
+    review.comments.remove(&comment);
+
+    // Update comment before review so that the edited comment is not considered stale.
+    let comment = review::comment::index::update(
+        review.id,
+        comment.id,
+        &edited_comment.summary,
+        &edited_comment.message,
+        &comment.selection,
+        &tx,
+    )?;
+    review.comments.insert(comment);
+    review::store(&review, &tx)?;
+
+    tx.commit(&format!(
+        "Updating comment {id} in review: {registry_host_name}/{package_name}/{package_version}",
+        id = args.comment_id,
+        registry_host_name = registry.host_name,
+        package_name = review.package.name,
+        package_version = review.package.version,
+    ))?;
+    println!("Comment updated.");
+    Ok(())
+}
+
+/// Returns the comment file path for display.
+///
+/// The path is shown relative to the package's review workspace if one exists.
+fn get_display_path(
+    comment: &review::comment::Comment,
+    review: &review::Review,
+    registry_host_name: &str,
+) -> Result<std::path::PathBuf> {
+    let workspace_manifest = review::workspace::get_existing(
+        &review.package.name,
+        &review.package.version,
+        &registry_host_name,
+    )?;
+    let workspace_directory = match workspace_manifest
+        .as_ref()
+        .and_then(|manifest| manifest.workspace_path.parent())
+    {
+        Some(workspace_directory) => workspace_directory.to_path_buf(),
+        None => return Ok(comment.path.clone()),
+    };
+    Ok(comment
+        .display_path(&workspace_directory, true)
+        .unwrap_or(comment.path.clone()))
+}
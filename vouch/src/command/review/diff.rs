@@ -0,0 +1,213 @@
+use anyhow::{format_err, Result};
+use structopt::{self, StructOpt};
+
+use crate::common;
+use crate::extension;
+use crate::peer;
+use crate::review;
+use crate::store;
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct Arguments {
+    /// Package name.
+    #[structopt(name = "package-name")]
+    pub package_name: String,
+
+    /// Package version.
+    #[structopt(name = "package-version")]
+    pub package_version: String,
+
+    /// Specify an extension for handling the package.
+    /// Example values: py, js, rs
+    #[structopt(long = "extension", short = "e", name = "name")]
+    pub extension_names: Option<Vec<String>>,
+
+    /// Show only a summary of changed files.
+    #[structopt(long = "stat")]
+    pub stat: bool,
+}
+
+pub fn run_command(args: &Arguments) -> Result<()> {
+    let mut config = common::config::Config::load()?;
+    extension::manage::update_config(&mut config)?;
+    let config = config;
+    let extension_names =
+        extension::manage::handle_extension_names_arg(&args.extension_names, &config)?;
+
+    let mut store = store::Store::from_root()?;
+    let tx = store.get_transaction()?;
+
+    let previous_review = get_previous_review(&args, &extension_names, &config, &tx)?;
+    let registry = super::get_primary_registry(&previous_review.package)?;
+    println!(
+        "Found previous review: {} {}",
+        previous_review.package.name, previous_review.package.version
+    );
+
+    let extensions = extension::manage::get_enabled(&extension_names, &config)?;
+    let registry_metadata = extension::search_registries(
+        &args.package_name,
+        &Some(&args.package_version),
+        &extensions,
+    )?
+    .into_iter()
+    .find(|registry_metadata| registry_metadata.registry_host_name == registry.host_name)
+    .ok_or(format_err!(
+        "Failed to find package in registry: {}",
+        registry.host_name
+    ))?;
+
+    let previous_workspace = WorkspaceGuard::ensure(
+        &previous_review.package.name,
+        &previous_review.package.version,
+        &registry.host_name,
+        &registry.artifact_url,
+        config.core.workspace_max_size_mb,
+    )?;
+    let workspace = WorkspaceGuard::ensure(
+        &args.package_name,
+        &args.package_version,
+        &registry.host_name,
+        &url::Url::parse(&registry_metadata.artifact_url)?,
+        config.core.workspace_max_size_mb,
+    )?;
+
+    diff(
+        &previous_workspace.manifest.workspace_path,
+        &workspace.manifest.workspace_path,
+        args.stat,
+    )?;
+    Ok(())
+}
+
+/// Returns the root peer review of the latest package version prior to the given version.
+fn get_previous_review(
+    args: &Arguments,
+    extension_names: &std::collections::BTreeSet<String>,
+    config: &common::config::Config,
+    tx: &common::StoreTransaction,
+) -> Result<review::Review> {
+    let root_peer =
+        peer::index::get_root(&tx)?.ok_or(format_err!("Cant find root peer. Index corrupt."))?;
+    let reviews = review::index::get(
+        &review::index::Fields {
+            package_name: Some(&args.package_name),
+            peer: Some(&root_peer),
+            ..Default::default()
+        },
+        &tx,
+    )?;
+    let reviews = super::filter_on_ecosystems(&reviews, &extension_names, &config)?;
+
+    reviews
+        .into_iter()
+        .filter(|review| is_earlier_version(&review.package.version, &args.package_version))
+        .max_by(|a, b| compare_versions(&a.package.version, &b.package.version))
+        .ok_or(format_err!(
+            "Failed to find a review of an earlier version of package: {}",
+            args.package_name
+        ))
+}
+
+/// Compare versions using semantic versioning if possible, otherwise lexicographically.
+fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    match (semver::Version::parse(a), semver::Version::parse(b)) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+fn is_earlier_version(version: &str, target_version: &str) -> bool {
+    compare_versions(version, target_version) == std::cmp::Ordering::Less
+}
+
+/// Review workspace which is removed when dropped if it did not exist beforehand.
+struct WorkspaceGuard {
+    manifest: review::workspace::Manifest,
+    created: bool,
+}
+
+impl WorkspaceGuard {
+    fn ensure(
+        package_name: &str,
+        package_version: &str,
+        registry_host_name: &str,
+        artifact_url: &url::Url,
+        max_size_mb: Option<u64>,
+    ) -> Result<Self> {
+        let created =
+            review::workspace::get_existing(&package_name, &package_version, &registry_host_name)?
+                .is_none();
+        let manifest = review::workspace::ensure(
+            &package_name,
+            &package_version,
+            &registry_host_name,
+            &artifact_url,
+            &None,
+            None,
+            false,
+            max_size_mb,
+        )?;
+        Ok(Self { manifest, created })
+    }
+}
+
+impl Drop for WorkspaceGuard {
+    fn drop(&mut self) {
+        if !self.created {
+            return;
+        }
+        if let Err(error) = review::workspace::remove(&self.manifest) {
+            log::warn!(
+                "Failed to remove temporary review workspace {}: {}",
+                self.manifest.workspace_path.display(),
+                error
+            );
+        }
+    }
+}
+
+/// Show differences between two workspace directories.
+///
+/// Output is paged (using git's configured pager) if stdout is a terminal.
+fn diff(
+    previous_workspace_directory: &std::path::PathBuf,
+    workspace_directory: &std::path::PathBuf,
+    stat: bool,
+) -> Result<()> {
+    let mut args = vec![];
+    if atty::is(atty::Stream::Stdout) {
+        args.push("--paginate");
+    }
+    args.extend(vec!["diff", "--no-index"]);
+    if stat {
+        args.push("--stat");
+    }
+
+    // Both workspaces share the package directory. Diff relative paths for readable output.
+    let package_directory = workspace_directory
+        .parent()
+        .and_then(|path| path.parent())
+        .ok_or(format_err!(
+            "Failed to find package directory of workspace: {}",
+            workspace_directory.display()
+        ))?
+        .to_path_buf();
+    let status = std::process::Command::new("git")
+        .args(args)
+        .arg(previous_workspace_directory.strip_prefix(&package_directory)?)
+        .arg(workspace_directory.strip_prefix(&package_directory)?)
+        .current_dir(&package_directory)
+        .status()?;
+
+    // Exit code 1 indicates that differences were found.
+    match status.code() {
+        Some(0) | Some(1) => Ok(()),
+        _ => Err(format_err!("Failed to diff review workspaces.")),
+    }
+}
@@ -0,0 +1,175 @@
+use anyhow::Result;
+use structopt::{self, StructOpt};
+
+use crate::common::StoreTransaction;
+use crate::peer;
+use crate::review;
+use crate::store;
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct Arguments {
+    /// Output format. Csv writes RFC 4180 CSV with a header row to stdout.
+    #[structopt(
+        long = "format",
+        name = "format",
+        default_value = "table",
+        possible_values = &["table", "csv"]
+    )]
+    pub format: Format,
+}
+
+/// Review list output format.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Format {
+    Table,
+    Csv,
+}
+
+impl std::str::FromStr for Format {
+    type Err = anyhow::Error;
+    fn from_str(input: &str) -> Result<Format, Self::Err> {
+        match input {
+            "table" => Ok(Format::Table),
+            "csv" => Ok(Format::Csv),
+            _ => Err(anyhow::format_err!(
+                "Failed to parse output format from string: {}",
+                input
+            )),
+        }
+    }
+}
+
+/// A single row of the review list.
+///
+/// Field order defines the CSV column order and must remain stable.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, serde::Serialize)]
+struct Entry {
+    registry_host_name: String,
+    package_name: String,
+    package_version: String,
+    peer_alias: String,
+    comment_count: usize,
+    worst_summary: review::Summary,
+    /// Date of the most recent review file commit. Empty for uncommitted reviews.
+    last_reviewed_date: String,
+}
+
+pub fn run_command(args: &Arguments) -> Result<()> {
+    let mut store = store::Store::from_root()?;
+    let tx = store.get_transaction()?;
+
+    let entries = get_entries(&tx)?;
+    match args.format {
+        Format::Table => print_table(&entries),
+        Format::Csv => write_csv(&entries, std::io::stdout())?,
+    }
+    Ok(())
+}
+
+fn get_entries(tx: &StoreTransaction) -> Result<Vec<Entry>> {
+    let reviews = review::index::get(&review::index::Fields::default(), &tx)?;
+
+    let mut entries = Vec::new();
+    for review in reviews {
+        let peer_branch = peer::index::get_peer_branch(&review.peer, &tx)?;
+        let last_reviewed_date = review::fs::get_last_updated_date(&review, &peer_branch)?
+            .map(|date| date.to_string())
+            .unwrap_or_default();
+        let worst_summary = review::get_summary(&review)?;
+        for registry in &review.package.registries {
+            entries.push(Entry {
+                registry_host_name: registry.host_name.clone(),
+                package_name: review.package.name.clone(),
+                package_version: review.package.version.clone(),
+                peer_alias: review.peer.alias.clone(),
+                comment_count: review.comments.len(),
+                worst_summary: worst_summary.clone(),
+                last_reviewed_date: last_reviewed_date.clone(),
+            });
+        }
+    }
+    entries.sort();
+    Ok(entries)
+}
+
+fn print_table(entries: &Vec<Entry>) {
+    if entries.is_empty() {
+        println!("No reviews found.");
+        return;
+    }
+
+    let mut table = prettytable::Table::new();
+    table.set_format(*prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(prettytable::Row::new(
+        vec![
+            "registry", "package", "version", "peer", "comments", "summary", "reviewed",
+        ]
+        .into_iter()
+        .map(prettytable::Cell::new)
+        .collect(),
+    ));
+    for entry in entries {
+        table.add_row(prettytable::Row::new(vec![
+            prettytable::Cell::new(&entry.registry_host_name),
+            prettytable::Cell::new(&entry.package_name),
+            prettytable::Cell::new(&entry.package_version),
+            prettytable::Cell::new(&entry.peer_alias),
+            prettytable::Cell::new(&entry.comment_count.to_string()),
+            prettytable::Cell::new(&entry.worst_summary.to_string()),
+            prettytable::Cell::new(&entry.last_reviewed_date),
+        ]));
+    }
+    table.printstd();
+}
+
+fn write_csv<W: std::io::Write>(entries: &Vec<Entry>, writer: W) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(writer);
+    if entries.is_empty() {
+        writer.write_record(&[
+            "registry_host_name",
+            "package_name",
+            "package_version",
+            "peer_alias",
+            "comment_count",
+            "worst_summary",
+            "last_reviewed_date",
+        ])?;
+    }
+    for entry in entries {
+        writer.serialize(entry)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_quotes_fields_and_writes_header() -> Result<()> {
+        let entries = vec![Entry {
+            registry_host_name: "pypi.org".to_string(),
+            package_name: "numpy".to_string(),
+            package_version: "1.0.0".to_string(),
+            peer_alias: "peer, \"one\"".to_string(),
+            comment_count: 2,
+            worst_summary: review::Summary::Warn,
+            last_reviewed_date: "2021-03-04".to_string(),
+        }];
+
+        let mut output = Vec::new();
+        write_csv(&entries, &mut output)?;
+
+        let expected = "registry_host_name,package_name,package_version,peer_alias,\
+            comment_count,worst_summary,last_reviewed_date\n\
+            pypi.org,numpy,1.0.0,\"peer, \"\"one\"\"\",2,warn,2021-03-04\n";
+        assert_eq!(String::from_utf8(output)?, expected);
+        Ok(())
+    }
+}
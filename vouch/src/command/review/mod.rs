@@ -12,6 +12,130 @@ use crate::registry;
 use crate::review;
 use crate::store;
 
+mod abort;
+mod audit;
+mod browse;
+mod comment;
+mod diff;
+mod list;
+pub(super) mod open;
+mod publish;
+mod template;
+mod transfer;
+mod verify;
+
+#[derive(Debug, StructOpt, Clone)]
+pub enum Subcommands {
+    /// Start or continue a package review.
+    ///
+    /// Same as `vouch review <package-name> [package-version]`. Use this form for packages which
+    /// share a name with a review subcommand.
+    Start(Arguments),
+
+    /// Reopen an ongoing review workspace in the review tool.
+    Open(open::Arguments),
+
+    /// List packages reviewed by a peer which have not been reviewed locally.
+    Audit(audit::Arguments),
+
+    /// Manage committed review comments.
+    Comment(comment::Subcommands),
+
+    /// Show changes in a package version since the latest previously reviewed version.
+    DiffToHead(diff::Arguments),
+
+    /// Cancel an ongoing review, removing its workspace and uncommitted review.
+    Abort(abort::Arguments),
+
+    /// List reviews in the index.
+    List(list::Arguments),
+
+    /// Check that committed review files match the index.
+    Verify(verify::Arguments),
+
+    /// Move reviews from one peer to another.
+    Transfer(transfer::Arguments),
+
+    /// Open a package's registry page in the browser.
+    Browse(browse::Arguments),
+
+    /// Start a review from a template of placeholder comments.
+    Template(template::Arguments),
+
+    /// Push a committed review to the remote repository without fetching peer updates.
+    Publish(publish::Arguments),
+
+    /// Start or continue a package review: vouch review <package-name> [package-version]
+    #[structopt(external_subcommand)]
+    Package(Vec<String>),
+}
+
+pub fn run_subcommand(subcommand: &Subcommands) -> Result<()> {
+    match subcommand {
+        Subcommands::Start(args) => {
+            log::info!("Running command: review start");
+            run_command(&args)?;
+        }
+        Subcommands::Open(args) => {
+            log::info!("Running command: review open");
+            open::run_command(&args)?;
+        }
+        Subcommands::Audit(args) => {
+            log::info!("Running command: review audit");
+            audit::run_command(&args)?;
+        }
+        Subcommands::Comment(subcommand) => {
+            log::info!("Running command: review comment");
+            comment::run_subcommand(&subcommand)?;
+        }
+        Subcommands::DiffToHead(args) => {
+            log::info!("Running command: review diff-to-head");
+            diff::run_command(&args)?;
+        }
+        Subcommands::Abort(args) => {
+            log::info!("Running command: review abort");
+            abort::run_command(&args)?;
+        }
+        Subcommands::List(args) => {
+            log::info!("Running command: review list");
+            list::run_command(&args)?;
+        }
+        Subcommands::Verify(args) => {
+            log::info!("Running command: review verify");
+            verify::run_command(&args)?;
+        }
+        Subcommands::Transfer(args) => {
+            log::info!("Running command: review transfer");
+            transfer::run_command(&args)?;
+        }
+        Subcommands::Browse(args) => {
+            log::info!("Running command: review browse");
+            browse::run_command(&args)?;
+        }
+        Subcommands::Template(args) => {
+            log::info!("Running command: review template");
+            template::run_command(&args)?;
+        }
+        Subcommands::Publish(args) => {
+            log::info!("Running command: review publish");
+            publish::run_command(&args)?;
+        }
+        Subcommands::Package(args) => {
+            log::info!("Running command: review");
+            run_command(&parse_package_arguments(&args))?;
+        }
+    }
+    Ok(())
+}
+
+/// Parse the arguments of `vouch review <package-name> [package-version]`.
+///
+/// Arguments which do not start with a review subcommand name are forwarded here, starting with
+/// the package name.
+fn parse_package_arguments(args: &Vec<String>) -> Arguments {
+    Arguments::from_iter(std::iter::once(&"vouch review".to_string()).chain(args.iter()))
+}
+
 #[derive(Debug, StructOpt, Clone)]
 #[structopt(
     name = "no_version",
@@ -31,9 +155,32 @@ pub struct Arguments {
     /// Example values: py, js, rs
     #[structopt(long = "extension", short = "e", name = "name")]
     pub extension_names: Option<Vec<String>>,
+
+    /// Apply the review to a range of package versions.
+    /// Example value: ">=1.2.0, <1.4.0"
+    #[structopt(long = "version-range", name = "constraint")]
+    pub version_range: Option<String>,
+
+    /// Delete and re-download the review workspace if it has been modified since download.
+    #[structopt(long = "redownload")]
+    pub redownload: bool,
+
+    /// Download the package archive even if it exceeds the configured workspace size limit.
+    #[structopt(long = "ignore-size-limit")]
+    pub ignore_size_limit: bool,
+}
+
+/// Review workspace download options.
+#[derive(Debug, Default, Clone)]
+struct DownloadOptions {
+    /// Delete and re-download the workspace if it has been modified since download.
+    pub redownload: bool,
+
+    /// Maximum package archive size. Unlimited if None.
+    pub max_size_mb: Option<u64>,
 }
 
-pub fn run_command(args: &Arguments) -> Result<()> {
+fn run_command(args: &Arguments) -> Result<()> {
     // TODO: Add gpg signing.
 
     let mut config = common::config::Config::load()?;
@@ -44,23 +191,51 @@ pub fn run_command(args: &Arguments) -> Result<()> {
     let extension_names =
         extension::manage::handle_extension_names_arg(&args.extension_names, &config)?;
 
+    if let Some(version_range) = &args.version_range {
+        semver::VersionReq::parse(&version_range)
+            .map_err(|error| format_err!("Invalid version range: {}\n{}", version_range, error))?;
+    }
+
+    let download_options = DownloadOptions {
+        redownload: args.redownload,
+        max_size_mb: if args.ignore_size_limit {
+            None
+        } else {
+            config.core.workspace_max_size_mb
+        },
+    };
+
     let mut store = store::Store::from_root()?;
     let tx = store.get_transaction()?;
 
-    let (mut review, edit_mode, workspace_manifest) = setup_review(
+    let (mut review, edit_mode, workspace_manifest) = match setup_review(
         &args.package_name,
         &args.package_version,
         &extension_names,
+        &download_options,
+        true,
         &config,
         &tx,
-    )?;
-
-    // TODO: Make use of workspace analysis in review.
-    review::workspace::analyse(&workspace_manifest.workspace_path)?;
+    )? {
+        Some(setup) => setup,
+        None => {
+            println!("Review not started.");
+            return Ok(());
+        }
+    };
+    if args.version_range.is_some() {
+        review.version_constraint = args.version_range.clone();
+    }
 
     let reviews_directory =
         review::tool::ensure_reviews_directory(&workspace_manifest.workspace_path)?;
-    let active_review_file = review::active::ensure(&review, &reviews_directory)?;
+    let maintainers = get_maintainers(&review.package, &extension_names, &config)?;
+    let active_review_file = review::active::ensure(
+        &review,
+        &maintainers,
+        &reviews_directory,
+        &config.review_tool.review_file_format,
+    )?;
 
     println!("Starting review tool.");
     review::tool::run(&workspace_manifest.workspace_path, &config)?;
@@ -83,6 +258,11 @@ pub fn run_command(args: &Arguments) -> Result<()> {
         .with_prompt("Is the review ready to share?")
         .interact()?
     {
+        let analysis = review::workspace::analyse(&workspace_manifest.workspace_path)?;
+        review.total_line_count = Some(review::workspace::get_total_line_count(&analysis));
+        review.quality_score = Some(review::QualityScore(review::quality_score(
+            &review, &analysis,
+        )));
         review::store(&review, &tx)?;
         let commit_message = get_commit_message(&review.package, &edit_mode)?;
         tx.commit(&commit_message)?;
@@ -95,6 +275,28 @@ pub fn run_command(args: &Arguments) -> Result<()> {
     Ok(())
 }
 
+/// Returns package maintainers from the primary registry. Returns an empty list on failure.
+fn get_maintainers(
+    package: &package::Package,
+    extension_names: &std::collections::BTreeSet<String>,
+    config: &common::config::Config,
+) -> Result<Vec<String>> {
+    let extensions = extension::manage::get_enabled(&extension_names, &config)?;
+    let registries_metadata =
+        match extension::search_registries(&package.name, &Some(&package.version), &extensions) {
+            Ok(registries_metadata) => registries_metadata,
+            Err(error) => {
+                log::warn!("Failed to find package maintainers: {}", error);
+                return Ok(vec![]);
+            }
+        };
+    Ok(registries_metadata
+        .into_iter()
+        .find(|registry_metadata| registry_metadata.is_primary)
+        .map(|registry_metadata| registry_metadata.maintainers)
+        .unwrap_or_default())
+}
+
 /// Parse user comments from active review file and insert into index.
 fn get_comments(
     active_review_file: &std::path::PathBuf,
@@ -104,7 +306,7 @@ fn get_comments(
 
     let mut inserted_comments = std::collections::BTreeSet::<_>::new();
     for comment in comments {
-        let comment = review::comment::index::insert(
+        let comment = review::comment::index::get_or_insert(
             &comment.path,
             &comment.summary,
             &comment.message,
@@ -124,13 +326,18 @@ enum ReviewEditMode {
 }
 
 /// Setup review for editing.
+///
+/// If confirm_ongoing is set, the user is asked how to proceed when an ongoing review with comments
+/// exists. Returns None if the user declines to continue or replace the ongoing review.
 fn setup_review(
     package_name: &str,
     package_version: &Option<String>,
     extension_names: &std::collections::BTreeSet<String>,
+    download_options: &DownloadOptions,
+    confirm_ongoing: bool,
     config: &common::config::Config,
     tx: &StoreTransaction,
-) -> Result<(review::Review, ReviewEditMode, review::workspace::Manifest)> {
+) -> Result<Option<(review::Review, ReviewEditMode, review::workspace::Manifest)>> {
     let extensions = extension::manage::get_enabled(&extension_names, &config)?;
 
     let package_version_was_given = package_version.is_some();
@@ -152,15 +359,20 @@ fn setup_review(
         println!("Found latest package version: {}", package_version);
     }
 
+    if confirm_ongoing && !confirm_ongoing_review(&package_name, &package_version)? {
+        return Ok(None);
+    }
+
     if let Some((review, workspace_manifest)) = setup_existing_review(
         &package_name,
         &package_version,
         &extension_names,
+        &download_options,
         &config,
         &tx,
     )? {
         println!("Selecting previously committed review for editing.");
-        Ok((review, ReviewEditMode::Update, workspace_manifest))
+        Ok(Some((review, ReviewEditMode::Update, workspace_manifest)))
     } else {
         println!("Editing local uncommitted review.");
         let (review, workspace_directory) = setup_new_review(
@@ -168,11 +380,67 @@ fn setup_review(
             &package_version,
             &registry_metadata,
             &extension_names,
+            &download_options,
             &config,
             &tx,
         )?;
-        Ok((review, ReviewEditMode::Create, workspace_directory))
+        Ok(Some((review, ReviewEditMode::Create, workspace_directory)))
+    }
+}
+
+/// Ask the user how to proceed if an ongoing review with comments exists for the package.
+///
+/// The ongoing review workspace is removed if the user chooses to start fresh. Returns false if
+/// the user declines both continuing and starting fresh.
+fn confirm_ongoing_review(package_name: &str, package_version: &str) -> Result<bool> {
+    let mut workspace_manifests = vec![];
+    let mut comment_count = 0;
+    for (registry_host_name, name, version) in review::workspace::get_all_existing()? {
+        if name != package_name || version != package_version {
+            continue;
+        }
+        let workspace_manifest = match review::workspace::get_existing(
+            &package_name,
+            &package_version,
+            &registry_host_name,
+        )? {
+            Some(workspace_manifest) => workspace_manifest,
+            None => continue,
+        };
+        let reviews_directory =
+            review::tool::get_reviews_directory(&workspace_manifest.workspace_path);
+        if let Some(active_review_file) = review::active::find(&reviews_directory) {
+            comment_count += review::active::parse(&active_review_file)?.len();
+        }
+        workspace_manifests.push(workspace_manifest);
+    }
+    if comment_count == 0 {
+        return Ok(true);
+    }
+
+    if dialoguer::Confirm::new()
+        .with_prompt(format!(
+            "Found ongoing review for {} {} with {} comments. Continue?",
+            package_name, package_version, comment_count
+        ))
+        .default(true)
+        .interact()?
+    {
+        return Ok(true);
+    }
+
+    if !dialoguer::Confirm::new()
+        .with_prompt("Delete existing and start fresh?")
+        .default(false)
+        .interact()?
+    {
+        return Ok(false);
+    }
+    for workspace_manifest in &workspace_manifests {
+        review::workspace::remove(&workspace_manifest)?;
     }
+    println!("Removed ongoing review workspace.");
+    Ok(true)
 }
 
 fn get_latest_package_version(
@@ -195,6 +463,7 @@ fn setup_existing_review(
     package_name: &str,
     package_version: &str,
     extension_names: &BTreeSet<String>,
+    download_options: &DownloadOptions,
     config: &common::config::Config,
     tx: &StoreTransaction,
 ) -> Result<Option<(review::Review, review::workspace::Manifest)>> {
@@ -211,6 +480,12 @@ fn setup_existing_review(
         &tx,
     )?;
 
+    // Constraint reviews for other package versions are not edited here.
+    let reviews: Vec<_> = reviews
+        .into_iter()
+        .filter(|review| review.package.version == package_version)
+        .collect();
+
     // TODO: Include filter in above get call.
 
     log::debug!("Count existing matching reviews: {}", reviews.len());
@@ -239,6 +514,10 @@ fn setup_existing_review(
         &review.package.version,
         &registry.host_name,
         &registry.artifact_url,
+        &None,
+        None,
+        download_options.redownload,
+        download_options.max_size_mb,
     )?;
     Ok(Some((review.clone(), workspace_manifest)))
 }
@@ -259,16 +538,8 @@ fn filter_on_ecosystems(
     target_extension_names: &BTreeSet<String>,
     config: &common::config::Config,
 ) -> Result<Vec<review::Review>> {
-    // Find registry host names which are handled by the given extensions.
-    let enabled_registries: std::collections::BTreeSet<String> = config
-        .extensions
-        .registries
-        .iter()
-        .filter(|(_registry_host_name, extension_name)| {
-            target_extension_names.contains(extension_name.as_str())
-        })
-        .map(|(registry_host_name, _extension_name)| registry_host_name.clone())
-        .collect();
+    let enabled_registries =
+        extension::manage::get_enabled_registry_host_names(&target_extension_names, &config)?;
 
     Ok(reviews
         .iter()
@@ -326,6 +597,7 @@ fn setup_new_review(
     package_version: &str,
     registry_metadata: &Option<vouch_lib::extension::RegistryPackageMetadata>,
     extension_names: &BTreeSet<String>,
+    download_options: &DownloadOptions,
     config: &common::config::Config,
     tx: &StoreTransaction,
 ) -> Result<(review::Review, review::workspace::Manifest)> {
@@ -335,6 +607,7 @@ fn setup_new_review(
         &package_version,
         &registry_metadata,
         &extensions,
+        &download_options,
         &tx,
     )?;
     let review = get_insert_empty_review(&package, &tx)?;
@@ -348,6 +621,7 @@ fn ensure_package_setup(
     package_version: &str,
     registry_metadata: &Option<vouch_lib::extension::RegistryPackageMetadata>,
     extensions: &Vec<Box<dyn vouch_lib::extension::Extension>>,
+    download_options: &DownloadOptions,
     tx: &common::StoreTransaction,
 ) -> Result<(package::Package, review::workspace::Manifest)> {
     // Don't query registries again if results already found.
@@ -368,6 +642,14 @@ fn ensure_package_setup(
 
     // Get package version from found metadata incase given version was unknown.
     let package_version = registry_metadata.package_version.clone();
+    let registry_extension = extensions
+        .iter()
+        .find(|extension| {
+            extension
+                .registries()
+                .contains(&registry_metadata.registry_host_name)
+        })
+        .map(|extension| extension.as_ref());
 
     let package = package::index::get(
         &package::index::Fields {
@@ -391,6 +673,10 @@ fn ensure_package_setup(
                 &package.version,
                 &registry.host_name,
                 &registry.artifact_url,
+                &review::workspace::ArtifactChecksum::from_registry_metadata(&registry_metadata),
+                registry_extension,
+                download_options.redownload,
+                download_options.max_size_mb,
             )?;
             (package, workspace_manifest)
         }
@@ -406,6 +692,10 @@ fn ensure_package_setup(
                 &package_version,
                 &registry.host_name,
                 &registry.artifact_url,
+                &review::workspace::ArtifactChecksum::from_registry_metadata(&registry_metadata),
+                registry_extension,
+                download_options.redownload,
+                download_options.max_size_mb,
             )?;
             let package = package::index::insert(
                 &package_name,
@@ -430,6 +720,7 @@ fn get_insert_empty_review(
         &std::collections::BTreeSet::<review::comment::Comment>::new(),
         &root_peer,
         &package,
+        &None,
         &tx,
     )?;
     Ok(unset_review)
@@ -449,3 +740,31 @@ fn get_commit_message(package: &package::Package, editing_mode: &ReviewEditMode)
         package_version = package.version,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_package_name_without_subcommand_starts_review() {
+        let subcommand = Subcommands::from_iter(&["review", "d3", "4.10.0", "--redownload"]);
+        let args = match subcommand {
+            Subcommands::Package(args) => parse_package_arguments(&args),
+            other => panic!("Unexpected subcommand: {:?}", other),
+        };
+        assert_eq!(args.package_name, "d3");
+        assert_eq!(args.package_version, Some("4.10.0".to_string()));
+        assert!(args.redownload);
+    }
+
+    #[test]
+    fn test_start_subcommand_reviews_package_named_like_subcommand() {
+        let subcommand = Subcommands::from_iter(&["review", "start", "open"]);
+        let args = match subcommand {
+            Subcommands::Start(args) => args,
+            other => panic!("Unexpected subcommand: {:?}", other),
+        };
+        assert_eq!(args.package_name, "open");
+        assert_eq!(args.package_version, None);
+    }
+}
@@ -0,0 +1,105 @@
+use anyhow::{format_err, Result};
+use structopt::{self, StructOpt};
+
+use crate::common;
+use crate::extension;
+use crate::review;
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct Arguments {
+    /// Package name.
+    #[structopt(name = "package-name")]
+    pub package_name: String,
+
+    /// Package version.
+    #[structopt(name = "package-version")]
+    pub package_version: String,
+
+    /// Registry host name. Required if multiple ongoing reviews match.
+    /// Example value: pypi.org
+    #[structopt(long = "registry", name = "host-name")]
+    pub registry_host_name: Option<String>,
+}
+
+pub fn run_command(args: &Arguments) -> Result<()> {
+    let mut config = common::config::Config::load()?;
+    extension::manage::update_config(&mut config)?;
+    review::tool::check_install(&mut config)?;
+    let config = config;
+
+    let workspace_manifest = get_workspace_manifest(&args)?.ok_or(format_err!(
+        "No ongoing review found for package: {name} {version}\n\
+        Start a new review with: vouch review {name} {version}",
+        name = args.package_name,
+        version = args.package_version
+    ))?;
+
+    println!("Starting review tool.");
+    review::tool::run(&workspace_manifest.workspace_path, &config)?;
+    println!(
+        "Review tool closed. Save the review with: vouch review {} {}",
+        args.package_name, args.package_version
+    );
+    Ok(())
+}
+
+/// Find the ongoing review workspace for the given package.
+fn get_workspace_manifest(args: &Arguments) -> Result<Option<review::workspace::Manifest>> {
+    if let Some(registry_host_name) = &args.registry_host_name {
+        return review::workspace::get_existing(
+            &args.package_name,
+            &args.package_version,
+            &registry_host_name,
+        );
+    }
+
+    let mut matches = vec![];
+    for registry_host_name in get_ongoing_registry_host_names()? {
+        if let Some(workspace_manifest) = review::workspace::get_existing(
+            &args.package_name,
+            &args.package_version,
+            &registry_host_name,
+        )? {
+            matches.push((registry_host_name, workspace_manifest));
+        }
+    }
+
+    if matches.len() > 1 {
+        let registry_host_names: Vec<_> = matches.into_iter().map(|(name, _)| name).collect();
+        return Err(format_err!(
+            "Found multiple matching ongoing reviews.\n\
+            Please specify a registry using --registry.\n\
+            Matching registries: {}",
+            registry_host_names.join(", ")
+        ));
+    }
+    Ok(matches
+        .into_iter()
+        .next()
+        .map(|(_registry_host_name, workspace_manifest)| workspace_manifest))
+}
+
+/// Returns the registry host names of all ongoing reviews.
+fn get_ongoing_registry_host_names() -> Result<Vec<String>> {
+    let paths = common::fs::DataPaths::new()?;
+    if !paths.ongoing_reviews_directory.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let mut registry_host_names = vec![];
+    for entry in std::fs::read_dir(&paths.ongoing_reviews_directory)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        if let Some(name) = entry.file_name().to_str() {
+            registry_host_names.push(name.to_string());
+        }
+    }
+    Ok(registry_host_names)
+}
@@ -0,0 +1,85 @@
+use anyhow::{format_err, Result};
+use structopt::{self, StructOpt};
+
+use crate::common;
+use crate::peer;
+use crate::review;
+use crate::store;
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct Arguments {
+    /// Package name.
+    #[structopt(name = "package-name")]
+    pub package_name: String,
+
+    /// Package version.
+    #[structopt(name = "package-version")]
+    pub package_version: String,
+}
+
+pub fn run_command(args: &Arguments) -> Result<()> {
+    let mut store = store::Store::from_root()?;
+    let tx = store.get_transaction()?;
+    let root_peer =
+        peer::index::get_root(&tx)?.ok_or(format_err!("Cant find root peer. Index corrupt."))?;
+
+    let reviews = review::index::get(
+        &review::index::Fields {
+            package_name: Some(&args.package_name),
+            package_version: Some(&args.package_version),
+            peer: Some(&root_peer),
+            ..Default::default()
+        },
+        &tx,
+    )?;
+    let review_found = reviews
+        .iter()
+        .any(|review| review.package.version == args.package_version);
+    if !review_found {
+        return Err(format_err!(
+            "No review found for package: {} {}",
+            args.package_name,
+            args.package_version
+        ));
+    }
+    let committed_review_found = reviews.iter().any(|review| {
+        review.package.version == args.package_version && !review.comments.is_empty()
+    });
+    if !committed_review_found {
+        return Err(format_err!(
+            "Review has no comments: {} {}\n\
+            Complete the review before publishing.",
+            args.package_name,
+            args.package_version
+        ));
+    }
+
+    if !common::fs::is_remote_repo_setup()? {
+        println!(
+            "Remote repository not specified.\n\
+        Specify using `vouch config core.root-git-url <url>`.\n\
+        Not pushing local changes."
+        );
+        return Ok(());
+    }
+
+    println!("Pushing local changes to remote repository.");
+    match common::fs::git_push_root()? {
+        common::fs::PushStatus::Pushed => {
+            println!(
+                "Published review: {} {}",
+                args.package_name, args.package_version
+            );
+            Ok(())
+        }
+        common::fs::PushStatus::NonFastForward => Err(format_err!(
+            "Remote repository contains changes which are not present locally.\n\
+            Run `vouch sync` first, then publish again."
+        )),
+    }
+}
@@ -0,0 +1,90 @@
+use anyhow::{format_err, Result};
+use structopt::{self, StructOpt};
+
+use crate::common;
+use crate::extension;
+use crate::review;
+use crate::store;
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct Arguments {
+    /// Package name.
+    #[structopt(name = "package-name")]
+    pub package_name: String,
+
+    /// Package version.
+    #[structopt(name = "package-version")]
+    pub package_version: String,
+
+    /// Specify an extension for handling the package.
+    /// Example values: py, js, rs
+    #[structopt(long = "extension", short = "e", name = "name")]
+    pub extension_names: Option<Vec<String>>,
+}
+
+pub fn run_command(args: &Arguments) -> Result<()> {
+    let mut config = common::config::Config::load()?;
+    extension::manage::update_config(&mut config)?;
+    review::tool::check_install(&mut config)?;
+    let config = config;
+
+    let extension_names =
+        extension::manage::handle_extension_names_arg(&args.extension_names, &config)?;
+    let template = review::template::Template::load()?;
+
+    let mut store = store::Store::from_root()?;
+    let tx = store.get_transaction()?;
+
+    let (review, _edit_mode, workspace_manifest) = super::setup_review(
+        &args.package_name,
+        &Some(args.package_version.clone()),
+        &extension_names,
+        &super::DownloadOptions {
+            redownload: false,
+            max_size_mb: config.core.workspace_max_size_mb,
+        },
+        false,
+        &config,
+        &tx,
+    )?
+    .ok_or(format_err!("Failed to setup review."))?;
+
+    let reviews_directory =
+        review::tool::ensure_reviews_directory(&workspace_manifest.workspace_path)?;
+    if review::active::find(&reviews_directory).is_some() {
+        return Err(format_err!(
+            "Review already in progress for package: {name} {version}\n\
+            Reopen the review with: vouch review open {name} {version}",
+            name = args.package_name,
+            version = args.package_version
+        ));
+    }
+
+    let analysis = review::workspace::analyse(&workspace_manifest.workspace_path)?;
+    let maintainers = super::get_maintainers(&review.package, &extension_names, &config)?;
+    let description =
+        review::template::get_description(&review, &maintainers, &analysis, &template);
+    // Comments from a previously committed review are retained.
+    let mut comments = review.comments.clone();
+    comments.extend(review::template::get_comments(&analysis, &template));
+    let active_review_file = review::active::create(
+        &description,
+        &comments,
+        &reviews_directory,
+        &config.review_tool.review_file_format,
+    )?;
+    println!("Review template written: {}", active_review_file.display());
+
+    println!("Starting review tool.");
+    review::tool::run(&workspace_manifest.workspace_path, &config)?;
+    println!(
+        "Review tool closed. Save the review with: vouch review {} {}",
+        args.package_name, args.package_version
+    );
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use anyhow::{format_err, Result};
+use structopt::{self, StructOpt};
+
+use crate::common::StoreTransaction;
+use crate::peer;
+use crate::review;
+use crate::store;
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct Arguments {
+    /// Alias or git repository URL of the peer which currently holds the reviews.
+    #[structopt(long = "from-peer", name = "from-peer")]
+    pub from_peer: String,
+
+    /// Alias or git repository URL of the peer which receives the reviews.
+    #[structopt(long = "to-peer", name = "to-peer")]
+    pub to_peer: String,
+
+    /// Transfer only reviews of the given package.
+    #[structopt(long = "package-name", name = "package-name")]
+    pub package_name: Option<String>,
+
+    /// Transfer only reviews of the given package version.
+    #[structopt(
+        long = "package-version",
+        name = "package-version",
+        requires("package-name")
+    )]
+    pub package_version: Option<String>,
+}
+
+pub fn run_command(args: &Arguments) -> Result<()> {
+    let mut store = store::Store::from_root()?;
+    let tx = store.get_transaction()?;
+
+    let source_peer = get_peer(&args.from_peer, &tx)?;
+    let target_peer = get_peer(&args.to_peer, &tx)?;
+    check_peers(&source_peer, &target_peer)?;
+
+    let reviews: Vec<_> = review::index::get(
+        &review::index::Fields {
+            package_name: args.package_name.as_deref(),
+            package_version: args.package_version.as_deref(),
+            peer: Some(&source_peer),
+            ..Default::default()
+        },
+        &tx,
+    )?
+    .into_iter()
+    // Constraint reviews for other package versions are not transferred.
+    .filter(|review| match &args.package_version {
+        Some(package_version) => &review.package.version == package_version,
+        None => true,
+    })
+    .collect();
+    if reviews.is_empty() {
+        println!("No matching reviews found for peer: {}", source_peer.alias);
+        return Ok(());
+    }
+
+    let source_peer_branch = peer::index::get_peer_branch(&source_peer, &tx)?;
+    let target_peer_branch = peer::index::get_peer_branch(&target_peer, &tx)?;
+
+    let mut commit_messages = vec![];
+    for review in reviews {
+        if has_review(&review.package, &target_peer, &tx)? {
+            println!(
+                "Skipping {} {}: target peer already has a review.",
+                review.package.name, review.package.version
+            );
+            continue;
+        }
+
+        // Insert before removing so that the package and comments remain referenced.
+        review::index::insert(
+            &review.comments,
+            &target_peer,
+            &review.package,
+            &review.version_constraint,
+            &tx,
+        )?;
+        review::index::remove(
+            &review::index::Fields {
+                id: Some(review.id),
+                ..Default::default()
+            },
+            &tx,
+        )?;
+        review::fs::transfer(&review.package, &source_peer_branch, &target_peer_branch)?;
+
+        commit_messages.push(format!(
+            "Transferring review: {name} {version} ({source} -> {target})",
+            name = review.package.name,
+            version = review.package.version,
+            source = source_peer.alias,
+            target = target_peer.alias
+        ));
+    }
+
+    if commit_messages.is_empty() {
+        println!("No reviews transferred.");
+        return Ok(());
+    }
+    tx.commit(&commit_messages.join("\n"))?;
+    for message in commit_messages {
+        println!("{}", message);
+    }
+    Ok(())
+}
+
+/// Ensure that reviews can be transferred between the given peers.
+///
+/// Non-root peer stores are replaced on sync. Transfers between two non-root peers would
+/// therefore be lost, so either the source or the target must be the root peer.
+fn check_peers(source_peer: &peer::Peer, target_peer: &peer::Peer) -> Result<()> {
+    if source_peer.id == target_peer.id {
+        return Err(format_err!("Source and target peers are the same."));
+    }
+    if !source_peer.is_root() && !target_peer.is_root() {
+        return Err(format_err!(
+            "Reviews can only be transferred to or from the root peer."
+        ));
+    }
+    Ok(())
+}
+
+fn get_peer(value: &str, tx: &StoreTransaction) -> Result<peer::Peer> {
+    peer::index::get_by_alias_or_git_url(&value, &tx)?
+        .ok_or(format_err!("Failed to find peer: {}", value))
+}
+
+/// Returns true if the peer has a review of the given package.
+fn has_review(
+    package: &crate::package::Package,
+    peer: &peer::Peer,
+    tx: &StoreTransaction,
+) -> Result<bool> {
+    Ok(review::index::get(
+        &review::index::Fields {
+            package_name: Some(&package.name),
+            package_version: Some(&package.version),
+            peer: Some(&peer),
+            ..Default::default()
+        },
+        &tx,
+    )?
+    .into_iter()
+    .any(|review| review.package.id == package.id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_peer(id: crate::common::index::ID, alias: &str) -> Result<peer::Peer> {
+        Ok(peer::Peer {
+            id,
+            alias: alias.to_string(),
+            git_url: std::convert::TryFrom::try_from(
+                format!("https://github.com/{}/reviews", alias).as_str(),
+            )?,
+            parent_id: if id == 1 { None } else { Some(1) },
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_check_peers() -> Result<()> {
+        let root_peer = get_peer(1, "root")?;
+        let peer = get_peer(2, "peer")?;
+        let other_peer = get_peer(3, "other_peer")?;
+
+        assert!(check_peers(&peer, &root_peer).is_ok());
+        assert!(check_peers(&root_peer, &peer).is_ok());
+        assert!(check_peers(&root_peer, &root_peer).is_err());
+        assert!(check_peers(&peer, &other_peer).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use anyhow::{format_err, Result};
+use structopt::{self, StructOpt};
+
+use crate::peer;
+use crate::review;
+use crate::store;
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct Arguments {
+    /// Alias or git repository URL of the peer whose review files are verified.
+    /// Defaults to the root peer.
+    #[structopt(long = "peer", name = "peer")]
+    pub peer: Option<String>,
+
+    /// Update the index to match the review files.
+    #[structopt(long = "repair")]
+    pub repair: bool,
+}
+
+pub fn run_command(args: &Arguments) -> Result<()> {
+    let mut store = store::Store::from_root()?;
+    let tx = store.get_transaction()?;
+
+    let target_peer = match &args.peer {
+        Some(value) => peer::index::get_by_alias_or_git_url(&value, &tx)?
+            .ok_or(format_err!("Failed to find peer: {}", value))?,
+        None => {
+            peer::index::get_root(&tx)?.ok_or(format_err!("Cant find root peer. Index corrupt."))?
+        }
+    };
+
+    let verifications = review::verify::verify(&target_peer, &tx)?;
+    for verification in verifications.iter().filter(|v| !v.is_pass()) {
+        print_verification(&verification);
+    }
+
+    let failed_count = verifications.iter().filter(|v| !v.is_pass()).count();
+    println!(
+        "Review files verified: {total}, failed: {failed}",
+        total = verifications.len(),
+        failed = failed_count
+    );
+    if failed_count == 0 {
+        return Ok(());
+    }
+
+    if !args.repair {
+        println!("Use --repair to update the index from the review files.");
+        return Ok(());
+    }
+
+    let mut repaired_count = 0;
+    for verification in verifications.iter().filter(|v| v.is_repairable()) {
+        review::verify::repair(&verification, &target_peer, &tx)?;
+        repaired_count += 1;
+    }
+    tx.commit_index()?;
+    println!("Reviews repaired in index: {}", repaired_count);
+    if repaired_count < failed_count {
+        println!(
+            "Review files which could not be used for repair: {}",
+            failed_count - repaired_count
+        );
+    }
+    Ok(())
+}
+
+fn print_verification(verification: &review::verify::FileVerification) {
+    println!("{}", verification.relative_path.display());
+    for error in &verification.errors {
+        println!("    error: {}", error);
+    }
+    for discrepancy in &verification.discrepancies {
+        println!("    {}", discrepancy.field);
+        println!("    - index: {}", discrepancy.index_value);
+        println!("    + file:  {}", discrepancy.file_value);
+    }
+}
@@ -66,7 +66,7 @@ fn setup_git_repository(
             &paths
                 .root_directory
                 .parent()
-                .unwrap_or(&std::path::PathBuf::from(r"/"))
+                .unwrap_or(&paths.root_directory)
                 .to_path_buf(),
         )?;
         setup_top_level_peers(&paths)?;
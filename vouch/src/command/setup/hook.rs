@@ -0,0 +1,155 @@
+//! Git pre-commit hook management.
+//!
+//! Git for Windows runs hooks using its bundled POSIX shell, so the same hook script is
+//! used on all platforms. Only Unix systems require the executable permission bit.
+
+use anyhow::{Context, Result};
+
+static BLOCK_START: &str = "# >>> vouch pre-commit hook >>>";
+static BLOCK_END: &str = "# <<< vouch pre-commit hook <<<";
+static SHEBANG: &str = "#!/bin/sh";
+
+fn get_block() -> String {
+    format!(
+        "{start}\n\
+        # Lines between these markers are generated by vouch.\n\
+        # Remove them with: vouch setup --remove-hook\n\
+        vouch check --format minimal --require-all-pass || exit $?\n\
+        {end}\n",
+        start = BLOCK_START,
+        end = BLOCK_END
+    )
+}
+
+/// Install the vouch check invocation into the current repository's pre-commit hook.
+///
+/// Existing hooks are appended to rather than overwritten.
+pub fn install() -> Result<()> {
+    let hook_path = get_hook_path()?;
+    let contents = if hook_path.is_file() {
+        Some(std::fs::read_to_string(&hook_path)?)
+    } else {
+        None
+    };
+
+    let contents = match add_block(contents.as_deref()) {
+        Some(contents) => contents,
+        None => {
+            println!("Pre-commit hook already installed: {}", hook_path.display());
+            return Ok(());
+        }
+    };
+
+    if let Some(parent) = hook_path.parent() {
+        std::fs::create_dir_all(&parent)?;
+    }
+    std::fs::write(&hook_path, contents).context(format!(
+        "Can't write pre-commit hook: {}",
+        hook_path.display()
+    ))?;
+    set_executable(&hook_path)?;
+    println!("Pre-commit hook installed: {}", hook_path.display());
+    Ok(())
+}
+
+/// Remove vouch generated lines from the current repository's pre-commit hook.
+///
+/// The hook file is removed if nothing else remains.
+pub fn remove() -> Result<()> {
+    let hook_path = get_hook_path()?;
+    if !hook_path.is_file() {
+        println!("Pre-commit hook not found.");
+        return Ok(());
+    }
+
+    let contents = std::fs::read_to_string(&hook_path)?;
+    let contents = match remove_block(&contents) {
+        Some(contents) => contents,
+        None => {
+            println!("Pre-commit hook does not contain vouch lines.");
+            return Ok(());
+        }
+    };
+
+    if contents.trim().is_empty() || contents.trim() == SHEBANG {
+        std::fs::remove_file(&hook_path)?;
+    } else {
+        std::fs::write(&hook_path, contents)?;
+    }
+    println!("Pre-commit hook removed: {}", hook_path.display());
+    Ok(())
+}
+
+/// Returns the pre-commit hook path of the Git repository containing the working directory.
+fn get_hook_path() -> Result<std::path::PathBuf> {
+    let working_directory = std::env::current_dir()?;
+    let hooks_directory =
+        crate::common::fs::git_output(vec!["rev-parse", "--git-path", "hooks"], &working_directory)
+            .context(format!(
+                "Working directory is not within a Git repository: {}",
+                working_directory.display()
+            ))?;
+    let hooks_directory = hooks_directory.trim();
+    Ok(working_directory.join(hooks_directory).join("pre-commit"))
+}
+
+/// Returns hook contents with the vouch block appended. Returns None if already present.
+fn add_block(contents: Option<&str>) -> Option<String> {
+    let mut contents = match contents {
+        Some(contents) => {
+            if contents.contains(BLOCK_START) {
+                return None;
+            }
+            contents.to_string()
+        }
+        None => format!("{}\n", SHEBANG),
+    };
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    contents.push_str(&get_block());
+    Some(contents)
+}
+
+/// Returns hook contents without the vouch block. Returns None if the block is not present.
+fn remove_block(contents: &str) -> Option<String> {
+    let start = contents.find(BLOCK_START)?;
+    let end = start + contents[start..].find(BLOCK_END)? + BLOCK_END.len();
+    let end = if contents[end..].starts_with('\n') {
+        end + 1
+    } else {
+        end
+    };
+    Some(format!("{}{}", &contents[..start], &contents[end..]))
+}
+
+#[cfg(unix)]
+fn set_executable(path: &std::path::PathBuf) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut permissions = std::fs::metadata(&path)?.permissions();
+    permissions.set_mode(permissions.mode() | 0o755);
+    std::fs::set_permissions(&path, permissions)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &std::path::PathBuf) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_and_remove_block_preserves_existing_hook() {
+        let existing = "#!/bin/sh\ncargo fmt -- --check\n";
+        let installed = add_block(Some(existing)).unwrap();
+        assert!(installed.starts_with(existing));
+        assert!(installed.contains("vouch check"));
+        assert_eq!(add_block(Some(&installed)), None);
+
+        assert_eq!(remove_block(&installed), Some(existing.to_string()));
+        assert_eq!(remove_block(existing), None);
+    }
+}
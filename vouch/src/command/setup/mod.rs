@@ -4,6 +4,7 @@ use structopt::{self, StructOpt};
 
 use crate::store;
 mod fs;
+mod hook;
 
 #[derive(Debug, StructOpt, Clone)]
 #[structopt(
@@ -19,9 +20,24 @@ pub struct Arguments {
     /// Force setup cleanly. Removes existing local setup data.
     #[structopt(long = "force", short = "f")]
     pub force: bool,
+
+    /// Install a Git pre-commit hook in the current repository which runs vouch check.
+    #[structopt(long = "install-hook", conflicts_with = "remove-hook")]
+    pub install_hook: bool,
+
+    /// Remove vouch lines from the current repository's Git pre-commit hook.
+    #[structopt(long = "remove-hook")]
+    pub remove_hook: bool,
 }
 
 pub fn run_command(args: &Arguments) -> Result<()> {
+    if args.install_hook {
+        return hook::install();
+    }
+    if args.remove_hook {
+        return hook::remove();
+    }
+
     fs::setup(&args.git_url, args.force)?;
 
     let mut store = store::Store::from_root()?;
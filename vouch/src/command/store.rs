@@ -0,0 +1,135 @@
+use anyhow::Result;
+use structopt::{self, StructOpt};
+
+use crate::store;
+
+#[derive(Debug, StructOpt, Clone)]
+pub enum Subcommands {
+    /// Backup local reviews and index to an archive.
+    Backup(BackupArguments),
+
+    /// Restore local reviews and index from a backup archive.
+    Restore(RestoreArguments),
+
+    /// Show local data size and index health.
+    Status(StatusArguments),
+}
+
+pub fn run_subcommand(subcommand: &Subcommands) -> Result<()> {
+    match subcommand {
+        Subcommands::Backup(args) => {
+            log::info!("Running command: store backup");
+            backup(&args)?;
+        }
+        Subcommands::Restore(args) => {
+            log::info!("Running command: store restore");
+            restore(&args)?;
+        }
+        Subcommands::Status(args) => {
+            log::info!("Running command: store status");
+            status(&args)?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct BackupArguments {
+    /// Backup archive output path.
+    /// Default: vouch-backup-{date}.tar.gz
+    #[structopt(name = "output-path", parse(from_os_str))]
+    pub output_path: Option<std::path::PathBuf>,
+}
+
+fn backup(args: &BackupArguments) -> Result<()> {
+    let output_path = match &args.output_path {
+        Some(output_path) => output_path.clone(),
+        None => std::env::current_dir()?.join(store::backup::get_default_file_name()),
+    };
+    store::backup::create(&output_path)?;
+    println!("Backup created: {}", output_path.display());
+    Ok(())
+}
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct RestoreArguments {
+    /// Backup archive path.
+    #[structopt(name = "backup-path", parse(from_os_str))]
+    pub backup_path: std::path::PathBuf,
+
+    /// Overwrite existing data directory contents.
+    #[structopt(long = "force")]
+    pub force: bool,
+}
+
+fn restore(args: &RestoreArguments) -> Result<()> {
+    let manifest = store::backup::restore(&args.backup_path, args.force)?;
+
+    let vouch_version = env!("CARGO_PKG_VERSION");
+    if manifest.vouch_version != vouch_version {
+        println!(
+            "WARNING: backup created by vouch version {}, current version is {}.",
+            manifest.vouch_version, vouch_version
+        );
+    }
+    println!("Backup restored: {}", args.backup_path.display());
+    Ok(())
+}
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct StatusArguments {
+    /// Output status as JSON.
+    #[structopt(long = "json")]
+    pub json: bool,
+}
+
+fn status(args: &StatusArguments) -> Result<()> {
+    let mut store = store::Store::from_root()?;
+    let tx = store.get_transaction()?;
+    let status = store::status::get(&tx)?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&status)?);
+        return Ok(());
+    }
+
+    println!("data-directory-size: {} bytes", status.data_directory_size);
+    println!("index-file-size: {} bytes", status.index_file_size);
+    for (table, count) in &status.table_row_counts {
+        println!("{}-rows: {}", table, count);
+    }
+    println!("ongoing-workspaces: {}", status.ongoing_workspaces);
+    println!("peer-submodules: {}", status.peer_submodules);
+    if status.integrity_errors.is_empty() {
+        println!("integrity-check: OK");
+    } else {
+        println!("integrity-check:");
+        for error in &status.integrity_errors {
+            println!("  {}", error);
+        }
+    }
+    if status.reference_errors.is_empty() {
+        println!("reference-check: OK");
+    } else {
+        println!("reference-check:");
+        for error in &status.reference_errors {
+            println!("  {}", error);
+        }
+    }
+    Ok(())
+}
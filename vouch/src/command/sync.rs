@@ -12,28 +12,45 @@ use crate::store;
     no_version,
     global_settings = &[structopt::clap::AppSettings::DisableVersion]
 )]
-pub struct Arguments {}
+pub struct Arguments {
+    /// Verify GPG signatures of fetched peer commits. Peers with invalid signatures are skipped.
+    #[structopt(long = "verify-signatures")]
+    pub verify_signatures: bool,
+
+    /// Merge peer updates with invalid commit signatures.
+    #[structopt(long = "trust-unsigned", requires("verify-signatures"))]
+    pub trust_unsigned: bool,
+}
 
-pub fn run_command(_args: &Arguments) -> Result<()> {
+pub fn run_command(args: &Arguments) -> Result<()> {
     let mut store = store::Store::from_root()?;
     let mut tx = store.get_transaction()?;
 
     let root_peer =
         peer::index::get_root(&tx)?.ok_or(format_err!("Cant find root peer. Index corrupt."))?;
-    let root_children = peer::index::get(
+    let root_children: Vec<_> = peer::index::get(
         &peer::index::Fields {
             parent_id: Some(root_peer.id),
             ..Default::default()
         },
         &tx,
-    )?;
+    )?
+    .into_iter()
+    .collect();
     let found_peers = !root_children.is_empty();
 
+    // Fetch concurrently (network only), then merge sequentially (index writes).
+    let fetch_results = fetch_updates(&root_children)?;
+
     let mut updated_peers = Vec::new();
-    for peer in root_children {
-        if let Some(peer) = update_peer(&peer, &mut tx)? {
-            updated_peers.push(peer);
+    for (peer, update_found) in root_children.iter().zip(fetch_results.into_iter()) {
+        if !update_found {
+            continue;
         }
+        if args.verify_signatures && !verify_signatures(&peer, args.trust_unsigned)? {
+            continue;
+        }
+        updated_peers.push(merge_peer_update(&peer, &mut tx)?);
     }
 
     if updated_peers.is_empty() {
@@ -49,20 +66,67 @@ pub fn run_command(_args: &Arguments) -> Result<()> {
     Ok(())
 }
 
-/// Update peer.
+/// Fetch peer updates in parallel.
+///
+/// Returns a flag for each peer indicating whether an update was found.
+fn fetch_updates(peers: &Vec<peer::Peer>) -> Result<Vec<bool>> {
+    crossbeam_utils::thread::scope(|s| {
+        let mut threads = Vec::new();
+        for peer in peers {
+            threads.push(s.spawn(move |_| {
+                println!("Fetching: {}", peer.git_url.to_string());
+                peer::fs::fetch_update(&peer)
+            }));
+        }
+        let mut result = Vec::new();
+        for thread in threads {
+            result.push(thread.join().unwrap()?);
+        }
+        Ok(result)
+    })
+    .unwrap()
+}
+
+/// Verify fetched peer commit signatures. Unsigned commits are reported as warnings.
 ///
-/// Return Some(peer) if updated, otherwise None.
-fn update_peer(peer: &peer::Peer, tx: &mut common::StoreTransaction) -> Result<Option<peer::Peer>> {
-    println!("Fetching: {}", peer.git_url.to_string());
-    let update_found = peer::fs::fetch_update(&peer, tx)?;
-    if !update_found {
-        return Ok(None);
+/// Returns false if the peer update should not be merged.
+fn verify_signatures(peer: &peer::Peer, trust_unsigned: bool) -> Result<bool> {
+    let mut invalid_found = false;
+    for (commit_id, status) in peer::fs::verify_update_signatures(&peer)? {
+        match status {
+            peer::fs::SignatureStatus::Valid => {}
+            peer::fs::SignatureStatus::Unsigned => {
+                println!(
+                    "Warning: unsigned commit {} from peer: {}",
+                    commit_id, peer.alias
+                );
+            }
+            peer::fs::SignatureStatus::Invalid => {
+                println!(
+                    "Error: invalid signature on commit {} from peer: {}",
+                    commit_id, peer.alias
+                );
+                invalid_found = true;
+            }
+        }
+    }
+
+    if invalid_found && !trust_unsigned {
+        println!(
+            "Skipping update from peer: {}\n\
+            Use --trust-unsigned to merge regardless.",
+            peer.alias
+        );
+        return Ok(false);
     }
+    Ok(true)
+}
 
+/// Merge fetched peer update into the local store and index.
+fn merge_peer_update(peer: &peer::Peer, tx: &mut common::StoreTransaction) -> Result<peer::Peer> {
     remove_index_peer_subtree(&peer, tx)?;
     peer::fs::merge_update(&peer, tx)?;
-    let peer = merge_updated_peer_subtree(&peer, tx)?;
-    Ok(Some(peer))
+    merge_updated_peer_subtree(&peer, tx)
 }
 
 fn get_commit_message(updated_peers: &Vec<peer::Peer>) -> Result<String> {
@@ -91,7 +155,9 @@ fn update_remote() -> Result<()> {
     }
 
     println!("Pushing local changes to remote repository.");
-    common::fs::git_push_root()?;
+    if common::fs::git_push_root()? == common::fs::PushStatus::NonFastForward {
+        println!("Remote repository contains changes which are not present locally. Not pushing.");
+    }
 
     let config = crate::common::config::Config::load()?;
     if config.core.notify_vouch_public_sync {
@@ -137,11 +203,13 @@ fn merge_updated_peer_subtree(
     // Get an up-to-date copy of the root peer.
     let mut root_peer =
         peer::index::get_root(&tx)?.ok_or(format_err!("Cant find root peer. Index corrupt."))?;
-    let peer = peer::index::insert(&peer.alias, &peer.git_url, Some(&mut root_peer), &tx)?;
+    let max_depth = peer.max_depth;
+    let mut peer = peer::index::insert(&peer.alias, &peer.git_url, Some(&mut root_peer), &tx)?;
+    peer::index::set_max_depth(&mut peer, max_depth, &tx)?;
 
     let mut peer_store = store::Store::from_peer(&vec![root_peer.clone(), peer.clone()])?;
     let peer_index_tx = peer_store.get_transaction()?;
-    store::index::merge(&peer.git_url, &peer_index_tx, &tx)?;
+    store::index::merge(&peer.git_url, peer.max_depth, &peer_index_tx, &tx)?;
 
     Ok(peer)
 }
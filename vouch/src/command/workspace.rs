@@ -0,0 +1,673 @@
+use anyhow::{format_err, Result};
+use structopt::{self, StructOpt};
+
+use crate::review;
+
+#[derive(Debug, StructOpt, Clone)]
+pub enum Subcommands {
+    /// List ongoing review workspaces.
+    List(ListArguments),
+
+    /// Reopen an ongoing review workspace in the review tool.
+    Open(super::review::open::Arguments),
+
+    /// Remove a review workspace. Uncommitted reviews are left in the index.
+    Remove(RemoveArguments),
+
+    /// Remove review workspaces in bulk.
+    Clean(CleanArguments),
+
+    /// Show review workspace line counts.
+    Analyse(AnalyseArguments),
+
+    /// Show the review workspace file tree with line counts.
+    Tree(TreeArguments),
+}
+
+pub fn run_subcommand(subcommand: &Subcommands) -> Result<()> {
+    match subcommand {
+        Subcommands::List(args) => {
+            log::info!("Running command: workspace list");
+            list(&args)?;
+        }
+        Subcommands::Open(args) => {
+            log::info!("Running command: workspace open");
+            super::review::open::run_command(&args)?;
+        }
+        Subcommands::Remove(args) => {
+            log::info!("Running command: workspace remove");
+            remove(&args)?;
+        }
+        Subcommands::Clean(args) => {
+            log::info!("Running command: workspace clean");
+            clean(&args)?;
+        }
+        Subcommands::Analyse(args) => {
+            log::info!("Running command: workspace analyse");
+            analyse(&args)?;
+        }
+        Subcommands::Tree(args) => {
+            log::info!("Running command: workspace tree");
+            tree(&args)?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct ListArguments {}
+
+fn list(_args: &ListArguments) -> Result<()> {
+    let workspaces = review::workspace::get_all_existing()?;
+    if workspaces.is_empty() {
+        println!("No ongoing review workspaces found.");
+        return Ok(());
+    }
+
+    let mut table = prettytable::Table::new();
+    table.set_format(*prettytable::format::consts::FORMAT_CLEAN);
+    table.set_titles(get_row(&["registry", "name", "version"]));
+    for (registry_host_name, package_name, package_version) in workspaces {
+        table.add_row(get_row(&[
+            &registry_host_name,
+            &package_name,
+            &package_version,
+        ]));
+    }
+    table.printstd();
+    Ok(())
+}
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct RemoveArguments {
+    /// Package name.
+    #[structopt(name = "package-name")]
+    pub package_name: String,
+
+    /// Package version.
+    #[structopt(name = "package-version")]
+    pub package_version: String,
+
+    /// Registry host name. Workspaces for all registries are removed if not given.
+    /// Example value: pypi.org
+    #[structopt(long = "registry", name = "host-name")]
+    pub registry_host_name: Option<String>,
+
+    /// Remove workspaces with ongoing review comments without confirmation.
+    #[structopt(long = "force")]
+    pub force: bool,
+}
+
+fn remove(args: &RemoveArguments) -> Result<()> {
+    let mut found = false;
+    for workspace in review::workspace::get_all_existing()? {
+        if !is_matching_workspace(
+            &workspace,
+            &args.package_name,
+            &args.package_version,
+            &args.registry_host_name,
+        ) {
+            continue;
+        }
+        let (registry_host_name, package_name, package_version) = workspace;
+        remove_workspace(
+            &registry_host_name,
+            &package_name,
+            &package_version,
+            args.force,
+        )?;
+        found = true;
+    }
+
+    if !found {
+        return Err(format_err!(
+            "No review workspace found for package: {} {}",
+            args.package_name,
+            args.package_version
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct CleanArguments {
+    /// Only remove workspaces which have not been set up or updated within this number of days.
+    #[structopt(long = "older-than", name = "days")]
+    pub older_than: Option<u64>,
+
+    /// Remove workspaces with ongoing review comments without confirmation.
+    #[structopt(long = "force")]
+    pub force: bool,
+}
+
+fn clean(args: &CleanArguments) -> Result<()> {
+    let min_age = args
+        .older_than
+        .map(|days| std::time::Duration::from_secs(days * 24 * 60 * 60));
+
+    let mut count_removed = 0;
+    for (registry_host_name, package_name, package_version) in
+        review::workspace::get_all_existing()?
+    {
+        if let Some(min_age) = min_age {
+            let workspace_manifest = match review::workspace::get_existing(
+                &package_name,
+                &package_version,
+                &registry_host_name,
+            )? {
+                Some(workspace_manifest) => workspace_manifest,
+                None => continue,
+            };
+            let modified = std::fs::metadata(&workspace_manifest.manifest_path)?.modified()?;
+            if !is_older_than(&modified, &min_age) {
+                continue;
+            }
+        }
+        if remove_workspace(
+            &registry_host_name,
+            &package_name,
+            &package_version,
+            args.force,
+        )? {
+            count_removed += 1;
+        }
+    }
+    println!("Removed {} review workspaces.", count_removed);
+    Ok(())
+}
+
+/// Returns true if the given modification time is at least the minimum age ago.
+fn is_older_than(modified: &std::time::SystemTime, min_age: &std::time::Duration) -> bool {
+    modified.elapsed().unwrap_or_default() >= *min_age
+}
+
+/// Returns true if the workspace (registry host name, package name, package version) belongs to
+/// the package. Any registry matches if the registry host name is not given.
+fn is_matching_workspace(
+    workspace: &(String, String, String),
+    package_name: &str,
+    package_version: &str,
+    registry_host_name: &Option<String>,
+) -> bool {
+    let (workspace_registry_host_name, workspace_package_name, workspace_package_version) =
+        workspace;
+    workspace_package_name == package_name
+        && workspace_package_version == package_version
+        && registry_host_name
+            .as_ref()
+            .map_or(true, |name| name == workspace_registry_host_name)
+}
+
+/// Remove a review workspace.
+///
+/// If the workspace holds ongoing review comments, the user must confirm removal unless forced.
+/// Returns true if the workspace was removed.
+fn remove_workspace(
+    registry_host_name: &str,
+    package_name: &str,
+    package_version: &str,
+    force: bool,
+) -> Result<bool> {
+    let workspace_manifest = match review::workspace::get_existing(
+        &package_name,
+        &package_version,
+        &registry_host_name,
+    )? {
+        Some(workspace_manifest) => workspace_manifest,
+        None => return Ok(false),
+    };
+
+    let comment_count = get_active_review_comment_count(&workspace_manifest.workspace_path)?;
+    if comment_count > 0
+        && !force
+        && !dialoguer::Confirm::new()
+            .with_prompt(format!(
+                "Review workspace {}/{}/{} has {} ongoing review comments. Remove?",
+                registry_host_name, package_name, package_version, comment_count
+            ))
+            .default(false)
+            .interact()?
+    {
+        println!(
+            "Skipped review workspace: {}/{}/{}",
+            registry_host_name, package_name, package_version
+        );
+        return Ok(false);
+    }
+
+    review::workspace::remove(&workspace_manifest)?;
+    println!(
+        "Removed review workspace: {}/{}/{}",
+        registry_host_name, package_name, package_version
+    );
+    Ok(true)
+}
+
+/// Returns the number of comments in the workspace's active review file.
+fn get_active_review_comment_count(workspace_path: &std::path::PathBuf) -> Result<usize> {
+    let reviews_directory = review::tool::get_reviews_directory(&workspace_path);
+    Ok(match review::active::find(&reviews_directory) {
+        Some(active_review_file) => review::active::parse(&active_review_file)?.len(),
+        None => 0,
+    })
+}
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct AnalyseArguments {
+    /// Package name.
+    #[structopt(name = "package-name")]
+    pub package_name: String,
+
+    /// Package version.
+    #[structopt(name = "package-version")]
+    pub package_version: String,
+
+    /// Registry host name. Required if workspaces for multiple registries exist.
+    /// Example value: pypi.org
+    #[structopt(long = "registry", name = "host-name")]
+    pub registry_host_name: Option<String>,
+}
+
+fn analyse(args: &AnalyseArguments) -> Result<()> {
+    let workspace_manifest = get_workspace_manifest(
+        &args.package_name,
+        &args.package_version,
+        &args.registry_host_name,
+    )?;
+
+    let analysis = review::workspace::analyse(&workspace_manifest.workspace_path)?;
+    let mut table = prettytable::Table::new();
+    table.set_format(*prettytable::format::consts::FORMAT_CLEAN);
+    table.set_titles(get_row(&["path", "lines"]));
+    for (path, path_analysis) in &analysis {
+        if path.as_os_str().is_empty() {
+            continue;
+        }
+        let path = match path_analysis.path_type {
+            crate::common::fs::PathType::File => path.display().to_string(),
+            crate::common::fs::PathType::Directory => format!("{}/", path.display()),
+        };
+        table.add_row(prettytable::Row::new(vec![
+            prettytable::Cell::new(&path),
+            prettytable::Cell::new_align(
+                &path_analysis.line_count.to_string(),
+                prettytable::format::Alignment::RIGHT,
+            ),
+        ]));
+    }
+    table.printstd();
+    println!(
+        "Total: {} lines",
+        review::workspace::get_total_line_count(&analysis)
+    );
+    Ok(())
+}
+
+/// Returns the manifest of the single review workspace matching the package.
+fn get_workspace_manifest(
+    package_name: &str,
+    package_version: &str,
+    registry_host_name: &Option<String>,
+) -> Result<review::workspace::Manifest> {
+    let registry_host_name = get_workspace_registry_host_name(
+        &review::workspace::get_all_existing()?,
+        &package_name,
+        &package_version,
+        &registry_host_name,
+    )?;
+    review::workspace::get_existing(&package_name, &package_version, &registry_host_name)?
+        .ok_or(format_err!("Failed to read review workspace manifest."))
+}
+
+/// Returns the registry host name of the single workspace matching the package.
+fn get_workspace_registry_host_name(
+    workspaces: &Vec<(String, String, String)>,
+    package_name: &str,
+    package_version: &str,
+    registry_host_name: &Option<String>,
+) -> Result<String> {
+    let registry_host_names: Vec<_> = workspaces
+        .iter()
+        .filter(|workspace| {
+            is_matching_workspace(
+                &workspace,
+                &package_name,
+                &package_version,
+                &registry_host_name,
+            )
+        })
+        .map(|(workspace_registry_host_name, _, _)| workspace_registry_host_name.clone())
+        .collect();
+    match registry_host_names.as_slice() {
+        [] => Err(format_err!(
+            "No review workspace found for package: {} {}",
+            package_name,
+            package_version
+        )),
+        [registry_host_name] => Ok(registry_host_name.clone()),
+        _ => Err(format_err!(
+            "Found multiple matching review workspaces.\n\
+            Please specify a registry using --registry.\n\
+            Matching registries: {}",
+            registry_host_names.join(", ")
+        )),
+    }
+}
+
+#[derive(Debug, StructOpt, Clone)]
+#[structopt(
+    name = "no_version",
+    no_version,
+    global_settings = &[structopt::clap::AppSettings::DisableVersion]
+)]
+pub struct TreeArguments {
+    /// Package name.
+    #[structopt(name = "package-name")]
+    pub package_name: String,
+
+    /// Package version.
+    #[structopt(name = "package-version")]
+    pub package_version: String,
+
+    /// Registry host name. Required if workspaces for multiple registries exist.
+    /// Example value: pypi.org
+    #[structopt(long = "registry", name = "host-name")]
+    pub registry_host_name: Option<String>,
+
+    /// Maximum directory depth to display.
+    #[structopt(long = "max-depth", name = "depth", default_value = "3")]
+    pub max_depth: usize,
+}
+
+/// Directory names which are omitted from the workspace tree, in addition to hidden directories.
+static TREE_EXCLUDED_DIRECTORIES: &[&str] = &["__pycache__", "node_modules"];
+
+fn tree(args: &TreeArguments) -> Result<()> {
+    let workspace_manifest = get_workspace_manifest(
+        &args.package_name,
+        &args.package_version,
+        &args.registry_host_name,
+    )?;
+    let workspace_path = &workspace_manifest.workspace_path;
+
+    let reviews_directory = review::tool::get_reviews_directory(&workspace_path);
+    let mut commented_paths = std::collections::BTreeSet::new();
+    if let Some(active_review_file) = review::active::find(&reviews_directory) {
+        for comment in review::active::parse(&active_review_file)? {
+            commented_paths.insert(comment.display_path(&workspace_path, false)?);
+        }
+    }
+
+    let tree = FileTree {
+        workspace_path: workspace_path.clone(),
+        analysis: review::workspace::analyse(&workspace_path)?,
+        commented_paths,
+        max_depth: args.max_depth,
+    };
+    for line in tree.get_lines()? {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+/// Review workspace file tree diagram.
+struct FileTree {
+    workspace_path: std::path::PathBuf,
+    analysis: review::workspace::Analysis,
+    /// Workspace relative paths which have review comments.
+    commented_paths: std::collections::BTreeSet<std::path::PathBuf>,
+    max_depth: usize,
+}
+
+impl FileTree {
+    /// Returns tree diagram lines, starting with the workspace directory.
+    fn get_lines(&self) -> Result<Vec<String>> {
+        let mut lines = vec![self.get_label(&std::path::PathBuf::new(), true)];
+        self.add_lines(&self.workspace_path, "", 1, &mut lines)?;
+        Ok(lines)
+    }
+
+    fn add_lines(
+        &self,
+        directory: &std::path::PathBuf,
+        prefix: &str,
+        depth: usize,
+        lines: &mut Vec<String>,
+    ) -> Result<()> {
+        if depth > self.max_depth {
+            return Ok(());
+        }
+        let entries: Vec<_> = walkdir::WalkDir::new(&directory)
+            .min_depth(1)
+            .max_depth(1)
+            .sort_by(|a, b| a.file_name().cmp(b.file_name()))
+            .into_iter()
+            .filter_entry(|entry| !is_excluded(&entry))
+            .collect::<std::result::Result<_, _>>()?;
+
+        for (index, entry) in entries.iter().enumerate() {
+            let is_last = index + 1 == entries.len();
+            let (connector, child_prefix) = if is_last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            let is_directory = entry.file_type().is_dir();
+            let relative_path = entry
+                .path()
+                .strip_prefix(&self.workspace_path)?
+                .to_path_buf();
+            lines.push(format!(
+                "{}{}{}",
+                prefix,
+                connector,
+                self.get_label(&relative_path, is_directory)
+            ));
+            if is_directory {
+                let child_prefix = format!("{}{}", prefix, child_prefix);
+                self.add_lines(&entry.path().to_path_buf(), &child_prefix, depth + 1, lines)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the path label with its line count and, if commented, a review indicator.
+    fn get_label(&self, relative_path: &std::path::PathBuf, is_directory: bool) -> String {
+        let name = match relative_path.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => self.workspace_path.display().to_string(),
+        };
+        let mut label = if is_directory && relative_path.file_name().is_some() {
+            format!("{}/", name)
+        } else {
+            name
+        };
+        if let Some(path_analysis) = self.analysis.get(relative_path) {
+            label.push_str(&format!(" ({} lines)", path_analysis.line_count));
+        }
+        if self.commented_paths.contains(relative_path) {
+            label.push_str(" [✓]");
+        }
+        label
+    }
+}
+
+/// Returns true if the entry is a hidden or excluded directory.
+fn is_excluded(entry: &walkdir::DirEntry) -> bool {
+    if !entry.file_type().is_dir() {
+        return false;
+    }
+    let name = entry.file_name().to_string_lossy();
+    name.starts_with('.') || TREE_EXCLUDED_DIRECTORIES.contains(&name.as_ref())
+}
+
+fn get_row(values: &[&str]) -> prettytable::Row {
+    prettytable::Row::new(
+        values
+            .iter()
+            .map(|value| prettytable::Cell::new(value))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fs::PathType;
+
+    fn get_path_analysis(
+        path_type: PathType,
+        line_count: usize,
+    ) -> review::workspace::PathAnalysis {
+        review::workspace::PathAnalysis {
+            path_type,
+            line_count,
+        }
+    }
+
+    fn get_workspace(registry_host_name: &str, package_version: &str) -> (String, String, String) {
+        (
+            registry_host_name.to_string(),
+            "numpy".to_string(),
+            package_version.to_string(),
+        )
+    }
+
+    #[test]
+    fn test_is_matching_workspace() {
+        let workspace = get_workspace("pypi.org", "1.18.5");
+        assert!(is_matching_workspace(&workspace, "numpy", "1.18.5", &None));
+        assert!(is_matching_workspace(
+            &workspace,
+            "numpy",
+            "1.18.5",
+            &Some("pypi.org".to_string())
+        ));
+        assert!(!is_matching_workspace(
+            &workspace,
+            "numpy",
+            "1.18.5",
+            &Some("npmjs.com".to_string())
+        ));
+        assert!(!is_matching_workspace(&workspace, "numpy", "1.18.4", &None));
+        assert!(!is_matching_workspace(&workspace, "scipy", "1.18.5", &None));
+    }
+
+    #[test]
+    fn test_get_workspace_registry_host_name() -> Result<()> {
+        let workspaces = vec![
+            get_workspace("pypi.org", "1.18.5"),
+            get_workspace("npmjs.com", "1.18.5"),
+            get_workspace("pypi.org", "1.18.4"),
+        ];
+        assert_eq!(
+            get_workspace_registry_host_name(&workspaces, "numpy", "1.18.4", &None)?,
+            "pypi.org"
+        );
+        assert_eq!(
+            get_workspace_registry_host_name(
+                &workspaces,
+                "numpy",
+                "1.18.5",
+                &Some("npmjs.com".to_string())
+            )?,
+            "npmjs.com"
+        );
+        // Ambiguous without a registry.
+        assert!(get_workspace_registry_host_name(&workspaces, "numpy", "1.18.5", &None).is_err());
+        assert!(get_workspace_registry_host_name(&workspaces, "numpy", "1.18.3", &None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_older_than() {
+        let day = std::time::Duration::from_secs(24 * 60 * 60);
+        let modified = std::time::SystemTime::now() - 2 * day;
+        assert!(is_older_than(&modified, &day));
+        assert!(!is_older_than(&modified, &(3 * day)));
+        assert!(!is_older_than(&std::time::SystemTime::now(), &day));
+    }
+
+    #[test]
+    fn test_get_active_review_comment_count() -> Result<()> {
+        let tmp_dir = tempdir::TempDir::new("vouch_test_get_active_review_comment_count")?;
+        let workspace_path = tmp_dir.path().to_path_buf();
+        assert_eq!(get_active_review_comment_count(&workspace_path)?, 0);
+
+        let reviews_directory = review::tool::get_reviews_directory(&workspace_path);
+        std::fs::create_dir_all(&reviews_directory)?;
+        std::fs::write(
+            reviews_directory.join("local.review"),
+            r#"{
+                "title": "local",
+                "description": "",
+                "isPrimary": true,
+                "comments": [
+                    {"summary": "warn", "file": "setup.py", "description": "a", "selection": null},
+                    {"summary": "pass", "file": "setup.py", "description": "b", "selection": null}
+                ]
+            }"#,
+        )?;
+        assert_eq!(get_active_review_comment_count(&workspace_path)?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_tree_lines() -> Result<()> {
+        let tmp_dir = tempdir::TempDir::new("vouch_test_file_tree_lines")?;
+        let workspace_path = tmp_dir.path().to_path_buf();
+        std::fs::create_dir_all(workspace_path.join("src").join("lib"))?;
+        std::fs::create_dir(workspace_path.join(".git"))?;
+        std::fs::create_dir(workspace_path.join("node_modules"))?;
+        std::fs::write(workspace_path.join("setup.py"), "a\nb\n")?;
+        std::fs::write(workspace_path.join("src").join("main.py"), "a\n")?;
+        std::fs::write(
+            workspace_path.join("src").join("lib").join("util.py"),
+            "a\n",
+        )?;
+
+        let tree = FileTree {
+            workspace_path: workspace_path.clone(),
+            analysis: maplit::btreemap! {
+                std::path::PathBuf::from("") => get_path_analysis(PathType::Directory, 4),
+                std::path::PathBuf::from("setup.py") => get_path_analysis(PathType::File, 2),
+                std::path::PathBuf::from("src") => get_path_analysis(PathType::Directory, 2),
+                std::path::PathBuf::from("src/lib") => get_path_analysis(PathType::Directory, 1),
+                std::path::PathBuf::from("src/main.py") => get_path_analysis(PathType::File, 1),
+                std::path::PathBuf::from("src/lib/util.py") => get_path_analysis(PathType::File, 1),
+            },
+            commented_paths: maplit::btreeset! {std::path::PathBuf::from("setup.py")},
+            max_depth: 2,
+        };
+        let result = tree.get_lines()?;
+        let expected = vec![
+            format!("{} (4 lines)", workspace_path.display()),
+            "├── setup.py (2 lines) [✓]".to_string(),
+            "└── src/ (2 lines)".to_string(),
+            "    ├── lib/ (1 lines)".to_string(),
+            "    └── main.py (1 lines)".to_string(),
+        ];
+        assert_eq!(result, expected);
+        Ok(())
+    }
+}
@@ -2,9 +2,8 @@ use crate::common::config::common;
 use anyhow::{format_err, Context, Result};
 use std::convert::TryFrom;
 
-#[derive(
-    Debug, Clone, Default, Ord, PartialOrd, Eq, PartialEq, serde::Serialize, serde::Deserialize,
-)]
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct Core {
     #[serde(rename = "root-git-url")]
     pub root_git_url: Option<crate::common::GitUrl>,
@@ -14,6 +13,55 @@ pub struct Core {
 
     #[serde(rename = "api-key")]
     pub api_key: String,
+
+    /// Base URL of the Vouch API used to retrieve official reviews.
+    #[serde(rename = "api-base-url", default = "get_default_api_base_url")]
+    pub api_base_url: String,
+
+    /// Number of days after which a review is considered stale.
+    #[serde(
+        rename = "review-freshness-days",
+        default = "get_default_review_freshness_days"
+    )]
+    pub review_freshness_days: u32,
+
+    /// Number of times a failed download is retried on transient errors.
+    #[serde(
+        rename = "download-max-retries",
+        default = "get_default_download_max_retries"
+    )]
+    pub download_max_retries: u32,
+
+    /// Maximum package archive size in megabytes for review workspace downloads.
+    /// No limit is applied if unset.
+    #[serde(rename = "workspace-max-size-mb", default)]
+    pub workspace_max_size_mb: Option<u64>,
+}
+
+impl Default for Core {
+    fn default() -> Self {
+        Self {
+            root_git_url: None,
+            notify_vouch_public_sync: false,
+            api_key: String::new(),
+            api_base_url: get_default_api_base_url(),
+            review_freshness_days: get_default_review_freshness_days(),
+            download_max_retries: get_default_download_max_retries(),
+            workspace_max_size_mb: None,
+        }
+    }
+}
+
+fn get_default_api_base_url() -> String {
+    "https://api.vouch.dev/v1".to_string()
+}
+
+fn get_default_review_freshness_days() -> u32 {
+    365
+}
+
+fn get_default_download_max_retries() -> u32 {
+    2
 }
 
 fn get_regex() -> Result<regex::Regex> {
@@ -55,6 +103,35 @@ pub fn set(core: &mut Core, name: &str, value: &str) -> Result<()> {
             core.api_key = value.to_string();
             Ok(())
         }
+        "api-base-url" => {
+            url::Url::parse(&value).context(format!("Failed to parse URL: {}", value))?;
+            core.api_base_url = value.to_string();
+            Ok(())
+        }
+        "review-freshness-days" => {
+            core.review_freshness_days = value
+                .parse()
+                .context(format!("Failed to parse number of days: {}", value))?;
+            Ok(())
+        }
+        "download-max-retries" => {
+            core.download_max_retries = value
+                .parse()
+                .context(format!("Failed to parse number of retries: {}", value))?;
+            Ok(())
+        }
+        "workspace-max-size-mb" => {
+            core.workspace_max_size_mb = if value.is_empty() {
+                None
+            } else {
+                Some(
+                    value
+                        .parse()
+                        .context(format!("Failed to parse size in megabytes: {}", value))?,
+                )
+            };
+            Ok(())
+        }
         _ => Err(format_err!(name_error_message.clone())),
     }
 }
@@ -77,6 +154,13 @@ pub fn get(core: &Core, name: &str) -> Result<String> {
         }),
         "notify-vouch-public-sync" => Ok(core.notify_vouch_public_sync.to_string()),
         "api-key" => Ok(core.api_key.clone()),
+        "api-base-url" => Ok(core.api_base_url.clone()),
+        "review-freshness-days" => Ok(core.review_freshness_days.to_string()),
+        "download-max-retries" => Ok(core.download_max_retries.to_string()),
+        "workspace-max-size-mb" => Ok(match &core.workspace_max_size_mb {
+            Some(size) => size.to_string(),
+            None => "".to_string(),
+        }),
         _ => Err(format_err!(name_error_message.clone())),
     }
 }
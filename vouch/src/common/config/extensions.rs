@@ -1,15 +1,41 @@
 use crate::common::config::common;
-use anyhow::{format_err, Result};
+use anyhow::{format_err, Context, Result};
 
-#[derive(
-    Debug, Clone, Default, Ord, PartialOrd, Eq, PartialEq, serde::Serialize, serde::Deserialize,
-)]
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct Extensions {
     pub enabled: std::collections::BTreeMap<String, bool>,
     pub registries: std::collections::BTreeMap<String, String>,
+
+    /// Maximum number of seconds to wait on an extension call.
+    #[serde(rename = "timeout-seconds", default = "get_default_timeout_seconds")]
+    pub timeout_seconds: u64,
+
+    /// Release tag names of extensions which are excluded from updates.
+    #[serde(rename = "pinned-versions", default)]
+    pub pinned_versions: std::collections::BTreeMap<String, String>,
+}
+
+impl Default for Extensions {
+    fn default() -> Self {
+        Self {
+            enabled: std::collections::BTreeMap::new(),
+            registries: std::collections::BTreeMap::new(),
+            timeout_seconds: get_default_timeout_seconds(),
+            pinned_versions: std::collections::BTreeMap::new(),
+        }
+    }
+}
+
+fn get_default_timeout_seconds() -> u64 {
+    30
 }
 
 fn get_regex() -> Result<regex::Regex> {
+    Ok(regex::Regex::new(r"extensions\.(.*)")?)
+}
+
+fn get_enabled_regex() -> Result<regex::Regex> {
     Ok(regex::Regex::new(r"extensions\.enabled\.(.*)")?)
 }
 
@@ -20,7 +46,14 @@ pub fn is_match(name: &str) -> Result<bool> {
 pub fn set(extensions: &mut Extensions, name: &str, value: &str) -> Result<()> {
     let name_error_message = format!("Unknown setting field name: {}", name);
 
-    let captures = get_regex()?
+    if name == "extensions.timeout-seconds" {
+        extensions.timeout_seconds = value
+            .parse()
+            .context(format!("Failed to parse number of seconds: {}", value))?;
+        return Ok(());
+    }
+
+    let captures = get_enabled_regex()?
         .captures(name)
         .ok_or(format_err!(name_error_message.clone()))?;
     let extension_name = captures
@@ -41,7 +74,11 @@ pub fn set(extensions: &mut Extensions, name: &str, value: &str) -> Result<()> {
 pub fn get(extensions: &Extensions, name: &str) -> Result<String> {
     let name_error_message = format!("Unknown setting field name: {}", name);
 
-    let captures = get_regex()?
+    if name == "extensions.timeout-seconds" {
+        return Ok(extensions.timeout_seconds.to_string());
+    }
+
+    let captures = get_enabled_regex()?
         .captures(name)
         .ok_or(format_err!(name_error_message.clone()))?;
     let extension_name = captures
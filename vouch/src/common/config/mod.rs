@@ -3,11 +3,16 @@ use anyhow::{format_err, Context, Result};
 mod common;
 mod core;
 mod extensions;
+pub mod project;
 mod review_tool;
 
+/// Setting field names whose values are masked when listed.
+pub static SECRET_FIELD_NAMES: &[&str] = &["core.api-key", "review-tool.gerrit-http-password"];
+
 #[derive(
     Debug, Clone, Default, Ord, PartialOrd, Eq, PartialEq, serde::Serialize, serde::Deserialize,
 )]
+#[serde(default)]
 pub struct Config {
     pub core: core::Core,
 
@@ -75,6 +80,100 @@ impl Config {
             Err(format_err!(name_error_message.clone()))
         };
     }
+
+    /// Set a setting field to its default value. Returns the default value.
+    pub fn reset(&mut self, name: &str) -> Result<String> {
+        let value = Config::default().get(&name)?;
+        self.set(&name, &value)?;
+        Ok(value)
+    }
+
+    /// Returns all setting field names and values.
+    pub fn list(&self) -> Result<Vec<(String, String)>> {
+        let mut fields = Vec::new();
+        flatten_fields("", &serde_json::to_value(&self)?, &mut fields);
+        Ok(fields)
+    }
+
+    /// Combine two configs. Settings from overlay take precedence over base.
+    ///
+    /// Maps are merged with overlay keys winning and sequences are concatenated. Overlay settings
+    /// left at their default value are treated as unset, so that a partial overlay file only
+    /// replaces the settings which it specifies.
+    pub fn merge(base: Config, overlay: Config) -> Result<Config> {
+        let overlay = remove_default_values(
+            serde_yaml::to_value(&overlay)?,
+            &serde_yaml::to_value(Config::default())?,
+        );
+        let merged = merge_values(serde_yaml::to_value(&base)?, overlay);
+        Ok(serde_yaml::from_value(merged)?)
+    }
+}
+
+fn merge_values(base: serde_yaml::Value, overlay: serde_yaml::Value) -> serde_yaml::Value {
+    match (base, overlay) {
+        (serde_yaml::Value::Mapping(mut base), serde_yaml::Value::Mapping(overlay)) => {
+            for (key, overlay_value) in overlay {
+                let value = match base.remove(&key) {
+                    Some(base_value) => merge_values(base_value, overlay_value),
+                    None => overlay_value,
+                };
+                base.insert(key, value);
+            }
+            serde_yaml::Value::Mapping(base)
+        }
+        (serde_yaml::Value::Sequence(mut base), serde_yaml::Value::Sequence(overlay)) => {
+            base.extend(overlay);
+            serde_yaml::Value::Sequence(base)
+        }
+        (_, overlay) => overlay,
+    }
+}
+
+/// Remove mapping entries which are equal to the entry at the same position in default.
+fn remove_default_values(
+    value: serde_yaml::Value,
+    default: &serde_yaml::Value,
+) -> serde_yaml::Value {
+    match (value, default) {
+        (serde_yaml::Value::Mapping(value), serde_yaml::Value::Mapping(default)) => {
+            let mut mapping = serde_yaml::Mapping::new();
+            for (key, value) in value {
+                match default.get(&key) {
+                    Some(default_value) if *default_value == value => {}
+                    Some(default_value) => {
+                        mapping.insert(key, remove_default_values(value, default_value));
+                    }
+                    None => {
+                        mapping.insert(key, value);
+                    }
+                }
+            }
+            serde_yaml::Value::Mapping(mapping)
+        }
+        (value, _) => value,
+    }
+}
+
+/// Collect leaf values of nested settings using dot separated field names.
+fn flatten_fields(prefix: &str, value: &serde_json::Value, fields: &mut Vec<(String, String)>) {
+    let value = match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                let name = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten_fields(&name, &value, fields);
+            }
+            return;
+        }
+        serde_json::Value::Null => "".to_string(),
+        serde_json::Value::String(value) => value.clone(),
+        value => value.to_string(),
+    };
+    fields.push((prefix.to_string(), value));
 }
 
 impl std::fmt::Display for Config {
@@ -86,3 +185,107 @@ impl std::fmt::Display for Config {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_uses_setting_field_names() -> Result<()> {
+        let mut config = Config::default();
+        config.core.api_key = "secret".to_string();
+        config
+            .extensions
+            .enabled
+            .insert("vouch-py".to_string(), true);
+
+        let fields = config.list()?;
+        assert!(fields.contains(&("core.api-key".to_string(), "secret".to_string())));
+        assert!(fields.contains(&("core.root-git-url".to_string(), "".to_string())));
+        assert!(fields.contains(&(
+            "extensions.enabled.vouch-py".to_string(),
+            "true".to_string()
+        )));
+        assert!(fields.contains(&(
+            "review-tool.review-file-format".to_string(),
+            "json".to_string()
+        )));
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_restores_default_value() -> Result<()> {
+        let mut config = Config::default();
+        config.core.review_freshness_days = 30;
+        config.review_tool.gerrit_url = "https://gerrit.example.com".to_string();
+
+        assert_eq!(config.reset("core.review-freshness-days")?, "365");
+        assert_eq!(config.reset("review-tool.gerrit-url")?, "");
+        assert_eq!(config, Config::default());
+        Ok(())
+    }
+
+    mod merge {
+        use super::*;
+
+        #[test]
+        fn test_overlay_scalar_takes_precedence() -> Result<()> {
+            let mut base = Config::default();
+            base.core.api_key = "base".to_string();
+            base.core.download_max_retries = 5;
+            let mut overlay = Config::default();
+            overlay.core.api_key = "overlay".to_string();
+            overlay.core.download_max_retries = 1;
+
+            let result = Config::merge(base, overlay)?;
+            assert_eq!(result.core.api_key, "overlay");
+            assert_eq!(result.core.download_max_retries, 1);
+            Ok(())
+        }
+
+        #[test]
+        fn test_overlay_default_scalar_keeps_base() -> Result<()> {
+            let mut base = Config::default();
+            base.core.api_key = "base".to_string();
+            base.core.review_freshness_days = 30;
+            let overlay: Config = serde_yaml::from_str("review-tool:\n  name: vscode")?;
+
+            let result = Config::merge(base, overlay)?;
+            assert_eq!(result.core.api_key, "base");
+            assert_eq!(result.core.review_freshness_days, 30);
+            assert_eq!(result.review_tool.name, "vscode");
+            Ok(())
+        }
+
+        #[test]
+        fn test_maps_merged_with_overlay_keys_winning() -> Result<()> {
+            let mut base = Config::default();
+            base.extensions.enabled.insert("py".to_string(), true);
+            base.extensions.enabled.insert("js".to_string(), true);
+            let mut overlay = Config::default();
+            overlay.extensions.enabled.insert("js".to_string(), false);
+            overlay.extensions.enabled.insert("rs".to_string(), true);
+
+            let result = Config::merge(base, overlay)?;
+            let expected = maplit::btreemap! {
+                "js".to_string() => false,
+                "py".to_string() => true,
+                "rs".to_string() => true,
+            };
+            assert_eq!(result.extensions.enabled, expected);
+            Ok(())
+        }
+
+        #[test]
+        fn test_sequences_concatenated() -> Result<()> {
+            let base = serde_yaml::from_str("ignore: [a, b]\nname: base")?;
+            let overlay = serde_yaml::from_str("ignore: [c]\nname: overlay")?;
+
+            let result = merge_values(base, overlay);
+            let expected: serde_yaml::Value =
+                serde_yaml::from_str("ignore: [a, b, c]\nname: overlay")?;
+            assert_eq!(result, expected);
+            Ok(())
+        }
+    }
+}
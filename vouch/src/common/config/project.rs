@@ -0,0 +1,50 @@
+use anyhow::{Context, Result};
+
+static PROJECT_CONFIG_FILE_NAME: &str = ".vouch.yaml";
+
+/// Project level settings. Loaded from a `.vouch.yaml` file.
+#[derive(
+    Debug, Clone, Default, Ord, PartialOrd, Eq, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+pub struct ProjectConfig {
+    #[serde(default)]
+    pub check: Check,
+
+    /// Settings which take precedence over the user config within the project.
+    #[serde(default)]
+    pub settings: Option<super::Config>,
+}
+
+#[derive(
+    Debug, Clone, Default, Ord, PartialOrd, Eq, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+pub struct Check {
+    /// Package name glob patterns to omit from check reports.
+    #[serde(default)]
+    pub ignore: Vec<String>,
+}
+
+impl ProjectConfig {
+    /// Load the project config file found in the given directory or its closest parent.
+    pub fn load(working_directory: &std::path::PathBuf) -> Result<Option<Self>> {
+        let path = match find_file(&working_directory) {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+        log::debug!("Loading project config: {}", path.display());
+
+        let file = std::fs::File::open(&path)?;
+        let reader = std::io::BufReader::new(file);
+        Ok(Some(serde_yaml::from_reader(reader).context(format!(
+            "Failed to parse project config: {}",
+            path.display()
+        ))?))
+    }
+}
+
+fn find_file(working_directory: &std::path::PathBuf) -> Option<std::path::PathBuf> {
+    working_directory
+        .ancestors()
+        .map(|directory| directory.join(PROJECT_CONFIG_FILE_NAME))
+        .find(|path| path.is_file())
+}
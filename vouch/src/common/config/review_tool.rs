@@ -1,13 +1,53 @@
-use anyhow::{format_err, Result};
+use anyhow::{format_err, Context, Result};
 
-#[derive(
-    Debug, Clone, Default, Ord, PartialOrd, Eq, PartialEq, serde::Serialize, serde::Deserialize,
-)]
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct ReviewTool {
     pub name: String,
 
     #[serde(rename = "install-check")]
     pub install_check: bool,
+
+    /// Active review file format. Values: json, yaml
+    #[serde(
+        rename = "review-file-format",
+        default = "get_default_review_file_format"
+    )]
+    pub review_file_format: String,
+
+    /// Gerrit instance URL. Used if the review tool is gerrit.
+    #[serde(rename = "gerrit-url", default)]
+    pub gerrit_url: String,
+
+    /// Gerrit project to which review changes are pushed. Used if the review tool is gerrit.
+    #[serde(rename = "gerrit-project", default)]
+    pub gerrit_project: String,
+
+    /// Gerrit account user name for authenticated REST API requests.
+    #[serde(rename = "gerrit-username", default)]
+    pub gerrit_username: String,
+
+    /// Gerrit account HTTP password for authenticated REST API requests.
+    #[serde(rename = "gerrit-http-password", default)]
+    pub gerrit_http_password: String,
+}
+
+impl Default for ReviewTool {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            install_check: false,
+            review_file_format: get_default_review_file_format(),
+            gerrit_url: String::new(),
+            gerrit_project: String::new(),
+            gerrit_username: String::new(),
+            gerrit_http_password: String::new(),
+        }
+    }
+}
+
+fn get_default_review_file_format() -> String {
+    "json".to_string()
 }
 
 fn get_regex() -> Result<regex::Regex> {
@@ -38,6 +78,36 @@ pub fn set(review_tool: &mut ReviewTool, name: &str, value: &str) -> Result<()>
             review_tool.install_check = value == "true";
             Ok(())
         }
+        "review-file-format" => {
+            if value != "json" && value != "yaml" {
+                return Err(format_err!(
+                    "Unknown review file format: {}\nExpected one of: json, yaml",
+                    value
+                ));
+            }
+            review_tool.review_file_format = value.to_string();
+            Ok(())
+        }
+        "gerrit-url" => {
+            // An empty value unsets the Gerrit URL.
+            if !value.is_empty() {
+                url::Url::parse(&value).context(format!("Failed to parse URL: {}", value))?;
+            }
+            review_tool.gerrit_url = value.to_string();
+            Ok(())
+        }
+        "gerrit-project" => {
+            review_tool.gerrit_project = value.to_string();
+            Ok(())
+        }
+        "gerrit-username" => {
+            review_tool.gerrit_username = value.to_string();
+            Ok(())
+        }
+        "gerrit-http-password" => {
+            review_tool.gerrit_http_password = value.to_string();
+            Ok(())
+        }
         _ => Err(format_err!(name_error_message.clone())),
     }
 }
@@ -56,6 +126,11 @@ pub fn get(review_tool: &ReviewTool, name: &str) -> Result<String> {
     match field {
         "name" => Ok(review_tool.name.to_string()),
         "install-check" => Ok(review_tool.install_check.to_string()),
+        "review-file-format" => Ok(review_tool.review_file_format.to_string()),
+        "gerrit-url" => Ok(review_tool.gerrit_url.to_string()),
+        "gerrit-project" => Ok(review_tool.gerrit_project.to_string()),
+        "gerrit-username" => Ok(review_tool.gerrit_username.to_string()),
+        "gerrit-http-password" => Ok(review_tool.gerrit_http_password.to_string()),
         _ => Err(format_err!(name_error_message.clone())),
     }
 }
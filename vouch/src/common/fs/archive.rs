@@ -41,11 +41,12 @@ impl ArchiveType {
 
 /// Extract and return archive file extension from given path.
 fn get_file_extension(path: &std::path::PathBuf) -> Result<String> {
-    if path
+    let file_name = path
+        .file_name()
+        .unwrap_or(std::ffi::OsStr::new(""))
         .to_str()
-        .ok_or(format_err!("Failed to parse URL path as str."))?
-        .ends_with(".tar.gz")
-    {
+        .ok_or(format_err!("Failed to parse file name unicode characters."))?;
+    if file_name.ends_with(".tar.gz") {
         return Ok("tar.gz".to_string());
     }
 
@@ -70,6 +71,70 @@ mod tests {
         assert!(result == expected);
         Ok(())
     }
+
+    fn get_test_retry_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 2,
+            initial_backoff: std::time::Duration::from_millis(1),
+        }
+    }
+
+    #[test]
+    fn test_download_retries_on_transient_status() -> Result<()> {
+        let unavailable = mockito::mock("GET", "/retry/archive.tgz")
+            .with_status(503)
+            .expect(2)
+            .create();
+        let available = mockito::mock("GET", "/retry/archive.tgz")
+            .with_status(200)
+            .with_body("archive")
+            .expect(1)
+            .create();
+
+        let tmp_dir = tempdir::TempDir::new("vouch_test_download_retries")?;
+        let destination_path = tmp_dir.path().join("archive.tgz");
+        let url = url::Url::parse(&format!("{}/retry/archive.tgz", mockito::server_url()))?;
+        download_with_retries(&url, &destination_path, &get_test_retry_policy())?;
+
+        unavailable.assert();
+        available.assert();
+        assert_eq!(std::fs::read_to_string(&destination_path)?, "archive");
+        Ok(())
+    }
+
+    #[test]
+    fn test_download_fails_immediately_on_client_error() -> Result<()> {
+        let not_found = mockito::mock("GET", "/missing/archive.tgz")
+            .with_status(404)
+            .expect(1)
+            .create();
+
+        let tmp_dir = tempdir::TempDir::new("vouch_test_download_client_error")?;
+        let destination_path = tmp_dir.path().join("archive.tgz");
+        let url = url::Url::parse(&format!("{}/missing/archive.tgz", mockito::server_url()))?;
+        let result = download_with_retries(&url, &destination_path, &get_test_retry_policy());
+
+        not_found.assert();
+        assert!(result.is_err());
+        assert!(!destination_path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_content_length_reads_head_response() -> Result<()> {
+        let head = mockito::mock("HEAD", "/size/archive.tgz")
+            .with_status(200)
+            .with_header("content-length", "2097152")
+            .expect(1)
+            .create();
+
+        let url = url::Url::parse(&format!("{}/size/archive.tgz", mockito::server_url()))?;
+        let result = get_content_length(&url);
+
+        head.assert();
+        assert_eq!(result, Some(2097152));
+        Ok(())
+    }
 }
 
 pub fn extract(
@@ -207,28 +272,88 @@ fn get_tar_top_directory_name(archive_path: &std::path::PathBuf) -> Result<Optio
         .ok_or(format_err!("Archive empty."))??;
     let first_archive_entry = (*first_archive_entry.path()?).to_path_buf();
 
-    let top_directory_name = first_archive_entry
-        .components()
-        .next()
-        .ok_or(format_err!("Archive empty."))?
-        .as_os_str()
-        .to_str()
-        .ok_or(format_err!("Failed to parse archive's first path."))?;
-
-    Ok(if top_directory_name == "/" {
-        None
-    } else {
-        Some(top_directory_name.to_string())
+    // Root and drive prefix components indicate that there is no top level directory.
+    Ok(match first_archive_entry.components().next() {
+        Some(std::path::Component::Normal(top_directory_name)) => Some(
+            top_directory_name
+                .to_str()
+                .ok_or(format_err!("Failed to parse archive's first path."))?
+                .to_string(),
+        ),
+        Some(_) => None,
+        None => return Err(format_err!("Archive empty.")),
     })
 }
 
+/// Retry policy for transient download failures.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Number of retries after the first failed attempt.
+    pub max_retries: u32,
+
+    /// Delay before the first retry. Doubled for each subsequent retry.
+    pub initial_backoff: std::time::Duration,
+}
+
+impl RetryPolicy {
+    fn from_config() -> Result<Self> {
+        let config = crate::common::config::Config::load()?;
+        Ok(Self {
+            max_retries: config.core.download_max_retries,
+            initial_backoff: std::time::Duration::from_secs(1),
+        })
+    }
+}
+
 pub fn download(target_url: &url::Url, destination_path: &std::path::PathBuf) -> Result<()> {
+    let span = tracing::info_span!("archive_download", url = %target_url);
+    let _enter = span.enter();
+    download_with_retries(&target_url, &destination_path, &RetryPolicy::from_config()?)
+}
+
+/// Returns the archive size in bytes given by the Content-Length header of a HEAD request.
+///
+/// Returns None if the request fails or the header is missing.
+pub fn get_content_length(target_url: &url::Url) -> Option<u64> {
+    let response = match reqwest::blocking::Client::new()
+        .head(target_url.clone())
+        .send()
+    {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => {
+            log::debug!(
+                "HEAD request failed with HTTP status {}: {}",
+                response.status(),
+                target_url
+            );
+            return None;
+        }
+        Err(error) => {
+            log::debug!("HEAD request failed: {}\n{}", target_url, error);
+            return None;
+        }
+    };
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+fn download_with_retries(
+    target_url: &url::Url,
+    destination_path: &std::path::PathBuf,
+    retry_policy: &RetryPolicy,
+) -> Result<()> {
     log::debug!(
         "Downloading archive to destination path: {}",
         destination_path.display()
     );
 
-    let response = reqwest::blocking::get(target_url.clone())?;
+    let response = get_with_retries(&target_url, &retry_policy)?;
     let mut file = std::fs::File::create(&destination_path)?;
     let content = response.bytes()?;
     file.write_all(&content)?;
@@ -238,3 +363,79 @@ pub fn download(target_url: &url::Url, destination_path: &std::path::PathBuf) ->
 
     Ok(())
 }
+
+/// Send GET request. Retries with exponential backoff on transient errors.
+///
+/// Retries on connection errors, timeouts, and HTTP status codes 429, 500, 502, 503, and 504.
+/// The Retry-After header of a 429 response takes precedence over the backoff delay.
+fn get_with_retries(
+    target_url: &url::Url,
+    retry_policy: &RetryPolicy,
+) -> Result<reqwest::blocking::Response> {
+    let mut backoff = retry_policy.initial_backoff;
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let is_last_attempt = attempt > retry_policy.max_retries;
+
+        let delay = match reqwest::blocking::get(target_url.clone()) {
+            Ok(response) if response.status().is_success() => return Ok(response),
+            Ok(response) => {
+                let status = response.status();
+                if is_last_attempt || !is_retryable_status(status) {
+                    return Err(format_err!(
+                        "Failed to download {}: HTTP status {}",
+                        target_url,
+                        status
+                    ));
+                }
+                log::debug!(
+                    "Download attempt {} failed with HTTP status {}: {}",
+                    attempt,
+                    status,
+                    target_url
+                );
+                get_retry_after(&response).unwrap_or(backoff)
+            }
+            Err(error) => {
+                if is_last_attempt || !(error.is_connect() || error.is_timeout()) {
+                    return Err(error.into());
+                }
+                log::debug!(
+                    "Download attempt {} failed with error: {}\n{}",
+                    attempt,
+                    target_url,
+                    error
+                );
+                backoff
+            }
+        };
+
+        log::debug!("Retrying download in {:?}.", delay);
+        std::thread::sleep(delay);
+        backoff *= 2;
+    }
+}
+
+fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    match status.as_u16() {
+        429 | 500 | 502 | 503 | 504 => true,
+        _ => false,
+    }
+}
+
+/// Returns the delay given by the Retry-After header of a 429 response, if any.
+fn get_retry_after(response: &reqwest::blocking::Response) -> Option<std::time::Duration> {
+    if response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+    let seconds: u64 = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(std::time::Duration::from_secs(seconds))
+}
@@ -56,6 +56,7 @@ fn set_directory_hidden_windows(_directory: &std::path::PathBuf) {}
 pub struct ConfigPaths {
     pub root_directory: std::path::PathBuf,
     pub config_file: std::path::PathBuf,
+    pub review_template_file: std::path::PathBuf,
     pub extensions_directory: std::path::PathBuf,
 }
 
@@ -68,6 +69,7 @@ impl ConfigPaths {
         Ok(Self {
             root_directory: root_directory.into(),
             config_file: root_directory.join("config.yaml"),
+            review_template_file: root_directory.join("review-template.json"),
             extensions_directory: root_directory.join("extensions"),
         })
     }
@@ -128,6 +130,50 @@ pub fn git(args: Vec<&str>, working_directory: &std::path::PathBuf) -> Result<()
     Ok(())
 }
 
+/// Run a git command and return its standard output.
+///
+/// Fails with git's standard error output if the command exits unsuccessfully.
+pub fn git_output(args: Vec<&str>, working_directory: &std::path::PathBuf) -> Result<String> {
+    let output = std::process::Command::new("git")
+        .args(&args)
+        .current_dir(working_directory)
+        .output()?;
+    if !output.status.success() {
+        return Err(format_err!(
+            "Git command failed: git {}\n{}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Open the given contents in the user's editor and return the edited contents.
+///
+/// The editor is selected using the EDITOR environment variable.
+pub fn edit_in_editor(contents: &str, file_name: &str) -> Result<String> {
+    let editor = std::env::var("EDITOR").unwrap_or_else(|_| {
+        if cfg!(windows) {
+            "notepad".to_string()
+        } else {
+            "vi".to_string()
+        }
+    });
+
+    let tmp_dir = tempdir::TempDir::new("vouch_edit")?;
+    let file_path = tmp_dir.path().join(file_name);
+    std::fs::write(&file_path, contents)?;
+
+    let status = std::process::Command::new(&editor)
+        .arg(&file_path)
+        .status()
+        .map_err(|error| format_err!("Failed to start editor {}: {}", editor, error))?;
+    if !status.success() {
+        return Err(format_err!("Editor exited with failure: {}", editor));
+    }
+    Ok(std::fs::read_to_string(&file_path)?)
+}
+
 /// Remove empty directories along relative path.
 pub fn remove_empty_directories(
     relative_path: &std::path::PathBuf,
@@ -136,23 +182,36 @@ pub fn remove_empty_directories(
     let paths = DataPaths::new()?;
 
     let mut absolute_path = working_directory.join(relative_path);
-    while &absolute_path != working_directory {
+    while absolute_path.starts_with(&working_directory) && &absolute_path != working_directory {
         if paths.is_protected(&absolute_path) {
             break;
         }
-        if !absolute_path.exists() {
-            absolute_path.pop();
-            continue;
-        }
-        if std::fs::remove_dir(&absolute_path).is_err() {
+        if absolute_path.exists() && std::fs::remove_dir(&absolute_path).is_err() {
             // Found first non-empty directory.
             break;
         }
-        absolute_path.pop();
+        if !absolute_path.pop() {
+            // Reached filesystem root.
+            break;
+        }
     }
     Ok(())
 }
 
+/// Returns a relative path as a string with forward slash separators, as expected by git.
+pub fn to_git_path(relative_path: &std::path::Path) -> Result<String> {
+    let components: Option<Vec<&str>> = relative_path
+        .components()
+        .map(|component| component.as_os_str().to_str())
+        .collect();
+    Ok(components
+        .ok_or(format_err!(
+            "Could not parse path: {}",
+            relative_path.display()
+        ))?
+        .join("/"))
+}
+
 /// Remove a git submodule.
 ///
 /// See: https://stackoverflow.com/a/36593218/1339591
@@ -161,10 +220,8 @@ pub fn git_remove_submodule(
     root_directory: &std::path::PathBuf,
 ) -> Result<()> {
     // Make a str version because its used throughout.
-    let submodule_relative_path_str = submodule_relative_path.to_str().ok_or(format_err!(
-        "Could not parse submodule path: {}",
-        submodule_relative_path.display()
-    ))?;
+    let submodule_relative_path_str = to_git_path(&submodule_relative_path)?;
+    let submodule_relative_path_str = submodule_relative_path_str.as_str();
 
     // Remove the submodule entry from .git/config
     std::process::Command::new("git")
@@ -178,12 +235,9 @@ pub fn git_remove_submodule(
         .status()?;
 
     // // Remove the submodule directory from the superproject's .git/modules directory
-    let modules_path = format!(".git/modules/{}", submodule_relative_path_str);
-    std::fs::remove_dir_all(&root_directory.join(modules_path))?;
-    remove_empty_directories(
-        &submodule_relative_path,
-        &root_directory.join(".git/modules"),
-    )?;
+    let modules_directory = root_directory.join(".git").join("modules");
+    std::fs::remove_dir_all(&modules_directory.join(&submodule_relative_path))?;
+    remove_empty_directories(&submodule_relative_path, &modules_directory)?;
 
     // Remove the entry in .gitmodules and remove the submodule directory
     std::process::Command::new("git")
@@ -219,45 +273,105 @@ pub fn is_remote_repo_setup() -> Result<bool> {
     Ok(!repo.remotes()?.is_empty())
 }
 
-pub fn git_push_root() -> Result<()> {
+/// Outcome of pushing the root repository to its remote.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PushStatus {
+    Pushed,
+
+    /// The remote contains commits which are not present locally.
+    NonFastForward,
+}
+
+pub fn git_push_root() -> Result<PushStatus> {
     let paths = DataPaths::new()?;
-    git(
-        vec!["push", "--set-upstream", "origin", "master"],
-        &paths.root_directory,
-    )?;
-    Ok(())
+    let output = std::process::Command::new("git")
+        .args(vec!["push", "--set-upstream", "origin", "master"])
+        .current_dir(&paths.root_directory)
+        .output()?;
+    if output.status.success() {
+        return Ok(PushStatus::Pushed);
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("non-fast-forward") || stderr.contains("fetch first") {
+        return Ok(PushStatus::NonFastForward);
+    }
+    Err(format_err!(
+        "Failed to push to remote repository:\n{}",
+        stderr.trim()
+    ))
 }
 
 pub struct GitTransaction {
     working_directory: std::path::PathBuf,
+
+    /// HEAD commit at construction. None if the repository could not be read or has no commits.
+    initial_commit_hash: Option<git2::Oid>,
 }
 
 impl GitTransaction {
     pub fn new() -> Result<Self> {
         let paths = DataPaths::new()?;
+        Ok(Self::from_directory(&paths.root_directory))
+    }
 
-        // TODO: Get initial commit for atomic reversion.
-        // let repository = git2::Repository::open(&paths.root_data_directory)?;
-        // let head_reference = repository.head()?;
-        // let initial_commit_hash = head_reference.peel_to_commit()?.id();
-
-        Ok(Self {
-            working_directory: paths.root_directory.clone(),
-        })
+    pub fn from_directory(working_directory: &std::path::PathBuf) -> Self {
+        let initial_commit_hash = match get_head_commit_hash(&working_directory) {
+            Ok(hash) => Some(hash),
+            Err(error) => {
+                log::debug!("Failed to find initial commit for rollback: {}", error);
+                None
+            }
+        };
+        Self {
+            working_directory: working_directory.clone(),
+            initial_commit_hash,
+        }
     }
 
     pub fn commit(&mut self, message: &str) -> Result<()> {
-        let args = vec!["add", "-A"];
-        git(args, &self.working_directory)?;
+        self.run_git(vec!["add", "-A"])?;
+        if git_output(vec!["status", "--porcelain"], &self.working_directory)?
+            .trim()
+            .is_empty()
+        {
+            log::debug!("No changes to commit.");
+            return Ok(());
+        }
+        self.run_git(vec!["commit", "-m", message])?;
+        Ok(())
+    }
 
-        let args = vec!["commit", "-am", message];
-        git(args, &self.working_directory)?;
+    /// Undo staged and committed changes made since the transaction was created.
+    pub fn rollback(self) -> Result<()> {
+        let initial_commit_hash = self.initial_commit_hash.ok_or(format_err!(
+            "Failed to roll back: initial commit unknown for repository: {}",
+            self.working_directory.display()
+        ))?;
+        self.run_git(vec!["reset", "--hard", &initial_commit_hash.to_string()])?;
+        Ok(())
+    }
 
+    fn run_git(&self, args: Vec<&str>) -> Result<()> {
+        let status = std::process::Command::new("git")
+            .args(&args)
+            .current_dir(&self.working_directory)
+            .status()?;
+        if !status.success() {
+            return Err(format_err!("Git command failed: git {}", args.join(" ")));
+        }
         Ok(())
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+fn get_head_commit_hash(working_directory: &std::path::PathBuf) -> Result<git2::Oid> {
+    let repository = git2::Repository::open(&working_directory)?;
+    let head_reference = repository.head()?;
+    let initial_commit_hash = head_reference.peel_to_commit()?.id();
+    Ok(initial_commit_hash)
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum PathType {
     File,
     Directory,
@@ -284,10 +398,183 @@ fn hash_file(path: &std::path::PathBuf) -> Result<String> {
     Ok(blake3_digest(reader)?)
 }
 
+fn sha_digest<D: sha2::Digest, R: std::io::Read>(mut reader: R) -> Result<String> {
+    let mut hasher = D::new();
+    let mut buffer = [0; 1024];
+
+    loop {
+        let count = reader.read(&mut buffer)?;
+        if count == 0 {
+            break;
+        }
+        hasher.update(&buffer[..count]);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// Returns the hex encoded hash of a file using the given algorithm.
+///
+/// Registries publish artifact checksums using different algorithms.
+pub fn hash_with_algorithm(
+    path: &std::path::PathBuf,
+    algorithm: vouch_lib::extension::HashAlgorithm,
+) -> Result<String> {
+    let input = std::fs::File::open(path)?;
+    let reader = std::io::BufReader::new(input);
+    match algorithm {
+        vouch_lib::extension::HashAlgorithm::Blake3 => blake3_digest(reader),
+        vouch_lib::extension::HashAlgorithm::Sha1 => sha_digest::<sha1::Sha1, _>(reader),
+        vouch_lib::extension::HashAlgorithm::Sha256 => sha_digest::<sha2::Sha256, _>(reader),
+        vouch_lib::extension::HashAlgorithm::Sha512 => sha_digest::<sha2::Sha512, _>(reader),
+    }
+}
+
 pub fn hash(path: &std::path::PathBuf) -> Result<(String, PathType)> {
     if path.is_file() {
         return Ok((hash_file(&path)?, PathType::File));
     } else {
-        unimplemented!("Only file hashing is currently implemented.");
+        return Ok((hash_directory(&path, &vec![])?, PathType::Directory));
+    }
+}
+
+/// Returns a hash of a directory tree's relative file paths and file contents.
+///
+/// Entries with a relative path in the excluded set are skipped.
+pub fn hash_directory(
+    directory: &std::path::PathBuf,
+    excluded_relative_paths: &Vec<std::path::PathBuf>,
+) -> Result<String> {
+    let mut file_hashes = std::collections::BTreeMap::new();
+    collect_file_hashes(
+        &directory,
+        &directory,
+        &excluded_relative_paths,
+        &mut file_hashes,
+    )?;
+
+    let mut hasher = blake3::Hasher::new();
+    for (relative_path, file_hash) in file_hashes {
+        hasher.update(relative_path.as_bytes());
+        hasher.update(b"\0");
+        hasher.update(file_hash.as_bytes());
+        hasher.update(b"\n");
+    }
+    Ok(hasher.finalize().to_hex().as_str().to_string())
+}
+
+fn collect_file_hashes(
+    root_directory: &std::path::PathBuf,
+    directory: &std::path::PathBuf,
+    excluded_relative_paths: &Vec<std::path::PathBuf>,
+    file_hashes: &mut std::collections::BTreeMap<String, String>,
+) -> Result<()> {
+    for entry in std::fs::read_dir(&directory)? {
+        let path = entry?.path();
+        let relative_path = path.strip_prefix(&root_directory)?.to_path_buf();
+        if excluded_relative_paths.contains(&relative_path) {
+            continue;
+        }
+
+        let metadata = std::fs::symlink_metadata(&path)?;
+        if metadata.file_type().is_symlink() {
+            let target = std::fs::read_link(&path)?;
+            file_hashes.insert(
+                to_git_path(&relative_path)?,
+                blake3::hash(target.to_string_lossy().as_bytes())
+                    .to_hex()
+                    .as_str()
+                    .to_string(),
+            );
+        } else if metadata.is_dir() {
+            collect_file_hashes(
+                &root_directory,
+                &path,
+                &excluded_relative_paths,
+                file_hashes,
+            )?;
+        } else {
+            file_hashes.insert(to_git_path(&relative_path)?, hash_file(&path)?);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_git_output_fails_on_git_error() -> Result<()> {
+        let tmp_dir = tempdir::TempDir::new("vouch_test_git_output_fails_on_git_error")?;
+        let directory = tmp_dir.path().to_path_buf();
+        git2::Repository::init(&directory)?;
+
+        // A repository without commits has no HEAD to log.
+        let result = git_output(vec!["log", "-1", "--format=%cs"], &directory);
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rollback_after_failed_commit() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp_dir = tempdir::TempDir::new("vouch_test_rollback_after_failed_commit")?;
+        let directory = tmp_dir.path().to_path_buf();
+        let repository = git2::Repository::init(&directory)?;
+        std::fs::write(directory.join("committed.txt"), "committed")?;
+        let mut index = repository.index()?;
+        index.add_path(std::path::Path::new("committed.txt"))?;
+        index.write()?;
+        let tree = repository.find_tree(index.write_tree()?)?;
+        let signature = git2::Signature::now("test", "test@localhost")?;
+        let initial_commit_hash =
+            repository.commit(Some("HEAD"), &signature, &signature, "Initial.", &tree, &[])?;
+
+        // Reject all further commits.
+        let hook_path = directory.join(".git").join("hooks").join("pre-commit");
+        std::fs::create_dir_all(hook_path.parent().unwrap())?;
+        std::fs::write(&hook_path, "#!/bin/sh\nexit 1\n")?;
+        std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o755))?;
+
+        let mut git_transaction = GitTransaction::from_directory(&directory);
+        std::fs::write(directory.join("uncommitted.txt"), "uncommitted")?;
+        assert!(git_transaction.commit("Rejected.").is_err());
+        git_transaction.rollback()?;
+
+        assert_eq!(
+            repository.head()?.peel_to_commit()?.id(),
+            initial_commit_hash
+        );
+        assert!(directory.join("committed.txt").exists());
+        assert!(!directory.join("uncommitted.txt").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_with_algorithm() -> Result<()> {
+        let tmp_dir = tempdir::TempDir::new("vouch_test_hash_with_algorithm")?;
+        let path = tmp_dir.path().join("artifact");
+        std::fs::write(&path, "abc")?;
+
+        assert_eq!(
+            hash_with_algorithm(&path, vouch_lib::extension::HashAlgorithm::Sha1)?,
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+        assert_eq!(
+            hash_with_algorithm(&path, vouch_lib::extension::HashAlgorithm::Sha256)?,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            hash_with_algorithm(&path, vouch_lib::extension::HashAlgorithm::Blake3)?,
+            hash(&path)?.0
+        );
+        Ok(())
     }
 }
@@ -0,0 +1,81 @@
+//! Forgejo and Gitea repository host API helpers.
+//!
+//! Self-hosted instances can not be identified by host name. They are detected by querying the
+//! repository API endpoint.
+
+use anyhow::{format_err, Context, Result};
+
+/// Returns the API URL of a repository.
+///
+/// Example: https://codeberg.org/vouch-dev/vouch-py -> https://codeberg.org/api/v1/repos/vouch-dev/vouch-py
+pub fn get_repo_api_url(repo_url: &url::Url) -> Result<url::Url> {
+    let segments: Vec<_> = repo_url
+        .path_segments()
+        .map(|segments| segments.filter(|segment| !segment.is_empty()).collect())
+        .unwrap_or_default();
+    let (owner, repo) = match segments.as_slice() {
+        [owner, repo, ..] => (owner, repo.trim_end_matches(".git")),
+        _ => {
+            return Err(format_err!(
+                "Failed to find repository owner and name in URL: {}",
+                repo_url
+            ))
+        }
+    };
+
+    let mut api_url = repo_url.clone();
+    api_url.set_path(&format!("api/v1/repos/{}/{}", owner, repo));
+    api_url.set_query(None);
+    api_url.set_fragment(None);
+    Ok(api_url)
+}
+
+/// Send a GET request to a Gitea compatible API endpoint and parse the JSON response.
+pub fn get_json(api_url: &url::Url) -> Result<serde_json::Value> {
+    let client = reqwest::blocking::Client::builder()
+        .user_agent(super::HTTP_USER_AGENT)
+        .build()?;
+    let response = client.get(api_url.as_str()).send()?;
+    if !response.status().is_success() {
+        return Err(format_err!(
+            "Request failed {}: HTTP status {}",
+            api_url,
+            response.status()
+        ));
+    }
+    let body = response.text()?;
+    Ok(serde_json::from_str(&body).context(format!("JSON was not well-formatted:\n{}", body))?)
+}
+
+/// Returns true if the repository is hosted by a Forgejo or Gitea instance, otherwise false.
+pub fn is_gitea_repo(repo_url: &url::Url) -> bool {
+    let api_url = match get_repo_api_url(&repo_url) {
+        Ok(api_url) => api_url,
+        Err(_) => return false,
+    };
+    match get_json(&api_url) {
+        Ok(repo) => repo.get("full_name").is_some() && repo.pointer("/owner/login").is_some(),
+        Err(error) => {
+            log::debug!("Repository host is not Gitea compatible: {}", error);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_gitea_repo() -> Result<()> {
+        let mock = mockito::mock("GET", "/api/v1/repos/user/repo")
+            .with_status(200)
+            .with_body(r#"{"full_name": "user/repo", "owner": {"login": "user"}}"#)
+            .create();
+
+        let repo_url = url::Url::parse(&format!("{}/user/repo.git", mockito::server_url()))?;
+        assert!(is_gitea_repo(&repo_url));
+        mock.assert();
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::collections::HashSet;
 use std::hash::Hasher;
 
@@ -68,3 +68,80 @@ pub fn get_ids_where_field<'a>(ids: &Option<&'a Vec<crate::common::index::ID>>)
         None => "true".to_string(),
     }
 }
+
+/// Apply pending schema migrations within the given transaction.
+///
+/// Migrations are (version, SQL) pairs. Those with a version greater than the database's
+/// `user_version` are executed in ascending version order. The `user_version` is then set to the
+/// last applied version. Any failure leaves the transaction to be rolled back.
+pub fn migrate(tx: &crate::common::StoreTransaction, migrations: &[(u32, &str)]) -> Result<()> {
+    let current_version: u32 =
+        tx.index_tx()
+            .query_row("PRAGMA user_version", rusqlite::NO_PARAMS, |row| row.get(0))?;
+
+    let mut pending: Vec<_> = migrations
+        .iter()
+        .filter(|(version, _sql)| *version > current_version)
+        .collect();
+    pending.sort_by_key(|(version, _sql)| *version);
+
+    let mut latest_version = current_version;
+    for (version, sql) in pending {
+        log::debug!("Applying index schema migration: {}", version);
+        tx.index_tx().execute_batch(sql).context(format!(
+            "Failed to apply index schema migration: {}",
+            version
+        ))?;
+        latest_version = *version;
+    }
+
+    if latest_version != current_version {
+        tx.index_tx()
+            .execute_batch(&format!("PRAGMA user_version = {}", latest_version))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_user_version(tx: &crate::common::StoreTransaction) -> Result<u32> {
+        Ok(tx
+            .index_tx()
+            .query_row("PRAGMA user_version", rusqlite::NO_PARAMS, |row| row.get(0))?)
+    }
+
+    #[test]
+    fn test_migrate_applies_pending_migrations_in_order() -> Result<()> {
+        let mut db = rusqlite::Connection::open_in_memory()?;
+        let tx = crate::common::StoreTransaction::new(db.transaction()?)?;
+        let migrations = [
+            (2, "ALTER TABLE example ADD COLUMN value TEXT"),
+            (1, "CREATE TABLE example (id INTEGER PRIMARY KEY)"),
+        ];
+
+        migrate(&tx, &migrations)?;
+
+        assert_eq!(get_user_version(&tx)?, 2);
+        tx.index_tx().execute(
+            "INSERT INTO example (value) VALUES ('x')",
+            rusqlite::NO_PARAMS,
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_skips_applied_migrations() -> Result<()> {
+        let mut db = rusqlite::Connection::open_in_memory()?;
+        let tx = crate::common::StoreTransaction::new(db.transaction()?)?;
+        let migrations = [(1, "CREATE TABLE example (id INTEGER PRIMARY KEY)")];
+
+        migrate(&tx, &migrations)?;
+        // Re-creating the table would fail if the migration was applied again.
+        migrate(&tx, &migrations)?;
+
+        assert_eq!(get_user_version(&tx)?, 1);
+        Ok(())
+    }
+}
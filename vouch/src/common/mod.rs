@@ -5,11 +5,12 @@
 //! should be as simple as possible.
 //!
 //! Print statements are prohibited within this module. Logging is allowed.
-use anyhow::Result;
+use anyhow::{format_err, Result};
 use std::convert::TryFrom;
 
 pub mod config;
 pub mod fs;
+pub mod gitea;
 pub mod index;
 
 pub static HTTP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
@@ -31,9 +32,24 @@ impl<'a> StoreTransaction<'a> {
         &self.index_transaction
     }
 
+    /// Commit index changes, then commit file changes to git.
+    ///
+    /// File changes are rolled back if the git commit fails.
     pub fn commit(mut self, message: &str) -> Result<()> {
         self.index_transaction.commit()?;
-        self.git_transaction.commit(message)?;
+        if let Err(commit_error) = self.git_transaction.commit(message) {
+            return Err(match self.git_transaction.rollback() {
+                Ok(()) => format_err!(
+                    "Failed to commit changes to git. File changes rolled back.\n{}",
+                    commit_error
+                ),
+                Err(rollback_error) => format_err!(
+                    "Failed to commit changes to git: {}\nFailed to roll back file changes: {}",
+                    commit_error,
+                    rollback_error
+                ),
+            });
+        }
         Ok(())
     }
 
@@ -0,0 +1,51 @@
+use anyhow::{format_err, Context, Result};
+use std::io::Read;
+
+use crate::common;
+
+/// Default location of the extension binary checksums manifest.
+pub static DEFAULT_MANIFEST_URL: &str = "https://vouch-extensions.example.com/checksums.json";
+
+/// Returns known good blake3 hashes of the given extension's binaries, keyed by version.
+///
+/// Only entries for the current platform are returned.
+/// Manifest keys are of the form: {extension_name}/{platform}/{version}
+pub fn get_known_hashes(
+    manifest_url: &url::Url,
+    extension_name: &str,
+) -> Result<std::collections::BTreeMap<String, String>> {
+    let manifest = get_manifest(&manifest_url)?;
+    let prefix = format!(
+        "{name}/{platform}/",
+        name = extension_name,
+        platform = super::get_platform()?
+    );
+    Ok(manifest
+        .into_iter()
+        .filter_map(|(key, hash)| {
+            key.strip_prefix(&prefix)
+                .map(|version| (version.to_string(), hash))
+        })
+        .collect())
+}
+
+fn get_manifest(manifest_url: &url::Url) -> Result<std::collections::BTreeMap<String, String>> {
+    log::debug!("Using checksums manifest URL: {}", manifest_url);
+    let client = reqwest::blocking::Client::builder()
+        .user_agent(common::HTTP_USER_AGENT)
+        .build()?;
+    let mut result = client.get(&manifest_url.to_string()).send()?;
+    if !result.status().is_success() {
+        return Err(format_err!(
+            "Failed to fetch checksums manifest {}: HTTP status {}",
+            manifest_url,
+            result.status()
+        ));
+    }
+    let mut body = String::new();
+    result.read_to_string(&mut body)?;
+    Ok(serde_json::from_str(&body).context(format!(
+        "Checksums manifest was not well-formatted:\n{}",
+        body
+    ))?)
+}
@@ -0,0 +1,36 @@
+use anyhow::Result;
+
+use crate::common;
+
+/// Returns a release asset URL for the current platform from a Forgejo or Gitea repository.
+///
+/// The release with the given tag name is used if a version is given, otherwise the latest.
+pub fn get_archive_url(repo_url: &url::Url, version: Option<&str>) -> Result<Option<url::Url>> {
+    let platform = super::get_platform()?;
+    log::debug!("Identified target platform: {}", platform);
+
+    let mut release_url = common::gitea::get_repo_api_url(&repo_url)?;
+    let release_path = match version {
+        Some(version) => format!("{}/releases/tags/{}", release_url.path(), version),
+        None => format!("{}/releases/latest", release_url.path()),
+    };
+    release_url.set_path(&release_path);
+    log::debug!("Using release URL: {}", release_url);
+
+    let release = common::gitea::get_json(&release_url)?;
+    if let Some(assets) = release.get("assets").and_then(|assets| assets.as_array()) {
+        for asset in assets {
+            if let Some(asset_name) = asset.get("name").and_then(|name| name.as_str()) {
+                if asset_name.contains(&platform) {
+                    if let Some(url) = asset
+                        .get("browser_download_url")
+                        .and_then(|url| url.as_str())
+                    {
+                        return Ok(Some(url::Url::parse(url)?));
+                    }
+                }
+            }
+        }
+    }
+    Ok(None)
+}
@@ -3,8 +3,8 @@ use std::io::Read;
 
 use crate::common;
 
-pub fn get_archive_url(repo_url: &url::Url) -> Result<Option<url::Url>> {
-    let platform = get_platform()?;
+pub fn get_archive_url(repo_url: &url::Url, version: Option<&str>) -> Result<Option<url::Url>> {
+    let platform = super::get_platform()?;
     log::debug!("Identified target platform: {}", platform);
 
     let releases = get_releases(&repo_url)?;
@@ -15,6 +15,11 @@ pub fn get_archive_url(repo_url: &url::Url) -> Result<Option<url::Url>> {
     }
 
     for release in releases {
+        if let Some(version) = version {
+            if release.get("tag_name").and_then(|tag| tag.as_str()) != Some(version) {
+                continue;
+            }
+        }
         if let Some(assets) = release.get("assets").and_then(|assets| assets.as_array()) {
             for asset in assets {
                 if let Some(asset_name) = asset.get("name").and_then(|name| name.as_str()) {
@@ -57,13 +62,3 @@ fn get_releases(repo_url: &url::Url) -> Result<Vec<serde_json::Value>> {
         .ok_or(format_err!("Failed to find releases from GitHub repo."))?;
     Ok(releases.clone())
 }
-
-fn get_platform() -> Result<String> {
-    Ok(match std::env::consts::OS {
-        "linux" => "unknown-linux-musl",
-        "macos" => "apple-darwin",
-        "windows" => "pc-windows-msvc",
-        other => return Err(format_err!("Unsupported OS type: {}", other)),
-    }
-    .to_string())
-}
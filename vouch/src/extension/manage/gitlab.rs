@@ -0,0 +1,84 @@
+use anyhow::{format_err, Context, Result};
+use std::io::Read;
+
+use crate::common;
+
+pub fn get_archive_url(repo_url: &url::Url, version: Option<&str>) -> Result<Option<url::Url>> {
+    let platform = super::get_platform()?;
+    log::debug!("Identified target platform: {}", platform);
+
+    let links = get_release_links(&repo_url, version)?;
+    if links.is_empty() {
+        log::debug!("Failed to find any release asset links corresponding to repository URL.");
+    } else {
+        log::debug!("Found {} candidate release asset links.", links.len());
+    }
+
+    for link in links {
+        if let Some(link_name) = link.get("name").and_then(|name| name.as_str()) {
+            if link_name.contains(&platform) {
+                if let Some(url) = link
+                    .get("direct_asset_url")
+                    .or(link.get("url"))
+                    .and_then(|url| url.as_str())
+                {
+                    return Ok(Some(url::Url::parse(url)?));
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Get release asset links given a repository URL such as: https://gitlab.com/vouch-dev/vouch-py
+///
+/// Links are taken from the latest release if no version tag name is given.
+fn get_release_links(repo_url: &url::Url, version: Option<&str>) -> Result<Vec<serde_json::Value>> {
+    let release = match version {
+        Some(version) => version.replace("/", "%2F"),
+        None => "permalink/latest".to_string(),
+    };
+    let links_url = url::Url::parse(
+        format!(
+            "https://gitlab.com/api/v4/projects/{id}/releases/{release}/assets/links",
+            id = get_project_id(&repo_url),
+            release = release
+        )
+        .as_str(),
+    )?;
+    log::debug!("Using release asset links URL: {}", links_url);
+
+    let client = reqwest::blocking::Client::builder()
+        .user_agent(common::HTTP_USER_AGENT)
+        .build()?;
+    let mut result = client.get(&links_url.to_string()).send()?;
+    let mut body = String::new();
+    result.read_to_string(&mut body)?;
+    let links: serde_json::Value =
+        serde_json::from_str(&body).context(format!("JSON was not well-formatted:\n{}", body))?;
+    let links = links.as_array().ok_or(format_err!(
+        "Failed to find release asset links from GitLab repo."
+    ))?;
+    Ok(links.clone())
+}
+
+/// Get URL encoded project ID from repository URL path.
+///
+/// Example: https://gitlab.com/vouch-dev/vouch-py -> vouch-dev%2Fvouch-py
+fn get_project_id(repo_url: &url::Url) -> String {
+    let path = repo_url.path().trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    path.replace("/", "%2F")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_id_url_encodes_nested_path() -> Result<()> {
+        let url = url::Url::parse("https://gitlab.com/vouch-dev/group/vouch-py.git")?;
+        assert_eq!(get_project_id(&url), "vouch-dev%2Fgroup%2Fvouch-py");
+        Ok(())
+    }
+}
@@ -3,16 +3,20 @@ use std::convert::TryFrom;
 
 use crate::common::config::Config;
 use crate::extension::{common, process};
+pub mod checksum;
+mod gitea;
 mod github;
+mod gitlab;
 
 pub fn add_from_url(
     url: &url::Url,
     extensions_bin_directory: &std::path::PathBuf,
+    version: Option<&str>,
 ) -> Result<String> {
     let archive_url = if is_supported_archive_url(&url)? {
         url.clone()
     } else {
-        match get_archive_url(&url)? {
+        match get_archive_url(&url, version)? {
             Some(url) => url,
             None => {
                 return Err(format_err!(
@@ -118,14 +122,29 @@ fn is_supported_archive_url(url: &url::Url) -> Result<bool> {
 }
 
 /// Returns a release archive URL.
-fn get_archive_url(url: &url::Url) -> Result<Option<url::Url>> {
-    Ok(if url.host_str() == Some("github.com") {
-        github::get_archive_url(&url)?
-    } else {
-        None
+///
+/// The release with the given tag name is used if a version is given, otherwise the latest.
+fn get_archive_url(url: &url::Url, version: Option<&str>) -> Result<Option<url::Url>> {
+    Ok(match url.host_str() {
+        Some("github.com") => github::get_archive_url(&url, version)?,
+        Some("gitlab.com") => gitlab::get_archive_url(&url, version)?,
+        Some(_) if crate::common::gitea::is_gitea_repo(&url) => {
+            gitea::get_archive_url(&url, version)?
+        }
+        _ => None,
     })
 }
 
+fn get_platform() -> Result<String> {
+    Ok(match std::env::consts::OS {
+        "linux" => "unknown-linux-musl",
+        "macos" => "apple-darwin",
+        "windows" => "pc-windows-msvc",
+        other => return Err(format_err!("Unsupported OS type: {}", other)),
+    }
+    .to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -155,13 +174,29 @@ mod tests {
             Ok(())
         }
     }
+
+    #[test]
+    fn test_enabled_registry_host_names_filtered_on_extension_names() -> Result<()> {
+        let mut config = Config::default();
+        config.extensions.registries = maplit::btreemap! {
+            "pypi.org".to_string() => "py".to_string(),
+            "npmjs.com".to_string() => "js".to_string(),
+            "crates.io".to_string() => "rs".to_string(),
+        };
+        let extension_names = maplit::btreeset! {"py".to_string(), "rs".to_string()};
+
+        let result = get_enabled_registry_host_names(&extension_names, &config)?;
+        let expected = maplit::btreeset! {"crates.io".to_string(), "pypi.org".to_string()};
+        assert_eq!(result, expected);
+        Ok(())
+    }
 }
 
 /// Update config with discoverable extensions.
 pub fn update_config(config: &mut Config) -> Result<()> {
     log::debug!("Discover extensions and update config.");
 
-    let extensions = process::get_all()?;
+    let extensions = process::get_all(&config)?;
     let extension_name_map: std::collections::BTreeMap<_, _> = extensions
         .iter()
         .map(|extension| (extension.name(), extension))
@@ -233,7 +268,29 @@ pub fn disable(name: &str, config: &mut Config) -> Result<()> {
     }
 }
 
-pub fn remove(name: &str) -> Result<()> {
+/// Pin extension to a release tag name.
+pub fn pin(name: &str, version: &str, config: &mut Config) -> Result<()> {
+    config
+        .extensions
+        .pinned_versions
+        .insert(name.to_string(), version.to_string());
+    config.dump()?;
+    Ok(())
+}
+
+/// Remove extension version pin. Returns the removed version, if any.
+pub fn unpin(name: &str, config: &mut Config) -> Result<Option<String>> {
+    let version = config.extensions.pinned_versions.remove(name);
+    if version.is_some() {
+        config.dump()?;
+    }
+    Ok(version)
+}
+
+/// Delete extension binary and remove extension from config. Returns the deleted binary path.
+///
+/// Built-in extensions can not be removed.
+pub fn remove(name: &str) -> Result<std::path::PathBuf> {
     let mut config = Config::load()?;
     update_config(&mut config)?;
 
@@ -247,6 +304,11 @@ pub fn remove(name: &str) -> Result<()> {
                 .join(", ")
         ));
     }
+    let bin_path = get_bin_path(&name)?.ok_or(format_err!(
+        "Failed to find extension binary: {name}\n\
+        Built-in extensions can not be removed. Disable with: vouch extension disable {name}",
+        name = name
+    ))?;
 
     // Remove extension specific config file.
     let path = common::get_config_path(&name)?;
@@ -255,15 +317,27 @@ pub fn remove(name: &str) -> Result<()> {
         std::fs::remove_file(&path)?;
     }
 
-    // Remove extension process file.
-    let extension_paths = process::get_extension_paths()?;
-    if let Some(path) = extension_paths.get(name) {
-        log::info!("Deleting extension bin file: {}", path.display());
-        std::fs::remove_file(&path)?;
+    log::info!("Deleting extension bin file: {}", bin_path.display());
+    std::fs::remove_file(&bin_path)?;
+
+    config.extensions.enabled.remove(name);
+    for registry_host_name in get_registry_host_names(&name, &config) {
+        config.extensions.registries.remove(&registry_host_name);
     }
+    config.extensions.pinned_versions.remove(name);
+    config.dump()?;
+    Ok(bin_path)
+}
 
-    update_config(&mut config)?;
-    Ok(())
+/// Returns the host names of registries handled by the given extension.
+pub fn get_registry_host_names(name: &str, config: &Config) -> std::collections::BTreeSet<String> {
+    config
+        .extensions
+        .registries
+        .iter()
+        .filter(|(_registry_host_name, extension_name)| extension_name.as_str() == name)
+        .map(|(registry_host_name, _extension_name)| registry_host_name.clone())
+        .collect()
 }
 
 /// Given an extension's name, returns true if the extension is enabled. Otherwise returns false.
@@ -271,13 +345,31 @@ pub fn is_enabled(name: &str, config: &Config) -> Result<bool> {
     Ok(*config.extensions.enabled.get(name).unwrap_or(&false))
 }
 
+/// Returns true if the extension is built-in or its binary can be found, otherwise false.
+pub fn is_installed(name: &str) -> Result<bool> {
+    let is_lib_extension = process::get_lib_extensions()
+        .iter()
+        .any(|extension| extension.name() == name);
+    Ok(is_lib_extension || process::get_extension_paths()?.contains_key(name))
+}
+
+/// Returns the binary path of an installed process extension.
+pub fn get_bin_path(name: &str) -> Result<Option<std::path::PathBuf>> {
+    Ok(process::get_extension_paths()?.remove(name))
+}
+
 /// Returns enabled extensions.
+///
+/// Disabled extensions are skipped with a warning.
 pub fn get_enabled(
     names: &std::collections::BTreeSet<String>,
     config: &Config,
 ) -> Result<Vec<Box<dyn vouch_lib::extension::Extension>>> {
     log::debug!("Identifying enabled extensions.");
-    let extensions = process::get_all()?
+    for name in get_disabled_names(&config)? {
+        log::warn!("Skipping disabled extension: {}", name);
+    }
+    let extensions = process::get_all(&config)?
         .into_iter()
         .filter(|extension| {
             *config
@@ -303,6 +395,33 @@ pub fn get_enabled_names(config: &Config) -> Result<std::collections::BTreeSet<S
         .collect())
 }
 
+/// Returns a set of all disabled installed extensions by names.
+pub fn get_disabled_names(config: &Config) -> Result<std::collections::BTreeSet<String>> {
+    Ok(config
+        .extensions
+        .enabled
+        .iter()
+        .filter(|(_name, enabled_flag)| !**enabled_flag)
+        .map(|(name, _enabled_flag)| name.clone())
+        .collect())
+}
+
+/// Returns registry host names which are handled by the given extensions.
+pub fn get_enabled_registry_host_names(
+    extension_names: &std::collections::BTreeSet<String>,
+    config: &Config,
+) -> Result<std::collections::BTreeSet<String>> {
+    Ok(config
+        .extensions
+        .registries
+        .iter()
+        .filter(|(_registry_host_name, extension_name)| {
+            extension_names.contains(extension_name.as_str())
+        })
+        .map(|(registry_host_name, _extension_name)| registry_host_name.clone())
+        .collect())
+}
+
 pub fn get_all_names(config: &Config) -> Result<std::collections::BTreeSet<String>> {
     Ok(config
         .extensions
@@ -333,7 +452,16 @@ pub fn handle_extension_names_arg(
                 extension_names.into_iter().cloned().collect()
             }
         }
-        None => get_enabled_names(&config)?,
+        None => {
+            let disabled_names = get_disabled_names(&config)?;
+            if !disabled_names.is_empty() {
+                println!(
+                    "Note: the following extensions are disabled and will not be used: {}",
+                    disabled_names.into_iter().collect::<Vec<_>>().join(", ")
+                );
+            }
+            get_enabled_names(&config)?
+        }
     };
     log::debug!("Using extensions: {:?}", names);
     Ok(names)
@@ -4,6 +4,7 @@ use crossbeam_utils;
 mod common;
 pub mod manage;
 mod process;
+pub mod protocol;
 
 /// Search package registries via extensions for package metadata from registries.
 ///
@@ -22,6 +23,13 @@ pub fn search_registries<'a>(
             .iter()
             .map(|extension| {
                 s.spawn(move |_| {
+                    let span = tracing::info_span!(
+                        "extension_registries_package_metadata",
+                        extension_name = %extension.name(),
+                        package_name,
+                        package_version = ?package_version
+                    );
+                    let _enter = span.enter();
                     extension.registries_package_metadata(&package_name, &package_version)
                 })
             })
@@ -88,7 +96,18 @@ pub fn identify_file_defined_dependencies(
         let mut threads = Vec::new();
         for extension in extensions {
             threads.push(s.spawn(move |_| {
-                extension.identify_file_defined_dependencies(&working_directory, &extension_args)
+                let span = tracing::info_span!(
+                    "extension_identify_file_defined_dependencies",
+                    extension_name = %extension.name()
+                );
+                let _enter = span.enter();
+                let mut all_dependencies = extension
+                    .identify_file_defined_dependencies(&working_directory, &extension_args)?;
+                for dependencies in &mut all_dependencies {
+                    dependencies.lock_file_path =
+                        get_lock_file_path(&dependencies.path, &working_directory);
+                }
+                Ok(all_dependencies)
             }));
         }
         let mut result = Vec::new();
@@ -100,6 +119,19 @@ pub fn identify_file_defined_dependencies(
     .unwrap()
 }
 
+/// Returns the dependencies specification file path relative to the working directory.
+///
+/// Files found outside of the working directory, such as in a parent directory, are given as
+/// absolute paths.
+fn get_lock_file_path(
+    path: &std::path::PathBuf,
+    working_directory: &std::path::PathBuf,
+) -> std::path::PathBuf {
+    path.strip_prefix(&working_directory)
+        .map(|path| path.to_path_buf())
+        .unwrap_or(path.clone())
+}
+
 /// Identify package dependencies.
 ///
 /// Conducts a parallel search across extensions.
@@ -113,6 +145,13 @@ pub fn identify_package_dependencies(
         let mut threads = Vec::new();
         for extension in extensions {
             threads.push(s.spawn(move |_| {
+                let span = tracing::info_span!(
+                    "extension_identify_package_dependencies",
+                    extension_name = %extension.name(),
+                    package_name,
+                    package_version = ?package_version
+                );
+                let _enter = span.enter();
                 extension.identify_package_dependencies(
                     &package_name,
                     &package_version,
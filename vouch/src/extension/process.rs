@@ -2,26 +2,33 @@ use anyhow::{format_err, Result};
 use std::collections::HashMap;
 use vouch_lib::extension::{FromLib, FromProcess};
 
+use crate::common::config::Config;
 use crate::extension::common;
 
 pub static EXTENSION_FILE_NAME_PREFIX: &str = "vouch-";
 
 /// Return handles to all known extensions.
-pub fn get_all() -> Result<Vec<Box<dyn vouch_lib::extension::Extension>>> {
+pub fn get_all(config: &Config) -> Result<Vec<Box<dyn vouch_lib::extension::Extension>>> {
     log::debug!("Identifying all extensions.");
 
-    let mut all_extensions = vec![
-        Box::new(vouch_py_lib::PyExtension::new()) as Box<dyn vouch_lib::extension::Extension>,
-        Box::new(vouch_js_lib::JsExtension::new()) as Box<dyn vouch_lib::extension::Extension>,
-    ];
-
-    for extension in get_process_extensions()? {
+    let timeout = std::time::Duration::from_secs(config.extensions.timeout_seconds);
+    let mut all_extensions = get_lib_extensions();
+    for mut extension in get_process_extensions()? {
+        extension.set_timeout(timeout);
         all_extensions.push(Box::new(extension) as Box<dyn vouch_lib::extension::Extension>);
     }
 
     Ok(all_extensions)
 }
 
+/// Return handles to extensions which are built into Vouch.
+pub fn get_lib_extensions() -> Vec<Box<dyn vouch_lib::extension::Extension>> {
+    vec![
+        Box::new(vouch_py_lib::PyExtension::new()) as Box<dyn vouch_lib::extension::Extension>,
+        Box::new(vouch_js_lib::JsExtension::new()) as Box<dyn vouch_lib::extension::Extension>,
+    ]
+}
+
 /// Discovers and loads process extensions.
 fn get_process_extensions() -> Result<Vec<vouch_lib::extension::process::ProcessExtension>> {
     let extension_paths = get_extension_paths()?;
@@ -0,0 +1,178 @@
+//! Extension process protocol compliance checks.
+
+use anyhow::{format_err, Result};
+
+use crate::review;
+
+/// Package used to query extension registries. Expected to exist in at least the PyPI registry.
+static KNOWN_PACKAGE_NAME: &str = "requests";
+static KNOWN_PACKAGE_VERSION: &str = "2.28.0";
+
+/// Outcome of a single protocol check.
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub command: String,
+    pub summary: review::Summary,
+    pub message: String,
+    pub raw_output: Option<String>,
+}
+
+/// Run protocol checks against an extension binary.
+pub fn check(process_path: &std::path::PathBuf) -> Result<Vec<CheckResult>> {
+    let working_directory = tempdir::TempDir::new("vouch_extension_check")?;
+    let working_directory = working_directory.path().to_str().ok_or(format_err!(
+        "Failed to parse temporary directory path: {}",
+        working_directory.path().display()
+    ))?;
+
+    Ok(vec![
+        check_static_data(&process_path)?,
+        check_identify_file_defined_dependencies(&process_path, &working_directory)?,
+        check_registries_package_metadata(&process_path)?,
+    ])
+}
+
+fn check_static_data(process_path: &std::path::PathBuf) -> Result<CheckResult> {
+    let args = vec!["static-data"];
+    let (output, result) = run::<vouch_lib::extension::process::StaticData>(&process_path, &args)?;
+    let (summary, message) = match result {
+        Ok(Ok(static_data)) => {
+            if static_data.name.is_empty() {
+                (review::Summary::Fail, "Field name is empty.".to_string())
+            } else if static_data.registry_host_names.is_empty() {
+                (
+                    review::Summary::Fail,
+                    "Field registry_host_names is empty.".to_string(),
+                )
+            } else {
+                (review::Summary::Pass, format!("name: {}", static_data.name))
+            }
+        }
+        Ok(Err(error)) => (
+            review::Summary::Fail,
+            format!("Extension returned error: {}", error),
+        ),
+        Err(error) => (review::Summary::Fail, error),
+    };
+    Ok(get_check_result(&args, summary, message, output))
+}
+
+fn check_identify_file_defined_dependencies(
+    process_path: &std::path::PathBuf,
+    working_directory: &str,
+) -> Result<CheckResult> {
+    let args = vec![
+        vouch_lib::extension::commands::identify_file_defined_dependencies::COMMAND_NAME,
+        "--working-directory",
+        working_directory,
+    ];
+    let (output, result) =
+        run::<Vec<vouch_lib::extension::FileDefinedDependencies>>(&process_path, &args)?;
+    let (summary, message) = match result {
+        Ok(Ok(all_dependencies)) => (
+            review::Summary::Pass,
+            format!("dependency files found: {}", all_dependencies.len()),
+        ),
+        Ok(Err(error)) => (
+            review::Summary::Warn,
+            format!("Extension returned error: {}", error),
+        ),
+        Err(error) => (review::Summary::Fail, error),
+    };
+    Ok(get_check_result(&args, summary, message, output))
+}
+
+fn check_registries_package_metadata(process_path: &std::path::PathBuf) -> Result<CheckResult> {
+    let args = vec![
+        vouch_lib::extension::commands::registries_package_metadata::COMMAND_NAME,
+        KNOWN_PACKAGE_NAME,
+        KNOWN_PACKAGE_VERSION,
+    ];
+    let (output, result) =
+        run::<Vec<vouch_lib::extension::RegistryPackageMetadata>>(&process_path, &args)?;
+    let (summary, message) = match result {
+        Ok(Ok(registries_metadata)) => {
+            if registries_metadata.is_empty() {
+                (
+                    review::Summary::Warn,
+                    "No registry metadata returned.".to_string(),
+                )
+            } else if !registries_metadata
+                .iter()
+                .any(|registry_metadata| registry_metadata.is_primary)
+            {
+                (
+                    review::Summary::Fail,
+                    "No registry metadata marked as primary.".to_string(),
+                )
+            } else {
+                (
+                    review::Summary::Pass,
+                    format!("registries found: {}", registries_metadata.len()),
+                )
+            }
+        }
+        Ok(Err(error)) => (
+            review::Summary::Warn,
+            format!("Extension returned error: {}", error),
+        ),
+        Err(error) => (review::Summary::Fail, error),
+    };
+    Ok(get_check_result(&args, summary, message, output))
+}
+
+fn get_check_result(
+    args: &Vec<&str>,
+    summary: review::Summary,
+    message: String,
+    output: String,
+) -> CheckResult {
+    let raw_output = if summary == review::Summary::Pass {
+        None
+    } else {
+        Some(output)
+    };
+    CheckResult {
+        command: args.join(" "),
+        summary,
+        message,
+        raw_output,
+    }
+}
+
+/// Run extension process. Returns raw standard output and parsed result.
+///
+/// The outer result describes schema violations. The inner result is the extension's own result.
+fn run<T>(
+    process_path: &std::path::PathBuf,
+    args: &Vec<&str>,
+) -> Result<(
+    String,
+    std::result::Result<std::result::Result<T, String>, String>,
+)>
+where
+    for<'de> T: serde::Deserialize<'de>,
+{
+    let output = std::process::Command::new(&process_path)
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .output()?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+
+    let process_result: vouch_lib::extension::process::ProcessResult<T> =
+        match serde_json::from_str(&stdout) {
+            Ok(process_result) => process_result,
+            Err(error) => {
+                return Ok((stdout, Err(format!("Invalid output structure: {}", error))));
+            }
+        };
+    let result = match (process_result.ok, process_result.err) {
+        (Some(ok), None) => Ok(Ok(ok)),
+        (None, Some(err)) => Ok(Err(err)),
+        (Some(_), Some(_)) => Err("Output contains both ok and err fields.".to_string()),
+        (None, None) => Err("Output contains neither ok nor err fields.".to_string()),
+    };
+    Ok((stdout, result))
+}
@@ -1,9 +1,9 @@
-use env_logger;
 use structopt::StructOpt;
 
 mod command;
 mod common;
 mod extension;
+mod osv;
 mod package;
 mod peer;
 mod registry;
@@ -11,14 +11,18 @@ mod review;
 mod store;
 
 fn main() {
-    let env = env_logger::Env::new().filter_or("VOUCH_LOG", "off");
-    env_logger::Builder::from_env(env).init();
+    let filter = tracing_subscriber::EnvFilter::try_from_env("VOUCH_LOG")
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("off"));
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .init();
 
     let args: Vec<String> = std::env::args().collect();
     let (vouch_args, extension_args) = split_extension_args(&args);
     let commands = command::Opts::from_iter(vouch_args.iter());
 
-    match command::run_command(commands.command, &extension_args) {
+    match command::run_command(commands, &extension_args) {
         Ok(_) => {}
         Err(e) => {
             eprintln!("{}", e);
@@ -0,0 +1,173 @@
+//! Known vulnerability lookups using the OSV database: https://osv.dev
+
+use anyhow::{format_err, Context, Result};
+
+use crate::common;
+
+static OSV_API_URL: &str = "https://api.osv.dev/v1";
+
+thread_local! {
+    /// Query results keyed by ecosystem, package name, and package version.
+    static CACHE: std::cell::RefCell<
+        std::collections::BTreeMap<(String, String, String), Vec<Vulnerability>>,
+    > = std::cell::RefCell::new(std::collections::BTreeMap::new());
+}
+
+/// A known vulnerability which affects a package version.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Vulnerability {
+    /// OSV identifier. Example: GHSA-jfh8-c2jp-5v3q
+    pub id: String,
+
+    /// Alternative identifiers, such as CVE IDs.
+    pub aliases: Vec<String>,
+}
+
+impl Vulnerability {
+    /// Returns the CVE ID, if known.
+    pub fn get_cve_id(&self) -> Option<&str> {
+        self.aliases
+            .iter()
+            .find(|alias| alias.starts_with("CVE-"))
+            .map(|alias| alias.as_str())
+    }
+
+    /// Returns a labelled identifier: the CVE ID if known, otherwise the OSV advisory identifier.
+    pub fn get_label(&self) -> String {
+        match self.get_cve_id() {
+            Some(cve_id) => format!("CVE: {}", cve_id),
+            None => format!("Advisory: {}", self.id),
+        }
+    }
+}
+
+/// Returns known vulnerabilities of a package version.
+///
+/// The OSV API is queried at most once per package version. Packages from registries which
+/// are not covered by OSV have no known vulnerabilities.
+pub fn query(
+    package_name: &str,
+    package_version: &str,
+    registry_host_name: &str,
+) -> Result<Vec<Vulnerability>> {
+    let ecosystem = match crate::registry::ecosystem::from_registry_host_name(&registry_host_name) {
+        Some(ecosystem) => ecosystem.osv_name,
+        None => {
+            log::debug!(
+                "Skipping OSV query for registry without OSV ecosystem: {}",
+                registry_host_name
+            );
+            return Ok(vec![]);
+        }
+    };
+    let key = (
+        ecosystem.to_string(),
+        package_name.to_string(),
+        package_version.to_string(),
+    );
+    if let Some(vulnerabilities) = CACHE.with(|cache| cache.borrow().get(&key).cloned()) {
+        return Ok(vulnerabilities);
+    }
+
+    let vulnerabilities = query_api(OSV_API_URL, &ecosystem, &package_name, &package_version)?;
+    CACHE.with(|cache| cache.borrow_mut().insert(key, vulnerabilities.clone()));
+    Ok(vulnerabilities)
+}
+
+fn query_api(
+    api_url: &str,
+    ecosystem: &str,
+    package_name: &str,
+    package_version: &str,
+) -> Result<Vec<Vulnerability>> {
+    let url = format!("{}/query", api_url.trim_end_matches('/'));
+    let body = serde_json::json!({
+        "version": package_version,
+        "package": {
+            "name": package_name,
+            "ecosystem": ecosystem,
+        },
+    });
+
+    let client = reqwest::blocking::Client::builder()
+        .user_agent(common::HTTP_USER_AGENT)
+        .build()?;
+    let response = client
+        .post(&url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body.to_string())
+        .send()
+        .context(format!("Failed to connect to OSV API: {}", url))?;
+    if !response.status().is_success() {
+        return Err(format_err!(
+            "OSV API request failed {}: HTTP status {}",
+            url,
+            response.status()
+        ));
+    }
+    let body = response.text()?;
+    let response: serde_json::Value =
+        serde_json::from_str(&body).context(format!("JSON was not well-formatted:\n{}", body))?;
+    Ok(parse_vulnerabilities(&response))
+}
+
+fn parse_vulnerabilities(response: &serde_json::Value) -> Vec<Vulnerability> {
+    let vulns = match response.get("vulns").and_then(|vulns| vulns.as_array()) {
+        Some(vulns) => vulns,
+        None => return vec![],
+    };
+    vulns
+        .iter()
+        .filter_map(|vuln| {
+            let id = vuln.get("id")?.as_str()?.to_string();
+            let aliases = vuln
+                .get("aliases")
+                .and_then(|aliases| aliases.as_array())
+                .map(|aliases| {
+                    aliases
+                        .iter()
+                        .filter_map(|alias| alias.as_str())
+                        .map(|alias| alias.to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+            Some(Vulnerability { id, aliases })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_api() -> Result<()> {
+        let mock = mockito::mock("POST", "/osv/query")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "version": "1.0.0",
+                "package": {"name": "d3", "ecosystem": "npm"},
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"vulns": [
+                    {"id": "GHSA-aaaa-bbbb-cccc", "aliases": ["CVE-2021-1234"]},
+                    {"id": "GHSA-dddd-eeee-ffff"}
+                ]}"#,
+            )
+            .create();
+
+        let api_url = format!("{}/osv", mockito::server_url());
+        let result = query_api(&api_url, "npm", "d3", "1.0.0")?;
+
+        mock.assert();
+        let labels: Vec<_> = result
+            .iter()
+            .map(|vulnerability| vulnerability.get_label())
+            .collect();
+        assert_eq!(
+            labels,
+            vec!["CVE: CVE-2021-1234", "Advisory: GHSA-dddd-eeee-ffff"]
+        );
+        Ok(())
+    }
+}
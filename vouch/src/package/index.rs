@@ -100,26 +100,32 @@ pub fn get(fields: &Fields, tx: &StoreTransaction) -> Result<HashSet<common::Pac
         (":version", &package_version),
     ])?;
 
-    let mut packages = HashSet::new();
+    // Registries are retrieved in a single batch after all package rows are read.
+    let mut package_rows = Vec::new();
+    let mut all_registry_ids = std::collections::BTreeSet::new();
     while let Some(row) = rows.next()? {
-        let registry_ids: Option<Result<Vec<crate::common::index::ID>>> = row
-            .get::<_, Option<Vec<u8>>>(3)?
-            .map(|x| Ok(bincode::deserialize(&x)?));
-        let registries = match registry_ids {
-            Some(registry_ids) => {
-                let registry_ids = registry_ids?;
-                registry::index::get(
-                    &registry::index::Fields {
-                        ids: Some(&registry_ids),
-                        ..Default::default()
-                    },
-                    &tx,
-                )?
-                .into_iter()
-                .collect()
-            }
-            None => std::collections::BTreeSet::<registry::Registry>::new(),
+        let registry_ids: Vec<crate::common::index::ID> = match row.get::<_, Option<Vec<u8>>>(3)? {
+            Some(registry_ids) => bincode::deserialize(&registry_ids)?,
+            None => Vec::new(),
         };
+        all_registry_ids.extend(registry_ids.iter().cloned());
+        package_rows.push((
+            row.get::<_, crate::common::index::ID>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            registry_ids,
+            row.get::<_, String>(4)?,
+        ));
+    }
+    let all_registry_ids: Vec<_> = all_registry_ids.into_iter().collect();
+    let all_registries = registry::index::get_by_ids(&all_registry_ids, &tx)?;
+
+    let mut packages = HashSet::new();
+    for (id, name, version, registry_ids, artifact_hash) in package_rows {
+        let registries: std::collections::BTreeSet<registry::Registry> = registry_ids
+            .iter()
+            .filter_map(|registry_id| all_registries.get(registry_id).cloned())
+            .collect();
 
         // Skip package if none of the given registry host names match to any registry.
         if let Some(registry_host_names) = &fields.registry_host_names {
@@ -135,11 +141,11 @@ pub fn get(fields: &Fields, tx: &StoreTransaction) -> Result<HashSet<common::Pac
         }
 
         let package = common::Package {
-            id: row.get(0)?,
-            name: row.get(1)?,
-            version: row.get(2)?,
-            registries: registries,
-            artifact_hash: row.get(4)?,
+            id,
+            name,
+            version,
+            registries,
+            artifact_hash,
         };
         packages.insert(package);
     }
@@ -160,7 +166,7 @@ pub fn merge(
     {
         let mut new_registries = std::collections::BTreeSet::new();
         for registry in package.registries {
-            let new_registry = registry::index::ensure(
+            let new_registry = registry::index::get_or_insert(
                 &registry.host_name,
                 &registry.human_url,
                 &registry.artifact_url,
@@ -271,7 +277,7 @@ mod tests {
         for package in vec![package_1, package_2] {
             let mut registries = std::collections::BTreeSet::<registry::Registry>::new();
             for registry in package.registries {
-                registries.insert(registry::index::ensure(
+                registries.insert(registry::index::get_or_insert(
                     &registry.host_name,
                     &registry.human_url,
                     &registry.artifact_url,
@@ -304,4 +310,40 @@ mod tests {
         assert_eq!(result, expected);
         Ok(())
     }
+
+    #[test]
+    fn test_get_joins_batched_registries() -> Result<()> {
+        let mut db = rusqlite::Connection::open_in_memory()?;
+        let tx = StoreTransaction::new(db.transaction()?)?;
+        crate::store::index::setup(&tx)?;
+
+        let mut registries = vec![];
+        for index in 0..3 {
+            registries.push(registry::index::get_or_insert(
+                &format!("host_name_{}", index),
+                &url::Url::parse(&format!("https://human_url_{}.com", index))?,
+                &url::Url::parse(&format!("https://artifact_url_{}.com", index))?,
+                &tx,
+            )?);
+        }
+
+        let mut expected = HashSet::new();
+        for index in 0..200 {
+            let package_registries = maplit::btreeset! {
+                registries[index % 3].clone(),
+                registries[(index + 1) % 3].clone(),
+            };
+            expected.insert(insert(
+                &format!("package_{}", index),
+                "1.0.0",
+                &package_registries,
+                &format!("artifact_hash_{}", index),
+                &tx,
+            )?);
+        }
+
+        let result = get(&Fields::default(), &tx)?;
+        assert_eq!(result, expected);
+        Ok(())
+    }
 }
@@ -0,0 +1,439 @@
+//! Integrity checks for peer review stores.
+//!
+//! Checks are diagnostic only. The peer store is never modified.
+
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+use super::common;
+use crate::common::fs::DataPaths;
+use crate::common::index::ID;
+
+/// Checks a peer's index and review files for consistency. Returns a description for each problem found.
+pub fn check(peer_branch: &Vec<common::Peer>) -> Result<Vec<String>> {
+    let db = super::fs::get_peer_database(&peer_branch)?;
+    let root_paths = DataPaths::new()?;
+    let peer_paths = super::fs::get_peer_data_paths(&peer_branch, &root_paths.root_directory)?;
+    check_store(&db, &peer_paths)
+}
+
+fn check_store(db: &rusqlite::Connection, peer_paths: &DataPaths) -> Result<Vec<String>> {
+    let mut warnings = Vec::new();
+    warnings.extend(check_review_packages(&db)?);
+    warnings.extend(check_review_comments(&db)?);
+    warnings.extend(check_package_registries(&db)?);
+    warnings.extend(check_review_files(&db, &peer_paths)?);
+    Ok(warnings)
+}
+
+/// Verification result for a single review in a peer's store.
+#[derive(Debug, Clone)]
+pub struct ReviewVerification {
+    pub review_id: ID,
+    pub package_name: Option<String>,
+    pub package_version: Option<String>,
+    pub problems: Vec<String>,
+}
+
+impl ReviewVerification {
+    pub fn is_pass(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Verifies each review in a peer's index against its comments and review file.
+///
+/// A review's file must exist and record the same package artifact hash as the index.
+pub fn verify_reviews(peer_branch: &Vec<common::Peer>) -> Result<Vec<ReviewVerification>> {
+    let db = super::fs::get_peer_database(&peer_branch)?;
+    let root_paths = DataPaths::new()?;
+    let peer_paths = super::fs::get_peer_data_paths(&peer_branch, &root_paths.root_directory)?;
+    verify_store_reviews(&db, &peer_paths)
+}
+
+fn verify_store_reviews(
+    db: &rusqlite::Connection,
+    peer_paths: &DataPaths,
+) -> Result<Vec<ReviewVerification>> {
+    let comment_ids = get_ids(&db, "comment")?;
+    let registry_host_names = get_registry_host_names(&db)?;
+
+    let mut statement = db.prepare(
+        r"
+        SELECT
+            review.id,
+            review.comment_ids,
+            package.name,
+            package.version,
+            package.registry_ids,
+            package.artifact_hash
+        FROM review
+        LEFT JOIN package
+            ON review.package_id = package.id
+        ORDER BY review.id
+        ",
+    )?;
+    let mut rows = statement.query(rusqlite::NO_PARAMS)?;
+
+    let mut verifications = Vec::new();
+    while let Some(row) = rows.next()? {
+        let mut verification = ReviewVerification {
+            review_id: row.get(0)?,
+            package_name: row.get(2)?,
+            package_version: row.get(3)?,
+            problems: Vec::new(),
+        };
+
+        if let Some(blob) = row.get::<_, Option<Vec<u8>>>(1)? {
+            let review_comment_ids: Vec<ID> = bincode::deserialize(&blob)?;
+            for comment_id in review_comment_ids {
+                if !comment_ids.contains(&comment_id) {
+                    verification
+                        .problems
+                        .push(format!("missing comment {}", comment_id));
+                }
+            }
+        }
+
+        let (package_name, package_version) =
+            match (&verification.package_name, &verification.package_version) {
+                (Some(name), Some(version)) => (name.clone(), version.clone()),
+                _ => {
+                    verification.problems.push("missing package".to_string());
+                    verifications.push(verification);
+                    continue;
+                }
+            };
+        let package_registry_ids: Vec<ID> = bincode::deserialize(&row.get::<_, Vec<u8>>(4)?)?;
+        let artifact_hash: String = row.get(5)?;
+
+        let mut review_file = None;
+        for registry_id in package_registry_ids {
+            if let Some(registry_host_name) = registry_host_names.get(&registry_id) {
+                let relative_path = match crate::review::fs::get_review_file_relative_path(
+                    &package_name,
+                    &package_version,
+                    &registry_host_name,
+                ) {
+                    Ok(relative_path) => relative_path,
+                    Err(error) => {
+                        verification.problems.push(error.to_string());
+                        continue;
+                    }
+                };
+                let path = peer_paths.reviews_directory.join(relative_path);
+                if path.is_file() {
+                    review_file = Some(path);
+                    break;
+                }
+            } else {
+                verification
+                    .problems
+                    .push(format!("missing registry {}", registry_id));
+            }
+        }
+
+        match review_file {
+            Some(review_file) => {
+                if let Some(problem) = check_review_file_hash(&review_file, &artifact_hash)? {
+                    verification.problems.push(problem);
+                }
+            }
+            None => verification
+                .problems
+                .push("review file not found".to_string()),
+        }
+        verifications.push(verification);
+    }
+    Ok(verifications)
+}
+
+/// Compare the package artifact hash recorded in a review file against the index.
+fn check_review_file_hash(
+    review_file: &std::path::PathBuf,
+    artifact_hash: &str,
+) -> Result<Option<String>> {
+    let contents = std::fs::read_to_string(&review_file)?;
+    let review: serde_json::Value = match serde_json::from_str(&contents) {
+        Ok(review) => review,
+        Err(_) => return Ok(Some("review file is not valid JSON".to_string())),
+    };
+    match review["package"]["artifact_hash"].as_str() {
+        Some(file_artifact_hash) if file_artifact_hash == artifact_hash => Ok(None),
+        Some(_) => Ok(Some(
+            "review file artifact hash does not match index".to_string(),
+        )),
+        None => Ok(Some("review file is missing an artifact hash".to_string())),
+    }
+}
+
+/// Run git fsck on the peer's repository. Returns the fsck output if problems were found.
+pub fn git_fsck(peer_branch: &Vec<common::Peer>) -> Result<Option<String>> {
+    let root_paths = DataPaths::new()?;
+    let peer_path = super::fs::get_peer_path(&peer_branch, &root_paths.root_directory)?;
+    git_fsck_directory(&peer_path)
+}
+
+fn git_fsck_directory(peer_path: &std::path::PathBuf) -> Result<Option<String>> {
+    let output = std::process::Command::new("git")
+        .args(vec!["fsck", "--no-progress"])
+        .current_dir(&peer_path)
+        .output()?;
+    if output.status.success() {
+        return Ok(None);
+    }
+
+    let mut message = String::from_utf8_lossy(&output.stdout).to_string();
+    message.push_str(&String::from_utf8_lossy(&output.stderr));
+    Ok(Some(message.trim().to_string()))
+}
+
+/// Check that every review references an existing package.
+fn check_review_packages(db: &rusqlite::Connection) -> Result<Vec<String>> {
+    let mut statement = db.prepare(
+        r"
+        SELECT review.id, review.package_id
+        FROM review
+        LEFT JOIN package
+            ON review.package_id = package.id
+        WHERE package.id IS NULL
+        ",
+    )?;
+    let mut rows = statement.query(rusqlite::NO_PARAMS)?;
+
+    let mut warnings = Vec::new();
+    while let Some(row) = rows.next()? {
+        let review_id: ID = row.get(0)?;
+        let package_id: ID = row.get(1)?;
+        warnings.push(format!(
+            "Review {review_id} references missing package {package_id}.",
+            review_id = review_id,
+            package_id = package_id
+        ));
+    }
+    Ok(warnings)
+}
+
+/// Check that every review comment ID references an existing comment.
+fn check_review_comments(db: &rusqlite::Connection) -> Result<Vec<String>> {
+    let comment_ids = get_ids(&db, "comment")?;
+
+    let mut statement = db.prepare("SELECT id, comment_ids FROM review")?;
+    let mut rows = statement.query(rusqlite::NO_PARAMS)?;
+
+    let mut warnings = Vec::new();
+    while let Some(row) = rows.next()? {
+        let review_id: ID = row.get(0)?;
+        let review_comment_ids: Vec<ID> = match row.get::<_, Option<Vec<u8>>>(1)? {
+            Some(blob) => bincode::deserialize(&blob)?,
+            None => continue,
+        };
+        for comment_id in review_comment_ids {
+            if !comment_ids.contains(&comment_id) {
+                warnings.push(format!(
+                    "Review {review_id} references missing comment {comment_id}.",
+                    review_id = review_id,
+                    comment_id = comment_id
+                ));
+            }
+        }
+    }
+    Ok(warnings)
+}
+
+/// Check that every package registry ID references an existing registry.
+fn check_package_registries(db: &rusqlite::Connection) -> Result<Vec<String>> {
+    let registry_ids = get_ids(&db, "registry")?;
+
+    let mut statement = db.prepare("SELECT id, registry_ids FROM package")?;
+    let mut rows = statement.query(rusqlite::NO_PARAMS)?;
+
+    let mut warnings = Vec::new();
+    while let Some(row) = rows.next()? {
+        let package_id: ID = row.get(0)?;
+        let package_registry_ids: Vec<ID> = bincode::deserialize(&row.get::<_, Vec<u8>>(1)?)?;
+        for registry_id in package_registry_ids {
+            if !registry_ids.contains(&registry_id) {
+                warnings.push(format!(
+                    "Package {package_id} references missing registry {registry_id}.",
+                    package_id = package_id,
+                    registry_id = registry_id
+                ));
+            }
+        }
+    }
+    Ok(warnings)
+}
+
+/// Check that every review has a corresponding review file.
+fn check_review_files(db: &rusqlite::Connection, peer_paths: &DataPaths) -> Result<Vec<String>> {
+    let registry_host_names = get_registry_host_names(&db)?;
+
+    let mut statement = db.prepare(
+        r"
+        SELECT review.id, package.name, package.version, package.registry_ids
+        FROM review
+        JOIN package
+            ON review.package_id = package.id
+        ",
+    )?;
+    let mut rows = statement.query(rusqlite::NO_PARAMS)?;
+
+    let mut warnings = Vec::new();
+    while let Some(row) = rows.next()? {
+        let review_id: ID = row.get(0)?;
+        let package_name: String = row.get(1)?;
+        let package_version: String = row.get(2)?;
+        let package_registry_ids: Vec<ID> = bincode::deserialize(&row.get::<_, Vec<u8>>(3)?)?;
+
+        let mut expected_paths = Vec::new();
+        for registry_id in package_registry_ids {
+            // Missing registries are reported separately.
+            if let Some(registry_host_name) = registry_host_names.get(&registry_id) {
+                match crate::review::fs::get_review_file_relative_path(
+                    &package_name,
+                    &package_version,
+                    &registry_host_name,
+                ) {
+                    Ok(relative_path) => {
+                        expected_paths.push(peer_paths.reviews_directory.join(relative_path))
+                    }
+                    Err(error) => warnings.push(format!(
+                        "Review {review_id}: {error}",
+                        review_id = review_id,
+                        error = error
+                    )),
+                }
+            }
+        }
+
+        if !expected_paths.is_empty() && !expected_paths.iter().any(|path| path.is_file()) {
+            warnings.push(format!(
+                "Review {review_id} file not found: {path}",
+                review_id = review_id,
+                path = expected_paths[0].display()
+            ));
+        }
+    }
+    Ok(warnings)
+}
+
+fn get_ids(db: &rusqlite::Connection, table_name: &str) -> Result<HashSet<ID>> {
+    let mut statement = db.prepare(&format!("SELECT id FROM {}", table_name))?;
+    let mut rows = statement.query(rusqlite::NO_PARAMS)?;
+
+    let mut ids = HashSet::new();
+    while let Some(row) = rows.next()? {
+        ids.insert(row.get(0)?);
+    }
+    Ok(ids)
+}
+
+fn get_registry_host_names(db: &rusqlite::Connection) -> Result<HashMap<ID, String>> {
+    let mut statement = db.prepare("SELECT id, host_name FROM registry")?;
+    let mut rows = statement.query(rusqlite::NO_PARAMS)?;
+
+    let mut host_names = HashMap::new();
+    while let Some(row) = rows.next()? {
+        host_names.insert(row.get(0)?, row.get(1)?);
+    }
+    Ok(host_names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::StoreTransaction;
+
+    /// Setup a peer store with a single review. Returns the review file path.
+    fn setup_store(
+        db: &mut rusqlite::Connection,
+        peer_paths: &DataPaths,
+    ) -> Result<std::path::PathBuf> {
+        let tx = StoreTransaction::new(db.transaction()?)?;
+        crate::store::index::setup(&tx)?;
+        let registry = crate::registry::index::insert(
+            "test_registry_host_name",
+            &url::Url::parse("http://localhost/test_registry_human_url")?,
+            &url::Url::parse("http://localhost/test_archive_url")?,
+            &tx,
+        )?;
+        let package = crate::package::index::insert(
+            "test_package_name",
+            "test_package_version",
+            &maplit::btreeset! {registry},
+            "test_artifact_hash",
+            &tx,
+        )?;
+        let root_peer = crate::peer::index::get_root(&tx)?.unwrap();
+        crate::review::index::insert(&maplit::btreeset! {}, &root_peer, &package, &None, &tx)?;
+        tx.commit_index()?;
+
+        let review_file =
+            peer_paths
+                .reviews_directory
+                .join(crate::review::fs::get_review_file_relative_path(
+                    "test_package_name",
+                    "test_package_version",
+                    "test_registry_host_name",
+                )?);
+        std::fs::create_dir_all(review_file.parent().unwrap())?;
+        std::fs::write(
+            &review_file,
+            r#"{"package": {"artifact_hash": "test_artifact_hash"}}"#,
+        )?;
+        Ok(review_file)
+    }
+
+    #[test]
+    fn test_clean_store_passes_checks() -> Result<()> {
+        let tmp_dir = tempdir::TempDir::new("vouch_test_clean_store_passes_checks")?;
+        let peer_paths = DataPaths::from_root_directory(&tmp_dir.path().to_path_buf())?;
+        let mut db = rusqlite::Connection::open_in_memory()?;
+        setup_store(&mut db, &peer_paths)?;
+
+        assert!(check_store(&db, &peer_paths)?.is_empty());
+        let verifications = verify_store_reviews(&db, &peer_paths)?;
+        assert_eq!(verifications.len(), 1);
+        assert!(verifications[0].is_pass());
+        Ok(())
+    }
+
+    #[test]
+    fn test_corrupt_review_file_fails_verification() -> Result<()> {
+        let tmp_dir = tempdir::TempDir::new("vouch_test_corrupt_review_file_fails_verification")?;
+        let peer_paths = DataPaths::from_root_directory(&tmp_dir.path().to_path_buf())?;
+        let mut db = rusqlite::Connection::open_in_memory()?;
+        let review_file = setup_store(&mut db, &peer_paths)?;
+        std::fs::write(&review_file, "{")?;
+
+        let verifications = verify_store_reviews(&db, &peer_paths)?;
+        assert_eq!(
+            verifications[0].problems,
+            vec!["review file is not valid JSON".to_string()]
+        );
+
+        std::fs::remove_file(&review_file)?;
+        let warnings = check_store(&db, &peer_paths)?;
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("Review 1 file not found:"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_fsck() -> Result<()> {
+        let tmp_dir = tempdir::TempDir::new("vouch_test_git_fsck")?;
+        let peer_path = tmp_dir.path().to_path_buf();
+        std::process::Command::new("git")
+            .args(vec!["init", "--quiet"])
+            .current_dir(&peer_path)
+            .status()?;
+        assert_eq!(git_fsck_directory(&peer_path)?, None);
+
+        let object_directory = peer_path.join(".git").join("objects").join("ab");
+        std::fs::create_dir_all(&object_directory)?;
+        std::fs::write(object_directory.join("c".repeat(38)), "corrupt")?;
+        assert!(git_fsck_directory(&peer_path)?.is_some());
+        Ok(())
+    }
+}
@@ -33,6 +33,9 @@ pub struct Peer {
     pub parent_id: Option<crate::common::index::ID>,
 
     pub child_peer_ids: Option<SubPeerIds>,
+
+    /// Maximum depth of peers imported from this peer's store. Unlimited if None.
+    pub max_depth: Option<usize>,
 }
 
 impl Peer {
@@ -87,6 +90,7 @@ impl Default for Peer {
             git_url: crate::common::GitUrl::try_from(ROOT_DEFAULT_GIT_URL).unwrap(),
             parent_id: None,
             child_peer_ids: None,
+            max_depth: None,
         }
     }
 }
@@ -104,6 +108,7 @@ mod tests {
             git_url: crate::common::GitUrl::try_from("http://localhost")?,
             parent_id: None,
             child_peer_ids: None,
+            max_depth: None,
         };
         let other_peer = Peer {
             id: 0,
@@ -111,6 +116,7 @@ mod tests {
             git_url: crate::common::GitUrl::try_from("http://aA-localhost")?,
             parent_id: Some(42),
             child_peer_ids: None,
+            max_depth: None,
         };
         assert!(root_peer < other_peer);
         Ok(())
@@ -124,6 +130,7 @@ mod tests {
             git_url: crate::common::GitUrl::try_from("http://localhost")?,
             parent_id: Some(42),
             child_peer_ids: None,
+            max_depth: None,
         };
         let peer_2 = Peer {
             id: 0,
@@ -131,6 +138,7 @@ mod tests {
             git_url: crate::common::GitUrl::try_from("http://aA-localhost")?,
             parent_id: Some(42),
             child_peer_ids: None,
+            max_depth: None,
         };
         assert!(peer_1 > peer_2);
         Ok(())
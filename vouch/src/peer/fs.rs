@@ -12,10 +12,17 @@ fn get_submodule_storage_relative_path(
         git_url
     ))?;
 
-    let url_path_component = std::path::PathBuf::from(git_url.url().path());
-    let url_path_component = url_path_component.strip_prefix("/")?;
-
-    Ok(std::path::PathBuf::from(url_registry_component).join(url_path_component))
+    // Join URL path segments individually to use platform specific path separators.
+    let url_path_segments = git_url
+        .url()
+        .path_segments()
+        .ok_or(format_err!("Cannot derive path from URL: {:?}", git_url))?;
+
+    let mut path = std::path::PathBuf::from(url_registry_component);
+    for segment in url_path_segments.filter(|segment| !segment.is_empty()) {
+        path.push(segment);
+    }
+    Ok(path)
 }
 
 /// Add a peer as a child of the root peer via Git repository URL.
@@ -24,7 +31,8 @@ pub fn add(git_url: &crate::common::GitUrl, _tx: &mut StoreTransaction) -> Resul
 
     let submodule_relative_path = get_submodule_storage_relative_path(git_url)?;
     let peers_directory_name = paths.peers_directory.strip_prefix(&paths.root_directory)?;
-    let submodule_relative_path = peers_directory_name.join(submodule_relative_path);
+    let submodule_relative_path =
+        crate::common::fs::to_git_path(&peers_directory_name.join(submodule_relative_path))?;
 
     let args = vec![
         "submodule",
@@ -32,10 +40,7 @@ pub fn add(git_url: &crate::common::GitUrl, _tx: &mut StoreTransaction) -> Resul
         "--depth",
         "1",
         git_url.as_str(),
-        submodule_relative_path.to_str().ok_or(format_err!(
-            "Could not parse submodule path: {:?}",
-            submodule_relative_path
-        ))?,
+        &submodule_relative_path,
     ];
     crate::common::fs::git(args, &paths.root_directory)?;
     Ok(())
@@ -48,8 +53,7 @@ pub fn get_root_database() -> Result<rusqlite::Connection> {
 
 pub fn get_peer_database(peer_branch: &Vec<common::Peer>) -> Result<rusqlite::Connection> {
     let root_peer_paths = DataPaths::new()?;
-    let peer_path = get_peer_path(&peer_branch, &root_peer_paths.root_directory)?;
-    let paths = DataPaths::from_root_directory(&peer_path)?;
+    let paths = get_peer_data_paths(&peer_branch, &root_peer_paths.root_directory)?;
     Ok(rusqlite::Connection::open(paths.index_file)?)
 }
 
@@ -75,11 +79,17 @@ pub fn remove(peer_branch: &Vec<common::Peer>, tx: &mut StoreTransaction) -> Res
     Ok(())
 }
 
-/// Given a top level (directly followed) peer, fetches from origin/master.
-/// Returns true if a remote update is available.
+/// Fetch origin/master for a top level (directly followed) peer without merging it.
+///
+/// Returns true if a remote update is available. The update is merged by merge_update, which
+/// `sync` calls sequentially. Does not modify the index, so it is safe to call concurrently
+/// across peers.
 ///
 /// See: https://stackoverflow.com/questions/58768910/how-to-perform-git-pull-with-the-rust-git2-crate
-pub fn fetch_update(peer: &common::Peer, _tx: &mut StoreTransaction) -> Result<bool> {
+pub fn fetch_update(peer: &common::Peer) -> Result<bool> {
+    let span =
+        tracing::info_span!("peer_fetch_update", peer_alias = %peer.alias, git_url = %peer.git_url);
+    let _enter = span.enter();
     log::debug!("Fetching updates for top level peer: {}", peer.git_url);
     let paths = DataPaths::new()?;
 
@@ -98,6 +108,9 @@ pub fn fetch_update(peer: &common::Peer, _tx: &mut StoreTransaction) -> Result<b
 }
 
 pub fn merge_update(peer: &common::Peer, _tx: &mut StoreTransaction) -> Result<()> {
+    let span =
+        tracing::info_span!("peer_merge_update", peer_alias = %peer.alias, git_url = %peer.git_url);
+    let _enter = span.enter();
     let paths = DataPaths::new()?;
 
     let submodule_relative_path = get_submodule_storage_relative_path(&peer.git_url)?;
@@ -130,6 +143,58 @@ pub fn merge_update(peer: &common::Peer, _tx: &mut StoreTransaction) -> Result<(
     Ok(())
 }
 
+/// Commit signature verification status.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SignatureStatus {
+    Valid,
+    Invalid,
+    Unsigned,
+}
+
+/// Verify signatures of fetched peer commits which have not yet been merged.
+///
+/// Signatures are verified by git against the user's GPG keyring.
+/// Returns commit IDs and their signature status.
+pub fn verify_update_signatures(peer: &common::Peer) -> Result<Vec<(git2::Oid, SignatureStatus)>> {
+    let paths = DataPaths::new()?;
+
+    let submodule_relative_path = get_submodule_storage_relative_path(&peer.git_url)?;
+    let peer_submodule_path = paths.peers_directory.join(&submodule_relative_path);
+
+    let repo = git2::Repository::open(&peer_submodule_path)?;
+    let fetch_head = repo.find_reference("FETCH_HEAD")?;
+    let fetch_commit = fetch_head.peel_to_commit()?;
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(fetch_commit.id())?;
+    revwalk.hide_head()?;
+
+    let mut statuses = Vec::new();
+    for commit_id in revwalk {
+        let commit_id = commit_id?;
+        let status = match repo.extract_signature(&commit_id, None) {
+            Ok(_) => {
+                let is_valid = std::process::Command::new("git")
+                    .args(vec!["verify-commit", &commit_id.to_string()])
+                    .current_dir(&peer_submodule_path)
+                    .stdout(std::process::Stdio::null())
+                    .stderr(std::process::Stdio::null())
+                    .status()?
+                    .success();
+                if is_valid {
+                    SignatureStatus::Valid
+                } else {
+                    SignatureStatus::Invalid
+                }
+            }
+            Err(error) if error.code() == git2::ErrorCode::NotFound => SignatureStatus::Unsigned,
+            Err(error) => return Err(error.into()),
+        };
+        statuses.push((commit_id, status));
+    }
+    Ok(statuses)
+}
+
 fn remove_direct_follow(peer: &common::Peer, _tx: &mut StoreTransaction) -> Result<()> {
     let paths = DataPaths::new()?;
 
@@ -156,10 +221,10 @@ pub fn get_peer_path(
     peer_branch: &Vec<common::Peer>,
     root_directory: &std::path::PathBuf,
 ) -> Result<std::path::PathBuf> {
-    let mut peer_path = std::path::PathBuf::from("/");
+    let mut peer_path = root_directory.clone();
     for peer in peer_branch {
         if peer.is_root() {
-            peer_path = peer_path.join(&root_directory);
+            peer_path = root_directory.clone();
         } else {
             let relative_peer_directory = get_submodule_storage_relative_path(&peer.git_url)?;
             peer_path = DataPaths::from_root_directory(&peer_path)?
@@ -170,6 +235,15 @@ pub fn get_peer_path(
     Ok(peer_path)
 }
 
+/// Returns the data paths of the last peer in the given branch.
+pub fn get_peer_data_paths(
+    peer_branch: &Vec<common::Peer>,
+    root_directory: &std::path::PathBuf,
+) -> Result<DataPaths> {
+    let peer_path = get_peer_path(&peer_branch, &root_directory)?;
+    DataPaths::from_root_directory(&peer_path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -29,6 +29,38 @@ pub fn get_root(tx: &StoreTransaction) -> Result<Option<common::Peer>> {
     .map(|x| x.clone()))
 }
 
+/// Returns the peer identified by the given alias or git URL.
+///
+/// Aliases take precedence over git URLs.
+pub fn get_by_alias_or_git_url(value: &str, tx: &StoreTransaction) -> Result<Option<common::Peer>> {
+    if let Some(peer) = get(
+        &Fields {
+            alias: Some(value),
+            ..Default::default()
+        },
+        &tx,
+    )?
+    .into_iter()
+    .next()
+    {
+        return Ok(Some(peer));
+    }
+
+    let git_url = match crate::common::GitUrl::try_from(value) {
+        Ok(git_url) => git_url,
+        Err(_) => return Ok(None),
+    };
+    Ok(get(
+        &Fields {
+            git_url: Some(&git_url),
+            ..Default::default()
+        },
+        &tx,
+    )?
+    .into_iter()
+    .next())
+}
+
 pub fn setup(tx: &StoreTransaction) -> Result<()> {
     tx.index_tx().execute(
         "
@@ -43,8 +75,13 @@ pub fn setup(tx: &StoreTransaction) -> Result<()> {
     )",
         rusqlite::NO_PARAMS,
     )?;
+    Ok(())
+}
 
-    // Insert root peer if absent.
+/// Insert root peer if absent.
+///
+/// Must be called after index schema migrations have been applied.
+pub fn ensure_root(tx: &StoreTransaction) -> Result<()> {
     let found_root_peer = !get(
         &Fields {
             alias: Some(common::ROOT_ALIAS),
@@ -94,6 +131,7 @@ pub fn insert(
         git_url: git_url.clone(),
         parent_id: parent_id,
         child_peer_ids: None,
+        max_depth: None,
     };
 
     if let Some(parent_peer) = parent_peer {
@@ -103,6 +141,24 @@ pub fn insert(
     Ok(new_peer)
 }
 
+/// Set the maximum depth of peers imported from a peer's store.
+pub fn set_max_depth(
+    peer: &mut common::Peer,
+    max_depth: Option<usize>,
+    tx: &StoreTransaction,
+) -> Result<()> {
+    tx.index_tx().execute(
+        r"
+        UPDATE peer
+        SET max_depth = ?2
+        WHERE id = ?1
+    ",
+        rusqlite::params![peer.id, max_depth.map(|max_depth| max_depth as i64)],
+    )?;
+    peer.max_depth = max_depth;
+    Ok(())
+}
+
 /// Given a peer, extend its child peer set.
 fn add_child_peer_id(
     peer: &mut common::Peer,
@@ -171,7 +227,7 @@ pub fn get(fields: &Fields, tx: &StoreTransaction) -> Result<HashSet<common::Pee
     );
 
     let sql_query = r"
-        SELECT *
+        SELECT id, alias, git_url, parent_id, child_peer_ids, max_depth
         FROM peer
         WHERE
             id LIKE :id ESCAPE '\'
@@ -211,6 +267,9 @@ pub fn get(fields: &Fields, tx: &StoreTransaction) -> Result<HashSet<common::Pee
             git_url,
             parent_id: row.get(3)?,
             child_peer_ids,
+            max_depth: row
+                .get::<_, Option<i64>>(5)?
+                .map(|max_depth| max_depth as usize),
         });
     }
     Ok(peers)
@@ -293,8 +352,12 @@ pub fn get_peer_branch(peer: &common::Peer, tx: &StoreTransaction) -> Result<Vec
 }
 
 /// Merge peers from incoming index into another index. Returns the newly merged peers.
+///
+/// The incoming root peer is at depth 1. If given, incoming peers deeper than the maximum depth
+/// are not merged.
 pub fn merge(
     incoming_root_git_url: &crate::common::GitUrl,
+    max_depth: Option<usize>,
     incoming_tx: &StoreTransaction,
     tx: &StoreTransaction,
 ) -> Result<HashSet<common::Peer>> {
@@ -335,7 +398,7 @@ pub fn merge(
     ))?;
 
     for subtree in get_peer_subtrees(None, &incoming_tx)? {
-        for peer_pair in subtree.windows(2) {
+        for (index, peer_pair) in subtree.windows(2).enumerate() {
             let parent_peer = &peer_pair[0];
             let peer = &peer_pair[1];
 
@@ -352,6 +415,10 @@ pub fn merge(
                 insert_peer(&parent_peer, Some(&root_peer))?;
             }
 
+            let depth = index + 2;
+            if max_depth.map_or(false, |max_depth| depth > max_depth) {
+                break;
+            }
             insert_peer(peer, Some(&parent_peer))?;
         }
     }
@@ -361,11 +428,43 @@ pub fn merge(
     Ok(inserted_peers)
 }
 
+/// Repository hosts which namespace repositories by user name.
+static USER_NAMESPACED_HOSTS: &[&str] = &["github.com", "gitlab.com"];
+
+/// Returns a new unique alias for a peer.
+///
+/// Peers hosted on known user namespaced hosts, such as GitHub, are aliased by user name.
+/// No network requests are made.
 pub fn get_new_alias(git_url: &crate::common::GitUrl, tx: &StoreTransaction) -> Result<String> {
+    get_alias(&git_url, is_user_namespaced_host(&git_url), &tx)
+}
+
+/// Returns a new unique alias for a peer, also checking for self-hosted Forgejo/Gitea hosts.
+///
+/// Forgejo/Gitea hosts are detected with a request to the host's API.
+pub fn get_new_alias_with_host_check(
+    git_url: &crate::common::GitUrl,
+    tx: &StoreTransaction,
+) -> Result<String> {
+    let is_user_namespaced =
+        is_user_namespaced_host(&git_url) || crate::common::gitea::is_gitea_repo(&git_url.url());
+    get_alias(&git_url, is_user_namespaced, &tx)
+}
+
+fn is_user_namespaced_host(git_url: &crate::common::GitUrl) -> bool {
+    git_url
+        .url()
+        .host_str()
+        .map_or(false, |host| USER_NAMESPACED_HOSTS.contains(&host))
+}
+
+fn get_alias(
+    git_url: &crate::common::GitUrl,
+    is_user_namespaced: bool,
+    tx: &StoreTransaction,
+) -> Result<String> {
     let mut alias = git_url.as_str();
-    if git_url.url().host_str() == Some("github.com")
-        || git_url.url().host_str() == Some("gitlab.com")
-    {
+    if is_user_namespaced {
         let segments = git_url.url().path_segments().map(|c| c.collect::<Vec<_>>());
         if let Some(segments) = segments {
             if let Some(first_segment) = segments.first() {
@@ -485,7 +584,7 @@ mod tests {
         let mut incoming_db = rusqlite::Connection::open_in_memory()?;
         let incoming_tx = StoreTransaction::new(incoming_db.transaction()?)?;
 
-        setup(&incoming_tx)?;
+        crate::store::index::setup(&incoming_tx)?;
         // root_incoming -> peer_1 -> peer_2
         let mut root_peer = get_root(&incoming_tx)?.unwrap();
         let mut peer_1 = insert(
@@ -504,7 +603,7 @@ mod tests {
         // Setup destination database.
         let mut db = rusqlite::Connection::open_in_memory()?;
         let tx = StoreTransaction::new(db.transaction()?)?;
-        setup(&tx)?;
+        crate::store::index::setup(&tx)?;
         // root -> peer_3 -> peer_2
         let mut root_peer = get_root(&tx)?.unwrap();
         let mut peer_3 = insert(
@@ -525,7 +624,7 @@ mod tests {
         // root -> peer_3 -> peer_2
         let incoming_root_git_url =
             crate::common::GitUrl::try_from("https://localhost/root_incoming")?;
-        merge(&incoming_root_git_url, &incoming_tx, &tx)?;
+        merge(&incoming_root_git_url, None, &incoming_tx, &tx)?;
 
         let result: HashSet<common::Peer> = get(&Fields::default(), &tx)?.into_iter().collect();
         let expected = maplit::hashset! {
@@ -535,6 +634,7 @@ mod tests {
                 git_url: crate::common::GitUrl::try_from("https://localhost")?,
                 parent_id: None,
                 child_peer_ids: Some(common::SubPeerIds(maplit::btreeset! {2, 4})),
+                max_depth: None,
             },
             common::Peer {
                 id: 2,
@@ -542,6 +642,7 @@ mod tests {
                 git_url: crate::common::GitUrl::try_from("https://localhost/peer_3")?,
                 parent_id: Some(1),
                 child_peer_ids: Some(common::SubPeerIds(maplit::btreeset! {3})),
+                max_depth: None,
             },
             common::Peer {
                 id: 3,
@@ -549,6 +650,7 @@ mod tests {
                 git_url: crate::common::GitUrl::try_from("https://localhost/peer_2")?,
                 parent_id: Some(2),
                 child_peer_ids: None,
+                max_depth: None,
             },
             common::Peer {
                 id: 4,
@@ -556,6 +658,7 @@ mod tests {
                 git_url: crate::common::GitUrl::try_from("https://localhost/root_incoming")?,
                 parent_id: Some(1),
                 child_peer_ids: Some(common::SubPeerIds(maplit::btreeset! {5})),
+                max_depth: None,
             },
             common::Peer {
                 id: 5,
@@ -563,6 +666,7 @@ mod tests {
                 git_url: crate::common::GitUrl::try_from("https://localhost/peer_1")?,
                 parent_id: Some(4),
                 child_peer_ids: None,
+                max_depth: None,
             },
         };
         let unexpected_peers = crate::common::index::get_difference_sans_id(&result, &expected)?;
@@ -578,7 +682,7 @@ mod tests {
 
         let incoming_root_git_url =
             crate::common::GitUrl::try_from("https://localhost/root_incoming")?;
-        setup(&incoming_tx)?;
+        crate::store::index::setup(&incoming_tx)?;
         // root_incoming -> peer_1 -> peer_2
         let mut root_peer = get_root(&incoming_tx)?.unwrap();
         let mut peer_1 = insert(
@@ -597,7 +701,7 @@ mod tests {
         // Setup destination database.
         let mut db = rusqlite::Connection::open_in_memory()?;
         let tx = StoreTransaction::new(db.transaction()?)?;
-        setup(&tx)?;
+        crate::store::index::setup(&tx)?;
         // root -> peer_3 -> peer_2
         let mut root_peer = get_root(&tx)?.unwrap();
         let mut peer_3 = insert(
@@ -616,7 +720,7 @@ mod tests {
         // Merge incoming and destination databases.
         // root -> root_incoming -> peer_1
         // root -> peer_3 -> peer_2
-        let result = merge(&incoming_root_git_url, &incoming_tx, &tx)?;
+        let result = merge(&incoming_root_git_url, None, &incoming_tx, &tx)?;
 
         let expected = maplit::hashset! {
             common::Peer {
@@ -625,6 +729,7 @@ mod tests {
                 git_url: crate::common::GitUrl::try_from("https://localhost/root_incoming")?,
                 parent_id: Some(1),
                 child_peer_ids: Some(common::SubPeerIds(maplit::btreeset! {5})),
+                max_depth: None,
             },
             common::Peer {
                 id: 5,
@@ -632,6 +737,7 @@ mod tests {
                 git_url: crate::common::GitUrl::try_from("https://localhost/peer_1")?,
                 parent_id: Some(4),
                 child_peer_ids: None,
+                max_depth: None,
             },
         };
         let unexpected_peers = crate::common::index::get_difference_sans_id(&result, &expected)?;
@@ -639,11 +745,109 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_merge_respects_max_depth() -> Result<()> {
+        // Setup incoming database.
+        let mut incoming_db = rusqlite::Connection::open_in_memory()?;
+        let incoming_tx = StoreTransaction::new(incoming_db.transaction()?)?;
+        crate::store::index::setup(&incoming_tx)?;
+        // root_incoming -> peer_1 -> peer_2
+        let mut root_peer = get_root(&incoming_tx)?.unwrap();
+        let mut peer_1 = insert(
+            "peer_1",
+            &crate::common::GitUrl::try_from("https://localhost/peer_1")?,
+            Some(&mut root_peer),
+            &incoming_tx,
+        )?;
+        insert(
+            "peer_2",
+            &crate::common::GitUrl::try_from("https://localhost/peer_2")?,
+            Some(&mut peer_1),
+            &incoming_tx,
+        )?;
+
+        // Setup destination database.
+        let mut db = rusqlite::Connection::open_in_memory()?;
+        let tx = StoreTransaction::new(db.transaction()?)?;
+        crate::store::index::setup(&tx)?;
+
+        // Merge incoming database up to peer_1.
+        // root -> root_incoming -> peer_1
+        let incoming_root_git_url =
+            crate::common::GitUrl::try_from("https://localhost/root_incoming")?;
+        merge(&incoming_root_git_url, Some(2), &incoming_tx, &tx)?;
+
+        let result: std::collections::BTreeSet<_> = get(&Fields::default(), &tx)?
+            .into_iter()
+            .map(|peer| peer.git_url.to_string())
+            .collect();
+        let expected = maplit::btreeset! {
+            crate::common::GitUrl::try_from("https://localhost")?.to_string(),
+            incoming_root_git_url.to_string(),
+            crate::common::GitUrl::try_from("https://localhost/peer_1")?.to_string(),
+        };
+        assert_eq!(result, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_max_depth() -> Result<()> {
+        let mut db = rusqlite::Connection::open_in_memory()?;
+        let tx = StoreTransaction::new(db.transaction()?)?;
+        crate::store::index::setup(&tx)?;
+        let mut root_peer = get_root(&tx)?.unwrap();
+        let mut peer = insert(
+            "peer_1",
+            &crate::common::GitUrl::try_from("https://localhost/peer_1")?,
+            Some(&mut root_peer),
+            &tx,
+        )?;
+
+        set_max_depth(&mut peer, Some(2), &tx)?;
+
+        let result = get(
+            &Fields {
+                id: Some(peer.id),
+                ..Default::default()
+            },
+            &tx,
+        )?;
+        assert_eq!(result, maplit::hashset! {peer.clone()});
+        assert_eq!(peer.max_depth, Some(2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_new_alias() -> Result<()> {
+        let mut db = rusqlite::Connection::open_in_memory()?;
+        let tx = StoreTransaction::new(db.transaction()?)?;
+        crate::store::index::setup(&tx)?;
+
+        let git_url = crate::common::GitUrl::try_from("https://github.com/user_1/reviews")?;
+        assert_eq!(get_new_alias(&git_url, &tx)?, "user_1");
+
+        // Unknown hosts are not queried.
+        let git_url = crate::common::GitUrl::try_from("https://localhost/user_2/reviews")?;
+        assert_eq!(get_new_alias(&git_url, &tx)?, git_url.to_string());
+
+        // Aliases in use are not reassigned.
+        let mut root_peer = get_root(&tx)?.unwrap();
+        insert(
+            "user_3",
+            &crate::common::GitUrl::try_from("https://gitlab.com/user_3/other")?,
+            Some(&mut root_peer),
+            &tx,
+        )?;
+        let git_url = crate::common::GitUrl::try_from("https://github.com/user_3/reviews")?;
+        assert_eq!(get_new_alias(&git_url, &tx)?, git_url.to_string());
+        Ok(())
+    }
+
     #[test]
     fn test_get_peer_subtrees() -> Result<()> {
         let mut db = rusqlite::Connection::open_in_memory()?;
         let tx = StoreTransaction::new(db.transaction()?)?;
-        setup(&tx)?;
+        crate::store::index::setup(&tx)?;
         let mut root_peer = get_root(&tx)?.unwrap();
 
         // root -> peer_1 -> peer_2
@@ -681,7 +885,7 @@ mod tests {
     fn test_insert_peers_correct_child_peer_ids() -> Result<()> {
         let mut db = rusqlite::Connection::open_in_memory()?;
         let tx = StoreTransaction::new(db.transaction()?)?;
-        setup(&tx)?;
+        crate::store::index::setup(&tx)?;
         let mut root_peer = get_root(&tx)?.unwrap();
         insert(
             "new_peer",
@@ -699,6 +903,7 @@ mod tests {
                 git_url: crate::common::GitUrl::try_from(common::ROOT_DEFAULT_GIT_URL)?,
                 parent_id: None,
                 child_peer_ids: Some(common::SubPeerIds(maplit::btreeset! { 2 as i64 })),
+                max_depth: None,
             },
             common::Peer {
                 id: 2,
@@ -706,17 +911,49 @@ mod tests {
                 git_url: crate::common::GitUrl::try_from("https://localhost/new_peer")?,
                 parent_id: Some(1),
                 child_peer_ids: None,
+                max_depth: None,
             },
         };
         assert_eq!(result, expected);
         Ok(())
     }
 
+    #[test]
+    fn test_get_peer_branch() -> Result<()> {
+        let mut db = rusqlite::Connection::open_in_memory()?;
+        let tx = StoreTransaction::new(db.transaction()?)?;
+        crate::store::index::setup(&tx)?;
+        // root -> peer_1 -> peer_2
+        let mut root_peer = get_root(&tx)?.unwrap();
+        let mut peer_1 = insert(
+            "peer_1",
+            &crate::common::GitUrl::try_from("https://localhost/peer_1")?,
+            Some(&mut root_peer),
+            &tx,
+        )?;
+        let peer_2 = insert(
+            "peer_2",
+            &crate::common::GitUrl::try_from("https://localhost/peer_2")?,
+            Some(&mut peer_1),
+            &tx,
+        )?;
+
+        let result: Vec<_> = get_peer_branch(&peer_2, &tx)?
+            .into_iter()
+            .map(|peer| peer.alias)
+            .collect();
+        assert_eq!(result, vec![common::ROOT_ALIAS, "peer_1", "peer_2"]);
+
+        let result = get_peer_branch(&root_peer, &tx)?;
+        assert_eq!(result.len(), 1);
+        Ok(())
+    }
+
     #[test]
     fn test_delete_peer() -> Result<()> {
         let mut db = rusqlite::Connection::open_in_memory()?;
         let tx = StoreTransaction::new(db.transaction()?)?;
-        setup(&tx)?;
+        crate::store::index::setup(&tx)?;
         let mut root_peer = get_root(&tx)?.unwrap();
 
         insert(
@@ -741,8 +978,79 @@ mod tests {
             git_url: crate::common::GitUrl::try_from("https://localhost")?,
             parent_id: None,
             child_peer_ids: None,
+            max_depth: None,
         }};
         assert_eq!(result, expected);
         Ok(())
     }
+
+    mod get_by_alias_or_git_url {
+        use super::*;
+
+        fn setup_peers(tx: &StoreTransaction) -> Result<(common::Peer, common::Peer)> {
+            crate::store::index::setup(&tx)?;
+            let mut root_peer = get_root(&tx)?.unwrap();
+            // The first peer's alias matches the second peer's git URL.
+            let peer_1 = insert(
+                "https://localhost/peer_2",
+                &crate::common::GitUrl::try_from("https://localhost/peer_1")?,
+                Some(&mut root_peer),
+                &tx,
+            )?;
+            let peer_2 = insert(
+                "peer_2",
+                &crate::common::GitUrl::try_from("https://localhost/peer_2")?,
+                Some(&mut root_peer),
+                &tx,
+            )?;
+            Ok((peer_1, peer_2))
+        }
+
+        #[test]
+        fn test_found_using_alias() -> Result<()> {
+            let mut db = rusqlite::Connection::open_in_memory()?;
+            let tx = StoreTransaction::new(db.transaction()?)?;
+            let (_peer_1, peer_2) = setup_peers(&tx)?;
+
+            let result = get_by_alias_or_git_url("peer_2", &tx)?;
+            assert_eq!(result, Some(peer_2));
+            Ok(())
+        }
+
+        #[test]
+        fn test_found_using_git_url() -> Result<()> {
+            let mut db = rusqlite::Connection::open_in_memory()?;
+            let tx = StoreTransaction::new(db.transaction()?)?;
+            let (peer_1, _peer_2) = setup_peers(&tx)?;
+
+            let result = get_by_alias_or_git_url("https://localhost/peer_1.git", &tx)?;
+            assert_eq!(result, Some(peer_1));
+            Ok(())
+        }
+
+        #[test]
+        fn test_alias_preferred_over_git_url() -> Result<()> {
+            let mut db = rusqlite::Connection::open_in_memory()?;
+            let tx = StoreTransaction::new(db.transaction()?)?;
+            let (peer_1, _peer_2) = setup_peers(&tx)?;
+
+            let result = get_by_alias_or_git_url("https://localhost/peer_2", &tx)?;
+            assert_eq!(result, Some(peer_1));
+            Ok(())
+        }
+
+        #[test]
+        fn test_no_match() -> Result<()> {
+            let mut db = rusqlite::Connection::open_in_memory()?;
+            let tx = StoreTransaction::new(db.transaction()?)?;
+            setup_peers(&tx)?;
+
+            assert_eq!(get_by_alias_or_git_url("peer_3", &tx)?, None);
+            assert_eq!(
+                get_by_alias_or_git_url("https://localhost/peer_3", &tx)?,
+                None
+            );
+            Ok(())
+        }
+    }
 }
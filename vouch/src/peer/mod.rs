@@ -1,3 +1,4 @@
+pub mod check;
 mod common;
 pub mod fs;
 pub mod index;
@@ -1,5 +1,9 @@
+use anyhow::{format_err, Result};
 use std::hash::Hash;
 
+/// Timeout for registry artifact URL validation requests.
+static VALIDATION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
 #[derive(
     Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, serde::Serialize, serde::Deserialize,
 )]
@@ -28,3 +32,29 @@ impl crate::common::index::Identify for Registry {
         &mut self.id
     }
 }
+
+/// Check that a registry artifact URL is reachable without downloading the artifact.
+///
+/// A 404 response is accepted: the URL structure is correct even if the artifact does not exist.
+pub fn validate_artifact_url(url: &url::Url) -> Result<()> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(VALIDATION_TIMEOUT)
+        .build()?;
+    let response = client.head(url.clone()).send().map_err(|error| {
+        format_err!(
+            "Registry artifact URL is unreachable: {url}\n{error}",
+            url = url,
+            error = error
+        )
+    })?;
+
+    let status = response.status();
+    if status.is_success() || status == reqwest::StatusCode::NOT_FOUND {
+        return Ok(());
+    }
+    Err(format_err!(
+        "Registry artifact URL validation failed: {url} (HTTP status: {status})",
+        url = url,
+        status = status
+    ))
+}
@@ -0,0 +1,61 @@
+//! Registry identifiers used by external package services.
+
+/// Identifiers of a supported registry's package ecosystem.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Ecosystem {
+    /// Registry host name. Example: pypi.org
+    pub registry_host_name: &'static str,
+
+    /// OSV ecosystem name. Example: PyPI
+    pub osv_name: &'static str,
+
+    /// Package URL type. Example: pypi
+    pub package_url_type: &'static str,
+}
+
+static ECOSYSTEMS: &[Ecosystem] = &[
+    Ecosystem {
+        registry_host_name: "pypi.org",
+        osv_name: "PyPI",
+        package_url_type: "pypi",
+    },
+    Ecosystem {
+        registry_host_name: "npmjs.com",
+        osv_name: "npm",
+        package_url_type: "npm",
+    },
+    Ecosystem {
+        registry_host_name: "crates.io",
+        osv_name: "crates.io",
+        package_url_type: "cargo",
+    },
+];
+
+/// Returns the ecosystem of a registry.
+pub fn from_registry_host_name(registry_host_name: &str) -> Option<&'static Ecosystem> {
+    ECOSYSTEMS
+        .iter()
+        .find(|ecosystem| ecosystem.registry_host_name == registry_host_name)
+}
+
+/// Returns the ecosystem of a package URL. Example: pkg:npm/d3@4.10.0
+pub fn from_package_url(package_url: &str) -> Option<&'static Ecosystem> {
+    let package_url_type = package_url.strip_prefix("pkg:")?.split('/').next()?;
+    ECOSYSTEMS
+        .iter()
+        .find(|ecosystem| ecosystem.package_url_type == package_url_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_package_url() {
+        let result =
+            from_package_url("pkg:cargo/serde@1.0.0").map(|ecosystem| ecosystem.registry_host_name);
+        assert_eq!(result, Some("crates.io"));
+        assert_eq!(from_package_url("pkg:maven/org.apache/commons@1.0"), None);
+        assert_eq!(from_package_url("npm/d3@4.10.0"), None);
+    }
+}
@@ -2,7 +2,10 @@ use anyhow::Result;
 
 use super::common;
 use crate::common::StoreTransaction;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+
+/// Maximum number of registry IDs bound to a single query.
+static MAX_IDS_PER_QUERY: usize = 500;
 
 pub fn setup(tx: &StoreTransaction) -> Result<()> {
     tx.index_tx().execute(
@@ -97,6 +100,35 @@ pub fn get(fields: &Fields, tx: &StoreTransaction) -> Result<HashSet<common::Reg
     Ok(registries)
 }
 
+/// Returns registries keyed on ID using a single query per batch of IDs.
+///
+/// Unknown IDs are omitted from the result.
+pub fn get_by_ids(
+    ids: &[crate::common::index::ID],
+    tx: &StoreTransaction,
+) -> Result<HashMap<crate::common::index::ID, common::Registry>> {
+    let mut registries = HashMap::new();
+    for ids in ids.chunks(MAX_IDS_PER_QUERY) {
+        let placeholders = vec!["?"; ids.len()].join(",");
+        let sql_query = format!(
+            "SELECT * FROM registry WHERE id IN ({placeholders})",
+            placeholders = placeholders
+        );
+        let mut statement = tx.index_tx().prepare(&sql_query)?;
+        let mut rows = statement.query(ids)?;
+        while let Some(row) = rows.next()? {
+            let registry = common::Registry {
+                id: row.get(0)?,
+                host_name: row.get(1)?,
+                human_url: url::Url::parse(row.get::<_, String>(2)?.as_str())?,
+                artifact_url: url::Url::parse(row.get::<_, String>(3)?.as_str())?,
+            };
+            registries.insert(registry.id, registry);
+        }
+    }
+    Ok(registries)
+}
+
 /// Merge registries from incoming index into another index. Returns the newly merged registries.
 pub fn merge(
     incoming_tx: &StoreTransaction,
@@ -120,13 +152,46 @@ pub fn merge(
     Ok(new_registries)
 }
 
+/// Returns the matching registry, inserting it if it does not already exist.
+///
+/// The artifact URL of a new registry is validated before insertion.
 pub fn ensure(
     host_name: &str,
     human_url: &url::Url,
     artifact_url: &url::Url,
     tx: &StoreTransaction,
 ) -> Result<common::Registry> {
-    let registry = get(
+    if let Some(registry) = get_existing(&host_name, &human_url, &artifact_url, &tx)? {
+        return Ok(registry);
+    }
+    super::validate_artifact_url(&artifact_url)?;
+    insert(&host_name, &human_url, &artifact_url, &tx)
+}
+
+/// Returns the matching registry, inserting it without validation if it does not already exist.
+///
+/// Used when merging registries from peer indexes.
+pub fn get_or_insert(
+    host_name: &str,
+    human_url: &url::Url,
+    artifact_url: &url::Url,
+    tx: &StoreTransaction,
+) -> Result<common::Registry> {
+    Ok(
+        match get_existing(&host_name, &human_url, &artifact_url, &tx)? {
+            Some(registry) => registry,
+            None => insert(&host_name, &human_url, &artifact_url, &tx)?,
+        },
+    )
+}
+
+fn get_existing(
+    host_name: &str,
+    human_url: &url::Url,
+    artifact_url: &url::Url,
+    tx: &StoreTransaction,
+) -> Result<Option<common::Registry>> {
+    Ok(get(
         &Fields {
             host_name: Some(host_name),
             human_url: Some(human_url.as_str()),
@@ -136,10 +201,5 @@ pub fn ensure(
         &tx,
     )?
     .into_iter()
-    .next();
-
-    Ok(match registry {
-        Some(registry) => registry,
-        None => insert(&host_name, &human_url, &artifact_url, &tx)?,
-    })
+    .next())
 }
@@ -1,4 +1,5 @@
 pub mod common;
+pub mod ecosystem;
 pub mod index;
 
-pub use common::Registry;
+pub use common::{validate_artifact_url, Registry};
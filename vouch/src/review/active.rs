@@ -1,5 +1,5 @@
 use crate::review;
-use anyhow::{Context, Result};
+use anyhow::{format_err, Context, Result};
 use std::io::Write;
 
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
@@ -8,27 +8,126 @@ struct ActiveReview {
     pub description: String,
     #[serde(rename = "isPrimary")]
     pub is_primary: Option<bool>,
+    #[serde(
+        serialize_with = "serialize_comments",
+        deserialize_with = "deserialize_comments"
+    )]
     pub comments: std::collections::BTreeSet<review::comment::Comment>,
 }
 
+/// Active review file comments format.
+#[derive(serde::Deserialize, Debug)]
+#[serde(untagged)]
+enum CommentsFormat {
+    /// Comments grouped by file path.
+    Grouped(std::collections::BTreeMap<std::path::PathBuf, Vec<review::comment::Comment>>),
+
+    /// Legacy flat comments array.
+    Flat(std::collections::BTreeSet<review::comment::Comment>),
+}
+
+/// Write comments grouped by file path.
+fn serialize_comments<S>(
+    comments: &std::collections::BTreeSet<review::comment::Comment>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    let mut grouped_comments = std::collections::BTreeMap::<_, Vec<_>>::new();
+    for comment in comments {
+        grouped_comments
+            .entry(comment.path.clone())
+            .or_default()
+            .push(comment);
+    }
+    serde::Serialize::serialize(&grouped_comments, serializer)
+}
+
+/// Read comments grouped by file path, or as a flat array.
+fn deserialize_comments<'de, D>(
+    deserializer: D,
+) -> std::result::Result<std::collections::BTreeSet<review::comment::Comment>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(
+        match <CommentsFormat as serde::Deserialize>::deserialize(deserializer)? {
+            CommentsFormat::Grouped(grouped_comments) => {
+                grouped_comments.into_values().flatten().collect()
+            }
+            CommentsFormat::Flat(comments) => comments,
+        },
+    )
+}
+
+static JSON_FILE_NAME: &str = "local.review";
+static YAML_FILE_NAME: &str = "local.review.yaml";
+
 /// Ensure active review file is in place.
+///
+/// Package maintainers, if known, are listed in the review description.
+/// An existing review file is reused regardless of the given file format.
 pub fn ensure(
     review: &review::Review,
+    maintainers: &Vec<String>,
     reviews_directory: &std::path::PathBuf,
+    review_file_format: &str,
 ) -> Result<std::path::PathBuf> {
-    let review_file_path = reviews_directory.join("local.review");
-    if review_file_path.exists() {
+    if let Some(review_file_path) = find(&reviews_directory) {
         return Ok(review_file_path);
     }
+    create(
+        &get_description(&review, &maintainers),
+        &review.comments,
+        &reviews_directory,
+        &review_file_format,
+    )
+}
+
+/// Returns the active review description for the given package review.
+pub fn get_description(review: &review::Review, maintainers: &Vec<String>) -> String {
+    let mut description = format!(
+        "Package name-version: {}-{}",
+        review.package.name, review.package.version
+    );
+    if !maintainers.is_empty() {
+        description.push_str(&format!("\nMaintainers: {}", maintainers.join(", ")));
+    }
+    description
+}
+
+/// Create an active review file with the given description and comments.
+///
+/// Fails if an active review file already exists.
+pub fn create(
+    description: &str,
+    comments: &std::collections::BTreeSet<review::comment::Comment>,
+    reviews_directory: &std::path::PathBuf,
+    review_file_format: &str,
+) -> Result<std::path::PathBuf> {
+    if let Some(review_file_path) = find(&reviews_directory) {
+        return Err(format_err!(
+            "Active review file already exists: {}",
+            review_file_path.display()
+        ));
+    }
+    let review_file_path = match review_file_format {
+        "json" => reviews_directory.join(JSON_FILE_NAME),
+        "yaml" => reviews_directory.join(YAML_FILE_NAME),
+        _ => {
+            return Err(format_err!(
+                "Unknown review file format: {}\nSee config field: review-tool.review-file-format",
+                review_file_format
+            ))
+        }
+    };
 
     let active_review = ActiveReview {
         title: "local".to_string(),
-        description: format!(
-            "Package name-version: {}-{}",
-            review.package.name, review.package.version
-        ),
+        description: description.to_string(),
         is_primary: Some(true),
-        comments: review.comments.clone(),
+        comments: comments.clone(),
     };
 
     let mut file = std::fs::OpenOptions::new()
@@ -40,16 +139,172 @@ pub fn ensure(
             "Can't open/create file for writing: {}",
             review_file_path.display()
         ))?;
-    file.write_all(serde_json::to_string_pretty(&active_review)?.as_bytes())?;
+    let contents = if is_yaml(&review_file_path) {
+        serde_yaml::to_string(&active_review)?
+    } else {
+        serde_json::to_string_pretty(&active_review)?
+    };
+    file.write_all(contents.as_bytes())?;
     Ok(review_file_path)
 }
 
+/// Returns the path of the existing active review file, if any.
+pub fn find(reviews_directory: &std::path::PathBuf) -> Option<std::path::PathBuf> {
+    [JSON_FILE_NAME, YAML_FILE_NAME]
+        .iter()
+        .map(|file_name| reviews_directory.join(file_name))
+        .find(|review_file_path| review_file_path.exists())
+}
+
+fn is_yaml(path: &std::path::PathBuf) -> bool {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) => extension == "yaml" || extension == "yml",
+        None => false,
+    }
+}
+
+/// Active review file serialization format.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum FileFormat {
+    Json,
+    Yaml,
+}
+
+/// Parse active review file contents.
+///
+/// The format is detected from the contents rather than the file name. JSON is attempted first.
+fn deserialize(contents: &str) -> Result<(ActiveReview, FileFormat)> {
+    let json_error = match serde_json::from_str(&contents) {
+        Ok(active_review) => return Ok((active_review, FileFormat::Json)),
+        Err(error) => error,
+    };
+    let yaml_error = match serde_yaml::from_str(&contents) {
+        Ok(active_review) => return Ok((active_review, FileFormat::Yaml)),
+        Err(error) => error,
+    };
+    Err(format_err!(
+        "Failed to parse active review file as JSON or YAML.\n\
+        JSON error: {}\n\
+        YAML error: {}",
+        json_error,
+        yaml_error
+    ))
+}
+
 pub fn parse(
     path: &std::path::PathBuf,
 ) -> Result<std::collections::BTreeSet<review::comment::Comment>> {
-    let file = std::fs::File::open(path)?;
-    let reader = std::io::BufReader::new(file);
-
-    let active_review: review::active::ActiveReview = serde_json::from_reader(reader)?;
+    let contents = std::fs::read_to_string(&path)?;
+    let (active_review, _format) =
+        deserialize(&contents).context(format!("Review file: {}", path.display()))?;
     Ok(active_review.comments)
 }
+
+/// Add comments to an existing active review file.
+///
+/// The file is rewritten in the format in which it was read.
+pub fn add_comments(
+    path: &std::path::PathBuf,
+    comments: &std::collections::BTreeSet<review::comment::Comment>,
+) -> Result<()> {
+    let contents = std::fs::read_to_string(&path)?;
+    let (mut active_review, format) =
+        deserialize(&contents).context(format!("Review file: {}", path.display()))?;
+    active_review.comments.extend(comments.iter().cloned());
+
+    let contents = match format {
+        FileFormat::Json => serde_json::to_string_pretty(&active_review)?,
+        FileFormat::Yaml => serde_yaml::to_string(&active_review)?,
+    };
+    std::fs::write(&path, contents)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_comment() -> review::comment::Comment {
+        review::comment::Comment {
+            id: 0,
+            path: std::path::PathBuf::from("setup.py"),
+            summary: review::Summary::Warn,
+            message: "test_message".to_string(),
+            selection: None,
+        }
+    }
+
+    fn write_active_review(path: &std::path::PathBuf) -> Result<()> {
+        let active_review = ActiveReview {
+            title: "local".to_string(),
+            description: "test_description".to_string(),
+            is_primary: Some(true),
+            comments: maplit::btreeset! {get_comment()},
+        };
+        let contents = if is_yaml(&path) {
+            serde_yaml::to_string(&active_review)?
+        } else {
+            serde_json::to_string_pretty(&active_review)?
+        };
+        std::fs::write(&path, contents)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_json_review_file() -> Result<()> {
+        let tmp_dir = tempdir::TempDir::new("vouch_test_parse_json_review_file")?;
+        let path = tmp_dir.path().join(JSON_FILE_NAME);
+        write_active_review(&path)?;
+
+        assert_eq!(parse(&path)?, maplit::btreeset! {get_comment()});
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_yaml_review_file() -> Result<()> {
+        let tmp_dir = tempdir::TempDir::new("vouch_test_parse_yaml_review_file")?;
+        let path = tmp_dir.path().join(YAML_FILE_NAME);
+        write_active_review(&path)?;
+
+        assert!(std::fs::read_to_string(&path)?.starts_with("---"));
+        assert_eq!(parse(&path)?, maplit::btreeset! {get_comment()});
+        Ok(())
+    }
+
+    #[test]
+    fn test_comments_grouped_by_path() -> Result<()> {
+        let tmp_dir = tempdir::TempDir::new("vouch_test_comments_grouped_by_path")?;
+        let path = tmp_dir.path().join(JSON_FILE_NAME);
+        write_active_review(&path)?;
+
+        let contents: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        assert_eq!(
+            contents["comments"]["setup.py"][0]["description"],
+            "test_message"
+        );
+
+        // Legacy flat comments array.
+        let mut contents = contents;
+        contents["comments"] = serde_json::to_value(vec![get_comment()])?;
+        std::fs::write(&path, contents.to_string())?;
+        assert_eq!(parse(&path)?, maplit::btreeset! {get_comment()});
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_detects_format_from_contents() -> Result<()> {
+        let tmp_dir = tempdir::TempDir::new("vouch_test_parse_detects_format_from_contents")?;
+        let yaml_path = tmp_dir.path().join(YAML_FILE_NAME);
+        write_active_review(&yaml_path)?;
+        let path = tmp_dir.path().join(JSON_FILE_NAME);
+        std::fs::rename(&yaml_path, &path)?;
+
+        assert_eq!(parse(&path)?, maplit::btreeset! {get_comment()});
+
+        std::fs::write(&path, "comments: [")?;
+        let error = parse(&path).err().ok_or(format_err!("Expected error."))?;
+        let error = format!("{:#}", error);
+        assert!(error.contains("JSON error") && error.contains("YAML error"));
+        Ok(())
+    }
+}
@@ -29,6 +29,30 @@ pub struct Comment {
     pub selection: Option<Selection>,
 }
 
+impl Comment {
+    /// Returns the comment file path relative to the given workspace directory.
+    ///
+    /// Relative comment paths are assumed to be relative to the workspace directory already.
+    /// The workspace root component (package name-version directory) is optionally stripped.
+    pub fn display_path(
+        &self,
+        workspace_directory: &std::path::PathBuf,
+        strip_workspace_root: bool,
+    ) -> anyhow::Result<std::path::PathBuf> {
+        let path = if self.path.is_absolute() {
+            self.path.strip_prefix(workspace_directory)?.to_path_buf()
+        } else {
+            self.path.clone()
+        };
+        if !strip_workspace_root {
+            return Ok(path);
+        }
+        let mut components = path.components();
+        components.next();
+        Ok(components.as_path().to_path_buf())
+    }
+}
+
 impl Ord for Comment {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         (
@@ -72,3 +96,30 @@ impl crate::common::index::Identify for Comment {
         &mut self.id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_path_strips_workspace_directory_and_root() -> anyhow::Result<()> {
+        let workspace_directory = std::env::temp_dir().join("npmjs.com/d3/4.10.0");
+        let comment = Comment {
+            id: 0,
+            summary: Summary::Pass,
+            path: workspace_directory.join("d3-4.10.0/build/file_2.js"),
+            message: String::new(),
+            selection: None,
+        };
+
+        assert_eq!(
+            comment.display_path(&workspace_directory, false)?,
+            std::path::PathBuf::from("d3-4.10.0/build/file_2.js")
+        );
+        assert_eq!(
+            comment.display_path(&workspace_directory, true)?,
+            std::path::PathBuf::from("build/file_2.js")
+        );
+        Ok(())
+    }
+}
@@ -14,7 +14,16 @@ pub fn setup(tx: &StoreTransaction) -> Result<()> {
             selection_start_line      INTEGER,
             selection_start_character INTEGER,
             selection_end_line        INTEGER,
-            selection_end_character   INTEGER
+            selection_end_character   INTEGER,
+            UNIQUE(
+                path,
+                summary,
+                message,
+                selection_start_line,
+                selection_start_character,
+                selection_end_line,
+                selection_end_character
+            )
         )",
         rusqlite::NO_PARAMS,
     )?;
@@ -86,6 +95,153 @@ pub fn insert(
     })
 }
 
+/// Return an existing comment which matches on all given fields, otherwise insert a new comment.
+///
+/// Reusing existing comments avoids accumulating duplicate rows when a review is re-submitted.
+pub fn get_or_insert(
+    path: &std::path::PathBuf,
+    summary: &crate::review::common::Summary,
+    message: &str,
+    selection: &Option<common::Selection>,
+    tx: &StoreTransaction,
+) -> Result<common::Comment> {
+    let path_field = path
+        .clone()
+        .into_os_string()
+        .into_string()
+        .map_err(|_| format_err!("Failed to convert path into String: {}", path.display()))?;
+
+    // Selection fields may be NULL, so compare with IS rather than =.
+    let mut statement = tx.index_tx().prepare(
+        r"
+        SELECT id
+        FROM comment
+        WHERE
+            path = :path
+            AND summary = :summary
+            AND message = :message
+            AND selection_start_line IS :selection_start_line
+            AND selection_start_character IS :selection_start_character
+            AND selection_end_line IS :selection_end_line
+            AND selection_end_character IS :selection_end_character
+        LIMIT 1
+    ",
+    )?;
+    let mut rows = statement.query_named(&[
+        (":path", &path_field),
+        (":summary", &summary.to_string()),
+        (":message", &message.to_string()),
+        (
+            ":selection_start_line",
+            &selection.clone().map(|s| s.start.line),
+        ),
+        (
+            ":selection_start_character",
+            &selection.clone().map(|s| s.start.character),
+        ),
+        (
+            ":selection_end_line",
+            &selection.clone().map(|s| s.end.line),
+        ),
+        (
+            ":selection_end_character",
+            &selection.clone().map(|s| s.end.character),
+        ),
+    ])?;
+
+    if let Some(row) = rows.next()? {
+        return Ok(common::Comment {
+            id: row.get(0)?,
+            path: path.clone(),
+            summary: summary.clone(),
+            message: message.to_string(),
+            selection: selection.clone(),
+        });
+    }
+    insert(&path, &summary, &message, &selection, &tx)
+}
+
+/// Update a review's comment summary, message, and selection. Returns the updated comment.
+///
+/// Comment rows are shared between reviews which contain identical comments. A comment which is
+/// also referenced by other reviews is left unchanged for them: the updated comment is stored in
+/// a separate row which replaces the original comment in the given review only. Otherwise, the
+/// comment is updated in place and its ID is unchanged.
+pub fn update(
+    review_id: crate::common::index::ID,
+    id: crate::common::index::ID,
+    summary: &crate::review::common::Summary,
+    message: &str,
+    selection: &Option<common::Selection>,
+    tx: &StoreTransaction,
+) -> Result<common::Comment> {
+    if crate::review::index::get_referenced_comment_ids(review_id, &tx)?.contains(&id) {
+        let comment = get(
+            &Fields {
+                ids: Some(&vec![id]),
+                ..Default::default()
+            },
+            &tx,
+        )?
+        .into_iter()
+        .next()
+        .ok_or(format_err!("Failed to find comment: {}", id))?;
+        let updated_comment = get_or_insert(&comment.path, &summary, &message, &selection, &tx)?;
+        crate::review::index::replace_comment_id(review_id, id, updated_comment.id, &tx)?;
+        return Ok(updated_comment);
+    }
+
+    let updated_row_count = tx.index_tx().execute_named(
+        r"
+            UPDATE comment
+            SET
+                summary = :summary,
+                message = :message,
+                selection_start_line = :selection_start_line,
+                selection_start_character = :selection_start_character,
+                selection_end_line = :selection_end_line,
+                selection_end_character = :selection_end_character
+            WHERE
+                id = :id
+        ",
+        &[
+            (":id", &id),
+            (":summary", &summary.to_string()),
+            (":message", &message.to_string()),
+            (
+                ":selection_start_line",
+                &selection.clone().map(|s| s.start.line),
+            ),
+            (
+                ":selection_start_character",
+                &selection.clone().map(|s| s.start.character),
+            ),
+            (
+                ":selection_end_line",
+                &selection.clone().map(|s| s.end.line),
+            ),
+            (
+                ":selection_end_character",
+                &selection.clone().map(|s| s.end.character),
+            ),
+        ],
+    )?;
+    if updated_row_count == 0 {
+        return Err(format_err!("Failed to find comment: {}", id));
+    }
+
+    get(
+        &Fields {
+            ids: Some(&vec![id]),
+            ..Default::default()
+        },
+        &tx,
+    )?
+    .into_iter()
+    .next()
+    .ok_or(format_err!("Failed to find comment: {}", id))
+}
+
 #[derive(Debug, Default)]
 pub struct Fields<'a> {
     pub id: Option<crate::common::index::ID>,
@@ -180,3 +336,221 @@ pub fn remove(fields: &Fields, tx: &StoreTransaction) -> Result<()> {
     )?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_or_insert_reuses_matching_comment() -> Result<()> {
+        let mut store = crate::store::Store::from_tmp()?;
+        let tx = store.get_transaction()?;
+
+        let path = std::path::PathBuf::from("test_path");
+        let summary = crate::review::common::Summary::Warn;
+
+        let first = get_or_insert(&path, &summary, "test_message", &None, &tx)?;
+        let second = get_or_insert(&path, &summary, "test_message", &None, &tx)?;
+        let other = get_or_insert(&path, &summary, "other_message", &None, &tx)?;
+
+        assert_eq!(first.id, second.id);
+        assert_ne!(first.id, other.id);
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_or_insert_distinguishes_selection_characters() -> Result<()> {
+        let mut store = crate::store::Store::from_tmp()?;
+        let tx = store.get_transaction()?;
+
+        let path = std::path::PathBuf::from("test_path");
+        let summary = crate::review::common::Summary::Warn;
+        let get_selection = |end_character| {
+            Some(common::Selection {
+                start: common::Position {
+                    line: 1,
+                    character: 0,
+                },
+                end: common::Position {
+                    line: 2,
+                    character: end_character,
+                },
+            })
+        };
+
+        let first = get_or_insert(&path, &summary, "test_message", &get_selection(4), &tx)?;
+        let second = get_or_insert(&path, &summary, "test_message", &get_selection(8), &tx)?;
+
+        assert_ne!(first.id, second.id);
+        assert_eq!(second.selection, get_selection(8));
+        Ok(())
+    }
+
+    fn insert_review(
+        package_name: &str,
+        comments: &std::collections::BTreeSet<common::Comment>,
+        tx: &StoreTransaction,
+    ) -> Result<crate::review::Review> {
+        let registry = crate::registry::index::get_or_insert(
+            "test_registry_host_name",
+            &url::Url::parse("http://localhost/test_registry_human_url")?,
+            &url::Url::parse("http://localhost/test_archive_url")?,
+            &tx,
+        )?;
+        let package = crate::package::index::insert(
+            &package_name,
+            "test_package_version",
+            &maplit::btreeset! {registry},
+            "test_source_code_hash",
+            &tx,
+        )?;
+        let root_peer = crate::peer::index::get_root(&tx)?.unwrap();
+        crate::review::index::insert(&comments, &root_peer, &package, &None, &tx)
+    }
+
+    fn get_review_comments(
+        review_id: crate::common::index::ID,
+        tx: &StoreTransaction,
+    ) -> Result<Vec<common::Comment>> {
+        Ok(crate::review::index::get(
+            &crate::review::index::Fields {
+                id: Some(review_id),
+                ..Default::default()
+            },
+            &tx,
+        )?
+        .into_iter()
+        .flat_map(|review| review.comments)
+        .collect())
+    }
+
+    #[test]
+    fn test_update_changes_only_given_comment() -> Result<()> {
+        let mut store = crate::store::Store::from_tmp()?;
+        let tx = store.get_transaction()?;
+
+        let path = std::path::PathBuf::from("test_path");
+        let comment = insert(
+            &path,
+            &crate::review::common::Summary::Warn,
+            "test_message",
+            &None,
+            &tx,
+        )?;
+        let other = insert(
+            &path,
+            &crate::review::common::Summary::Warn,
+            "other_message",
+            &None,
+            &tx,
+        )?;
+        let review = insert_review(
+            "test_package_name",
+            &maplit::btreeset! {comment.clone()},
+            &tx,
+        )?;
+
+        let selection = Some(common::Selection {
+            start: common::Position {
+                line: 1,
+                character: 0,
+            },
+            end: common::Position {
+                line: 2,
+                character: 4,
+            },
+        });
+        let updated = update(
+            review.id,
+            comment.id,
+            &crate::review::common::Summary::Fail,
+            "updated_message",
+            &selection,
+            &tx,
+        )?;
+
+        let expected = common::Comment {
+            id: comment.id,
+            path: path.clone(),
+            summary: crate::review::common::Summary::Fail,
+            message: "updated_message".to_string(),
+            selection,
+        };
+        assert_eq!(updated, expected);
+        let result = get(&Fields::default(), &tx)?;
+        let expected = maplit::hashset! {expected, other};
+        assert_eq!(result, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_keeps_review_comment_ids() -> Result<()> {
+        let mut store = crate::store::Store::from_tmp()?;
+        let tx = store.get_transaction()?;
+
+        let comment = insert(
+            &std::path::PathBuf::from("test_path"),
+            &crate::review::common::Summary::Warn,
+            "test_message",
+            &None,
+            &tx,
+        )?;
+        let review = insert_review(
+            "test_package_name",
+            &maplit::btreeset! {comment.clone()},
+            &tx,
+        )?;
+
+        let updated = update(
+            review.id,
+            comment.id,
+            &crate::review::common::Summary::Pass,
+            "updated_message",
+            &None,
+            &tx,
+        )?;
+
+        assert_eq!(updated.id, comment.id);
+        assert_eq!(get_review_comments(review.id, &tx)?, vec![updated]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_does_not_change_shared_comment() -> Result<()> {
+        let mut store = crate::store::Store::from_tmp()?;
+        let tx = store.get_transaction()?;
+
+        let comment = get_or_insert(
+            &std::path::PathBuf::from("test_path"),
+            &crate::review::common::Summary::Warn,
+            "test_message",
+            &None,
+            &tx,
+        )?;
+        let review = insert_review(
+            "test_package_name",
+            &maplit::btreeset! {comment.clone()},
+            &tx,
+        )?;
+        let other_review = insert_review(
+            "other_package_name",
+            &maplit::btreeset! {comment.clone()},
+            &tx,
+        )?;
+
+        let updated = update(
+            review.id,
+            comment.id,
+            &crate::review::common::Summary::Pass,
+            "updated_message",
+            &None,
+            &tx,
+        )?;
+
+        assert_ne!(updated.id, comment.id);
+        assert_eq!(updated.message, "updated_message");
+        assert_eq!(get_review_comments(review.id, &tx)?, vec![updated]);
+        assert_eq!(get_review_comments(other_review.id, &tx)?, vec![comment]);
+        Ok(())
+    }
+}
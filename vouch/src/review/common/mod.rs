@@ -20,16 +20,37 @@ pub struct Review {
     pub peer: crate::peer::Peer,
     pub package: crate::package::Package,
     pub comments: std::collections::BTreeSet<crate::review::comment::Comment>,
+    /// Semver version constraint for which the review applies. Example: ">=1.2.0, <1.4.0"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_constraint: Option<String>,
+    /// Total package line count at the time of review. Not stored in the index.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_line_count: Option<usize>,
+    /// Review quality score at the time of review. Not stored in the index.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality_score: Option<QualityScore>,
 }
 
 impl Ord for Review {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        (&self.peer, &self.package, &self.comments, &self.id).cmp(&(
-            &other.peer,
-            &other.package,
-            &other.comments,
-            &other.id,
-        ))
+        (
+            &self.peer,
+            &self.package,
+            &self.comments,
+            &self.version_constraint,
+            &self.total_line_count,
+            &self.quality_score,
+            &self.id,
+        )
+            .cmp(&(
+                &other.peer,
+                &other.package,
+                &other.comments,
+                &other.version_constraint,
+                &other.total_line_count,
+                &other.quality_score,
+                &other.id,
+            ))
     }
 }
 
@@ -38,3 +59,42 @@ impl PartialOrd for Review {
         Some(self.cmp(other))
     }
 }
+
+/// Review quality score between 0.0 and 1.0.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct QualityScore(pub f64);
+
+impl PartialEq for QualityScore {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+
+impl Eq for QualityScore {}
+
+impl Hash for QualityScore {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+impl Ord for QualityScore {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0
+            .partial_cmp(&other.0)
+            .unwrap_or_else(|| self.0.to_bits().cmp(&other.0.to_bits()))
+    }
+}
+
+impl PartialOrd for QualityScore {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for QualityScore {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:.2}", self.0)
+    }
+}
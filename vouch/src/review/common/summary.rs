@@ -6,6 +6,7 @@ pub enum Summary {
     Fail,
     Warn,
     Pass,
+    Info,
     Todo,
 }
 
@@ -15,6 +16,7 @@ impl std::str::FromStr for Summary {
         match input {
             "todo" => Ok(Summary::Todo),
             "pass" => Ok(Summary::Pass),
+            "info" => Ok(Summary::Info),
             "warn" => Ok(Summary::Warn),
             "fail" => Ok(Summary::Fail),
             _ => Err(anyhow::format_err!(
@@ -4,7 +4,7 @@ use std::io::Write;
 use crate::common;
 use crate::review;
 
-static REVIEW_FILE_NAME: &str = "review.json";
+pub static REVIEW_FILE_NAME: &str = "review.json";
 
 /// Given a package, returns a package version specific relative directory path.
 ///
@@ -15,33 +15,234 @@ pub fn get_unique_package_path(
     registry_host_name: &str,
 ) -> Result<std::path::PathBuf> {
     let registry_host_name = std::path::PathBuf::from(&registry_host_name);
-    Ok(registry_host_name
+    let path = registry_host_name
         .join(&package_name)
-        .join(&package_version))
+        .join(&package_version);
+
+    // Package metadata originates from registries and peers, so the path must not escape
+    // the reviews directory.
+    let is_valid = path
+        .components()
+        .all(|component| matches!(component, std::path::Component::Normal(_)));
+    if !is_valid {
+        return Err(format_err!(
+            "Invalid package path derived from registry, name and version: {}",
+            path.display()
+        ));
+    }
+    Ok(path)
+}
+
+/// Returns the review file path relative to a reviews directory.
+///
+/// Example: "pypi.org/numpy/1.18.5/review.json"
+pub fn get_review_file_relative_path(
+    package_name: &str,
+    package_version: &str,
+    registry_host_name: &str,
+) -> Result<std::path::PathBuf> {
+    Ok(
+        get_unique_package_path(&package_name, &package_version, &registry_host_name)?
+            .join(REVIEW_FILE_NAME),
+    )
 }
 
-fn get_storage_file_path(review: &review::Review) -> Result<std::path::PathBuf> {
+/// Returns the canonical review file path for a package within the given peer's review store.
+///
+/// Each peer stores reviews in its own repository, so the peer is identified by its
+/// store directory rather than a path component.
+pub fn get_review_file_path(
+    package: &crate::package::Package,
+    peer_branch: &Vec<crate::peer::Peer>,
+) -> Result<std::path::PathBuf> {
     // TODO: Handle multiple registries.
-    let review_directory_path = get_unique_package_path(
-        &review.package.name,
-        &review.package.version,
-        &review
-            .package
-            .registries
-            .iter()
-            .next()
-            .ok_or(format_err!("Package does not have associated registries."))?
-            .host_name,
-    )?;
-
-    let paths = common::fs::DataPaths::new()?;
-    let package_specific_directory = paths.reviews_directory.join(review_directory_path);
-    Ok(package_specific_directory.join(REVIEW_FILE_NAME))
+    let registry_host_name = &package
+        .registries
+        .iter()
+        .next()
+        .ok_or(format_err!("Package does not have associated registries."))?
+        .host_name;
+
+    let root_paths = common::fs::DataPaths::new()?;
+    let paths = crate::peer::fs::get_peer_data_paths(&peer_branch, &root_paths.root_directory)?;
+    Ok(paths.reviews_directory.join(get_review_file_relative_path(
+        &package.name,
+        &package.version,
+        &registry_host_name,
+    )?))
+}
+
+/// Returns the commit date of the most recent change to a review's file.
+///
+/// The given peer branch must correspond to the review's peer.
+pub fn get_last_updated_date(
+    review: &review::Review,
+    peer_branch: &Vec<crate::peer::Peer>,
+) -> Result<Option<chrono::NaiveDate>> {
+    let root_paths = common::fs::DataPaths::new()?;
+    let paths = crate::peer::fs::get_peer_data_paths(&peer_branch, &root_paths.root_directory)?;
+
+    // Nothing has been committed yet, git log would fail.
+    if git2::Repository::open(&paths.root_directory)?
+        .head()
+        .is_err()
+    {
+        return Ok(None);
+    }
+
+    let mut last_updated_date = None;
+    for registry in &review.package.registries {
+        let review_directory = paths
+            .reviews_directory
+            .join(get_unique_package_path(
+                &review.package.name,
+                &review.package.version,
+                &registry.host_name,
+            )?)
+            .strip_prefix(&paths.root_directory)?
+            .to_path_buf();
+        let review_directory = review_directory.to_str().ok_or(format_err!(
+            "Failed to parse review directory path: {}",
+            review_directory.display()
+        ))?;
+
+        let output = common::fs::git_output(
+            vec!["log", "-1", "--format=%cs", "--", review_directory],
+            &paths.root_directory,
+        )?;
+        let output = output.trim();
+        if output.is_empty() {
+            continue;
+        }
+        let date = chrono::NaiveDate::parse_from_str(&output, "%Y-%m-%d")?;
+        last_updated_date = std::cmp::max(last_updated_date, Some(date));
+    }
+    Ok(last_updated_date)
+}
+
+/// Returns the paths of all review files within the given reviews directory.
+///
+/// Only files at the review file location `<registry>/<name>/<version>/review.json` are
+/// returned. Scoped package names (`@scope/name`) add one directory level. Hidden directories,
+/// which include ongoing review workspaces, are skipped and symbolic links are not followed.
+pub fn get_review_file_paths(
+    reviews_directory: &std::path::PathBuf,
+) -> Result<Vec<std::path::PathBuf>> {
+    let mut file_paths = Vec::new();
+    if !reviews_directory.is_dir() {
+        return Ok(file_paths);
+    }
+    let walker = walkdir::WalkDir::new(&reviews_directory)
+        .min_depth(1)
+        .max_depth(5)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|entry| !is_hidden_directory(&entry));
+    for entry in walker {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative_path = entry.path().strip_prefix(&reviews_directory)?;
+        if is_review_file_relative_path(&relative_path) {
+            file_paths.push(entry.path().to_path_buf());
+        }
+    }
+    file_paths.sort();
+    Ok(file_paths)
+}
+
+fn is_hidden_directory(entry: &walkdir::DirEntry) -> bool {
+    entry.file_type().is_dir()
+        && entry
+            .file_name()
+            .to_str()
+            .map_or(false, |name| name.starts_with('.'))
+}
+
+fn is_review_file_relative_path(relative_path: &std::path::Path) -> bool {
+    let components: Vec<_> = relative_path
+        .components()
+        .filter_map(|component| match component {
+            std::path::Component::Normal(component) => component.to_str(),
+            _ => None,
+        })
+        .collect();
+    let is_scoped = components
+        .get(1)
+        .map_or(false, |component| component.starts_with('@'));
+    let expected_length = if is_scoped { 5 } else { 4 };
+    components.len() == expected_length && components.last() == Some(&REVIEW_FILE_NAME)
+}
+
+/// Read a review from a review file.
+pub fn read(file_path: &std::path::PathBuf) -> Result<review::Review> {
+    let file = std::fs::File::open(&file_path)?;
+    let reader = std::io::BufReader::new(file);
+    Ok(serde_json::from_reader(reader).context(format!(
+        "Failed to parse review file: {}",
+        file_path.display()
+    ))?)
+}
+
+/// Returns the review as recorded in its file, including fields which are not indexed.
+///
+/// The given peer branch must correspond to the review's peer.
+pub fn get_file_review(
+    review: &review::Review,
+    peer_branch: &Vec<crate::peer::Peer>,
+) -> Result<Option<review::Review>> {
+    let file_path = get_review_file_path(&review.package, &peer_branch)?;
+    if !file_path.is_file() {
+        return Ok(None);
+    }
+    Ok(Some(read(&file_path)?))
+}
+
+/// Move a package's review file from one peer's review store to another's.
+///
+/// Returns false if the source peer has no review file for the package.
+pub fn transfer(
+    package: &crate::package::Package,
+    source_peer_branch: &Vec<crate::peer::Peer>,
+    target_peer_branch: &Vec<crate::peer::Peer>,
+) -> Result<bool> {
+    let source_path = get_review_file_path(&package, &source_peer_branch)?;
+    let target_path = get_review_file_path(&package, &target_peer_branch)?;
+    move_review_file(&source_path, &target_path)
+}
+
+/// Move a review file, creating the target's parent directories.
+///
+/// Returns false if the source file does not exist. Fails if the target file already exists.
+fn move_review_file(
+    source_path: &std::path::PathBuf,
+    target_path: &std::path::PathBuf,
+) -> Result<bool> {
+    if !source_path.is_file() {
+        return Ok(false);
+    }
+    if target_path.exists() {
+        return Err(format_err!(
+            "Review file already exists: {}",
+            target_path.display()
+        ));
+    }
+    let parent_directory = target_path.parent().ok_or(format_err!(
+        "Can't find parent directory for file path: {}",
+        target_path.display()
+    ))?;
+    std::fs::create_dir_all(&parent_directory).context(format!(
+        "Can't create directory: {}",
+        parent_directory.display()
+    ))?;
+    std::fs::rename(&source_path, &target_path)?;
+    Ok(true)
 }
 
 /// Store a review.
 pub fn add(review: &review::Review) -> Result<()> {
-    let file_path = get_storage_file_path(&review)?;
+    let file_path = get_review_file_path(&review.package, &vec![review.peer.clone()])?;
     let parent_directory = file_path.parent().ok_or(format_err!(
         "Can't find parent directory for file path: {}",
         file_path.display()
@@ -51,7 +252,13 @@ pub fn add(review: &review::Review) -> Result<()> {
         parent_directory.display()
     ))?;
 
+    let mut review = review.clone();
     if file_path.is_file() {
+        // Fields which are not stored in the index retain any previously recorded values.
+        if let Ok(existing_review) = read(&file_path) {
+            review.total_line_count = review.total_line_count.or(existing_review.total_line_count);
+            review.quality_score = review.quality_score.or(existing_review.quality_score);
+        }
         std::fs::remove_file(&file_path)?;
     }
 
@@ -66,3 +273,106 @@ pub fn add(review: &review::Review) -> Result<()> {
     file.write_all(serde_json::to_string_pretty(&review)?.as_bytes())?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_package(package_name: &str) -> Result<crate::package::Package> {
+        Ok(crate::package::Package {
+            id: 1,
+            name: package_name.to_string(),
+            version: "1.18.5".to_string(),
+            registries: maplit::btreeset! {crate::registry::Registry {
+                id: 1,
+                host_name: "pypi.org".to_string(),
+                human_url: url::Url::parse("https://pypi.org/project/numpy/1.18.5")?,
+                artifact_url: url::Url::parse("https://files.pythonhosted.org/numpy-1.18.5.zip")?,
+            }},
+            artifact_hash: "test_artifact_hash".to_string(),
+        })
+    }
+
+    fn get_root_peer_branch() -> Vec<crate::peer::Peer> {
+        vec![crate::peer::Peer {
+            alias: "root".to_string(),
+            ..Default::default()
+        }]
+    }
+
+    #[test]
+    fn test_get_review_file_paths_skips_ongoing_and_nested_files() -> Result<()> {
+        let tmp_dir = tempdir::TempDir::new("vouch_test_get_review_file_paths")?;
+        let reviews_directory = tmp_dir.path().to_path_buf();
+        let review_file_paths = vec![
+            reviews_directory.join("npmjs.com/d3/4.10.0/review.json"),
+            reviews_directory.join("npmjs.com/@types/node/1.0.0/review.json"),
+        ];
+        let other_file_paths = vec![
+            reviews_directory.join(".ongoing/npmjs.com/d3/4.10.0/d3-4.10.0/review.json"),
+            reviews_directory.join("npmjs.com/d3/4.10.0/test/review.json"),
+            reviews_directory.join("npmjs.com/d3/review.json"),
+        ];
+        for path in review_file_paths.iter().chain(other_file_paths.iter()) {
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(&path, "{}")?;
+        }
+
+        let result = get_review_file_paths(&reviews_directory)?;
+        let mut expected = review_file_paths.clone();
+        expected.sort();
+        assert_eq!(result, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_review_file_path_is_consistent() -> Result<()> {
+        let package = get_package("numpy")?;
+        let peer_branch = get_root_peer_branch();
+
+        let result = get_review_file_path(&package, &peer_branch)?;
+        assert_eq!(result, get_review_file_path(&package, &peer_branch)?);
+        assert!(result.ends_with(
+            std::path::PathBuf::from("reviews/pypi.org/numpy/1.18.5").join(REVIEW_FILE_NAME)
+        ));
+        assert!(result
+            .components()
+            .all(|component| component != std::path::Component::ParentDir));
+        Ok(())
+    }
+
+    #[test]
+    fn test_review_file_path_rejects_parent_directory() -> Result<()> {
+        let package = get_package("../numpy")?;
+        assert!(get_review_file_path(&package, &get_root_peer_branch()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_move_review_file() -> Result<()> {
+        let tmp_dir = tempdir::TempDir::new("vouch_test_move_review_file")?;
+        let source_path = tmp_dir
+            .path()
+            .join("source/pypi.org/numpy/1.18.5")
+            .join(REVIEW_FILE_NAME);
+        let target_path = tmp_dir
+            .path()
+            .join("target/pypi.org/numpy/1.18.5")
+            .join(REVIEW_FILE_NAME);
+
+        assert!(!move_review_file(&source_path, &target_path)?);
+
+        std::fs::create_dir_all(source_path.parent().unwrap())?;
+        std::fs::write(&source_path, "{}")?;
+        assert!(move_review_file(&source_path, &target_path)?);
+        assert!(!source_path.exists());
+        assert_eq!(std::fs::read_to_string(&target_path)?, "{}");
+
+        // An existing target review file is never overwritten.
+        std::fs::write(&source_path, "{\"source\": true}")?;
+        assert!(move_review_file(&source_path, &target_path).is_err());
+        assert!(source_path.is_file());
+        assert_eq!(std::fs::read_to_string(&target_path)?, "{}");
+        Ok(())
+    }
+}
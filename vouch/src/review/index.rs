@@ -48,6 +48,7 @@ pub fn insert(
     comments: &std::collections::BTreeSet<comment::Comment>,
     peer: &crate::peer::Peer,
     package: &crate::package::Package,
+    version_constraint: &Option<String>,
     tx: &StoreTransaction,
 ) -> Result<common::Review> {
     let comment_ids: Vec<crate::common::index::ID> = comments.into_iter().map(|c| c.id).collect();
@@ -62,18 +63,21 @@ pub fn insert(
             INSERT INTO review (
                 peer_id,
                 package_id,
-                comment_ids
+                comment_ids,
+                version_constraint
             )
             VALUES (
                 :peer_id,
                 :package_id,
-                :comment_ids
+                :comment_ids,
+                :version_constraint
             )
         ",
         &[
             (":peer_id", &peer.id),
             (":package_id", &package.id),
             (":comment_ids", &comment_ids),
+            (":version_constraint", version_constraint),
         ],
     )?;
     Ok(common::Review {
@@ -81,6 +85,9 @@ pub fn insert(
         peer: peer.clone(),
         package: package.clone(),
         comments: comments.clone(),
+        version_constraint: version_constraint.clone(),
+        total_line_count: None,
+        quality_score: None,
     })
 }
 
@@ -93,7 +100,8 @@ pub fn update(review: &common::Review, tx: &StoreTransaction) -> Result<()> {
             SET
                 peer_id = :peer_id,
                 package_id = :package_id,
-                comment_ids = :comment_ids
+                comment_ids = :comment_ids,
+                version_constraint = :version_constraint
             WHERE
                 id = :id
         ",
@@ -105,6 +113,7 @@ pub fn update(review: &common::Review, tx: &StoreTransaction) -> Result<()> {
                 ":comment_ids",
                 &bincode::serialize(&review.comments.iter().map(|c| c.id).collect::<Vec<_>>())?,
             ),
+            (":version_constraint", &review.version_constraint),
         ],
     )?;
     Ok(())
@@ -135,7 +144,12 @@ fn remove_stale_comments(review: &common::Review, tx: &StoreTransaction) -> Resu
     let stale_comments =
         crate::common::index::get_difference_sans_id(&current_comments, &new_comments)?;
 
+    let referenced_comment_ids = get_referenced_comment_ids(review.id, &tx)?;
     for comment in stale_comments {
+        // Comments are shared between reviews which contain identical comments.
+        if referenced_comment_ids.contains(&comment.id) {
+            continue;
+        }
         comment::index::remove(
             &comment::index::Fields {
                 id: Some(comment.id),
@@ -147,7 +161,79 @@ fn remove_stale_comments(review: &common::Review, tx: &StoreTransaction) -> Resu
     Ok(())
 }
 
+/// Returns the IDs of all comments referenced by reviews other than the given review.
+pub fn get_referenced_comment_ids(
+    excluded_review_id: crate::common::index::ID,
+    tx: &StoreTransaction,
+) -> Result<HashSet<crate::common::index::ID>> {
+    let mut statement = tx.index_tx().prepare(
+        r"
+        SELECT comment_ids
+        FROM review
+        WHERE
+            id != :id
+            AND comment_ids IS NOT NULL
+    ",
+    )?;
+    let mut rows = statement.query_named(&[(":id", &excluded_review_id)])?;
+
+    let mut comment_ids = HashSet::new();
+    while let Some(row) = rows.next()? {
+        let ids: Vec<crate::common::index::ID> = bincode::deserialize(&row.get::<_, Vec<u8>>(0)?)?;
+        comment_ids.extend(ids);
+    }
+    Ok(comment_ids)
+}
+
+/// Replace a comment ID within a review's comment IDs.
+pub fn replace_comment_id(
+    review_id: crate::common::index::ID,
+    comment_id: crate::common::index::ID,
+    new_comment_id: crate::common::index::ID,
+    tx: &StoreTransaction,
+) -> Result<()> {
+    let comment_ids: Option<Vec<u8>> = tx.index_tx().query_row_named(
+        "SELECT comment_ids FROM review WHERE id = :id",
+        &[(":id", &review_id)],
+        |row| row.get(0),
+    )?;
+    let comment_ids: Vec<crate::common::index::ID> = match comment_ids {
+        Some(comment_ids) => bincode::deserialize(&comment_ids)?,
+        None => vec![],
+    };
+    if !comment_ids.contains(&comment_id) {
+        return Err(format_err!(
+            "Review {} does not reference comment {}.",
+            review_id,
+            comment_id
+        ));
+    }
+    let comment_ids: Vec<_> = comment_ids
+        .into_iter()
+        .map(|id| if id == comment_id { new_comment_id } else { id })
+        .collect();
+
+    tx.index_tx().execute_named(
+        "UPDATE review SET comment_ids = :comment_ids WHERE id = :id",
+        &[
+            (":id", &review_id),
+            (":comment_ids", &bincode::serialize(&comment_ids)?),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Returns reviews which match on the given fields.
+///
+/// If a package version is given, reviews with a version constraint which includes
+/// that version are also returned.
 pub fn get(fields: &Fields, tx: &StoreTransaction) -> Result<Vec<common::Review>> {
+    let span = tracing::info_span!(
+        "review_index_get",
+        package_name = ?fields.package_name,
+        package_version = ?fields.package_version
+    );
+    let _enter = span.enter();
     let review_id =
         crate::common::index::get_like_clause_param(fields.id.map(|id| id.to_string()).as_deref());
 
@@ -164,7 +250,8 @@ pub fn get(fields: &Fields, tx: &StoreTransaction) -> Result<Vec<common::Review>
             review.id,
             peer.id,
             package.id,
-            review.comment_ids
+            review.comment_ids,
+            review.version_constraint
         FROM review
         JOIN peer
             ON review.peer_id = peer.id
@@ -173,7 +260,10 @@ pub fn get(fields: &Fields, tx: &StoreTransaction) -> Result<Vec<common::Review>
         WHERE
             review.id LIKE :review_id ESCAPE '\'
             AND package.name LIKE :name ESCAPE '\'
-            AND package.version LIKE :version ESCAPE '\'
+            AND (
+                package.version LIKE :version ESCAPE '\'
+                OR review.version_constraint IS NOT NULL
+            )
             AND peer.id LIKE :peer_id ESCAPE '\'
         ",
     )?;
@@ -208,6 +298,18 @@ pub fn get(fields: &Fields, tx: &StoreTransaction) -> Result<Vec<common::Review>
         .next()
         .ok_or(format_err!("Failed to find review package in index."))?;
 
+        // Skip constraint review if given package version is outside of the constraint.
+        let version_constraint: Option<String> = row.get(4)?;
+        if let (Some(package_version), Some(version_constraint)) =
+            (fields.package_version, &version_constraint)
+        {
+            if package.version != package_version
+                && !is_version_in_constraint(&package_version, &version_constraint)?
+            {
+                continue;
+            }
+        }
+
         // Skip review if associated package registries do not match on given registry host names.
         if let Some(registry_host_names) = &fields.registry_host_names {
             if !package
@@ -243,34 +345,66 @@ pub fn get(fields: &Fields, tx: &StoreTransaction) -> Result<Vec<common::Review>
             peer,
             package,
             comments,
+            version_constraint,
+            total_line_count: None,
+            quality_score: None,
         };
         reviews.push(review);
     }
     Ok(reviews)
 }
 
-pub fn remove(fields: &Fields, tx: &StoreTransaction) -> Result<()> {
-    let id =
-        crate::common::index::get_like_clause_param(fields.id.map(|id| id.to_string()).as_deref());
-    let package_name = crate::common::index::get_like_clause_param(fields.package_name);
-    let package_version = crate::common::index::get_like_clause_param(fields.package_version);
-
-    let peer_id = crate::common::index::get_like_clause_param(
-        fields.peer.map(|peer| peer.id.to_string()).as_deref(),
-    );
+/// Returns true if the package version is included in the semver version constraint.
+///
+/// Package versions which are not valid semver versions are never included.
+fn is_version_in_constraint(package_version: &str, version_constraint: &str) -> Result<bool> {
+    let version_constraint = semver::VersionReq::parse(&version_constraint).map_err(|error| {
+        format_err!(
+            "Failed to parse review version constraint: {}\n{}",
+            version_constraint,
+            error
+        )
+    })?;
+    Ok(match semver::Version::parse(&package_version) {
+        Ok(package_version) => version_constraint.matches(&package_version),
+        Err(_) => false,
+    })
+}
 
+pub fn remove(fields: &Fields, tx: &StoreTransaction) -> Result<()> {
     for review in get(&fields, &tx)? {
-        // Remove package.
-        package::index::remove(
-            &package::index::Fields {
-                id: Some(review.package.id),
-                ..Default::default()
-            },
-            &tx,
+        // Skip constraint reviews which were only included because of the given package version.
+        if let Some(package_version) = fields.package_version {
+            if review.package.version != package_version {
+                continue;
+            }
+        }
+
+        tx.index_tx().execute_named(
+            r"
+            DELETE FROM review
+            WHERE id = :id
+            ",
+            &[(":id", &review.id)],
         )?;
 
-        // Remove comments.
+        // Remove package if no other review references it.
+        if !is_package_referenced(review.package.id, &tx)? {
+            package::index::remove(
+                &package::index::Fields {
+                    id: Some(review.package.id),
+                    ..Default::default()
+                },
+                &tx,
+            )?;
+        }
+
+        // Remove comments which are not shared with other reviews.
+        let referenced_comment_ids = get_referenced_comment_ids(review.id, &tx)?;
         for comment in review.comments {
+            if referenced_comment_ids.contains(&comment.id) {
+                continue;
+            }
             comment::index::remove(
                 &comment::index::Fields {
                     id: Some(comment.id),
@@ -280,44 +414,50 @@ pub fn remove(fields: &Fields, tx: &StoreTransaction) -> Result<()> {
             )?;
         }
     }
+    Ok(())
+}
 
-    tx.index_tx().execute_named(
-        r"
-        DELETE FROM review
-        WHERE review.id IN (
-            SELECT review.id
-            FROM review
-            JOIN peer
-                ON review.peer_id = peer.id
-            JOIN package
-                ON review.package_id = package.id
-            WHERE
-                review.id LIKE :id ESCAPE '\'
-                AND package.name LIKE :name ESCAPE '\'
-                AND package.version LIKE :version ESCAPE '\'
-                AND peer.id LIKE :peer_id ESCAPE '\'
-        )
-        ",
-        &[
-            (":id", &id),
-            (":name", &package_name),
-            (":version", &package_version),
-            (":peer_id", &peer_id),
-        ],
+fn is_package_referenced(
+    package_id: crate::common::index::ID,
+    tx: &StoreTransaction,
+) -> Result<bool> {
+    let count: i64 = tx.index_tx().query_row_named(
+        "SELECT COUNT(*) FROM review WHERE package_id = :package_id",
+        &[(":package_id", &package_id)],
+        |row| row.get(0),
     )?;
-    Ok(())
+    Ok(count > 0)
 }
 
 /// Merge reviews from incoming index into another index. Returns the newly merged reviews.
+///
+/// If given, reviews of incoming peers deeper than the maximum depth are skipped. The incoming
+/// root peer is at depth 1.
 pub fn merge(
     incoming_root_git_url: &crate::common::GitUrl,
+    max_depth: Option<usize>,
     incoming_tx: &StoreTransaction,
     tx: &StoreTransaction,
 ) -> Result<HashSet<common::Review>> {
     let incoming_reviews = get(&Fields::default(), &incoming_tx)?;
 
+    let mut peer_depths = std::collections::HashMap::new();
     let mut new_reviews = HashSet::new();
     for review in incoming_reviews {
+        if let Some(max_depth) = max_depth {
+            let depth = match peer_depths.get(&review.peer.id) {
+                Some(depth) => *depth,
+                None => {
+                    let depth = peer::index::get_peer_branch(&review.peer, &incoming_tx)?.len();
+                    peer_depths.insert(review.peer.id, depth);
+                    depth
+                }
+            };
+            if depth > max_depth {
+                continue;
+            }
+        }
+
         let peer_git_url = if review.peer.is_root() {
             incoming_root_git_url.clone()
         } else {
@@ -363,7 +503,7 @@ pub fn merge(
 
         let mut new_comments = std::collections::BTreeSet::<_>::new();
         for comment in review.comments {
-            let comment = comment::index::insert(
+            let comment = comment::index::get_or_insert(
                 &comment.path,
                 &comment.summary,
                 &comment.message,
@@ -373,7 +513,13 @@ pub fn merge(
             new_comments.insert(comment);
         }
 
-        let review = insert(&new_comments, &peer, &package, &tx)?;
+        let review = insert(
+            &new_comments,
+            &peer,
+            &package,
+            &review.version_constraint,
+            &tx,
+        )?;
         new_reviews.insert(review);
     }
     Ok(new_reviews)
@@ -423,12 +569,14 @@ mod tests {
                 &std::collections::BTreeSet::<comment::Comment>::new(),
                 &root_peer,
                 &package_1,
+                &None,
                 &tx,
             )?;
             let review_2 = insert(
                 &std::collections::BTreeSet::<comment::Comment>::new(),
                 &root_peer,
                 &package_2,
+                &None,
                 &tx,
             )?;
 
@@ -454,6 +602,7 @@ mod tests {
                 &std::collections::BTreeSet::<comment::Comment>::new(),
                 &root_peer,
                 &package_1,
+                &None,
                 &tx,
             )?;
 
@@ -471,6 +620,41 @@ mod tests {
             Ok(())
         }
 
+        #[test]
+        fn test_found_using_version_constraint() -> Result<()> {
+            let mut store = crate::store::Store::from_tmp()?;
+            let tx = store.get_transaction()?;
+
+            let package_1 = get_package("package_1", &tx)?;
+            let root_peer = peer::index::get_root(&tx)?.unwrap();
+            let review_1 = insert(
+                &std::collections::BTreeSet::<comment::Comment>::new(),
+                &root_peer,
+                &package_1,
+                &Some(">=1.2.0, <1.4.0".to_string()),
+                &tx,
+            )?;
+
+            let result: Vec<_> = get(
+                &Fields {
+                    package_version: Some("1.3.5"),
+                    ..Default::default()
+                },
+                &tx,
+            )?;
+            assert_eq!(result, vec![review_1]);
+
+            let result: Vec<_> = get(
+                &Fields {
+                    package_version: Some("1.4.0"),
+                    ..Default::default()
+                },
+                &tx,
+            )?;
+            assert_eq!(result, vec![]);
+            Ok(())
+        }
+
         #[test]
         fn test_not_found_using_registry_host_names() -> Result<()> {
             let mut store = crate::store::Store::from_tmp()?;
@@ -482,6 +666,7 @@ mod tests {
                 &std::collections::BTreeSet::<comment::Comment>::new(),
                 &root_peer,
                 &package_1,
+                &None,
                 &tx,
             )?;
 
@@ -517,12 +702,14 @@ mod tests {
                 &std::collections::BTreeSet::<comment::Comment>::new(),
                 &root_peer,
                 &package_1,
+                &None,
                 &tx,
             )?;
             let review_2 = insert(
                 &std::collections::BTreeSet::<comment::Comment>::new(),
                 &root_peer,
                 &package_2,
+                &None,
                 &tx,
             )?;
 
@@ -555,12 +742,14 @@ mod tests {
                 &std::collections::BTreeSet::<comment::Comment>::new(),
                 &root_peer,
                 &package_1,
+                &None,
                 &tx,
             )?;
             let _review_2 = insert(
                 &std::collections::BTreeSet::<comment::Comment>::new(),
                 &root_peer,
                 &package_2,
+                &None,
                 &tx,
             )?;
 
@@ -580,5 +769,88 @@ mod tests {
             assert_eq!(result, expected);
             Ok(())
         }
+
+        #[test]
+        fn test_remove_deletes_review_and_keeps_shared_package() -> Result<()> {
+            let mut store = crate::store::Store::from_tmp()?;
+            let tx = store.get_transaction()?;
+
+            let package = get_package("package_1", &tx)?;
+            let mut root_peer = peer::index::get_root(&tx)?.unwrap();
+            let other_peer = peer::index::insert(
+                "other_peer",
+                &std::convert::TryFrom::try_from("https://localhost/other_peer")?,
+                Some(&mut root_peer),
+                &tx,
+            )?;
+
+            let review_1 = insert(
+                &std::collections::BTreeSet::<comment::Comment>::new(),
+                &root_peer,
+                &package,
+                &None,
+                &tx,
+            )?;
+            let review_2 = insert(
+                &std::collections::BTreeSet::<comment::Comment>::new(),
+                &other_peer,
+                &package,
+                &None,
+                &tx,
+            )?;
+
+            remove(
+                &Fields {
+                    id: Some(review_1.id),
+                    ..Default::default()
+                },
+                &tx,
+            )?;
+
+            let result = get(&Fields::default(), &tx)?;
+            assert_eq!(result, vec![review_2]);
+            Ok(())
+        }
+    }
+
+    mod merge {
+        use super::*;
+
+        #[test]
+        fn test_merge_skips_reviews_beyond_max_depth() -> Result<()> {
+            // Setup incoming database: root_incoming -> peer_1, both reviewing the package.
+            let mut incoming_store = crate::store::Store::from_tmp()?;
+            let incoming_tx = incoming_store.get_transaction()?;
+            let package = get_package("package_1", &incoming_tx)?;
+            let mut incoming_root_peer = peer::index::get_root(&incoming_tx)?.unwrap();
+            let peer_1 = peer::index::insert(
+                "peer_1",
+                &std::convert::TryFrom::try_from("https://localhost/peer_1")?,
+                Some(&mut incoming_root_peer),
+                &incoming_tx,
+            )?;
+            for peer in &[&incoming_root_peer, &peer_1] {
+                insert(
+                    &std::collections::BTreeSet::<comment::Comment>::new(),
+                    &peer,
+                    &package,
+                    &None,
+                    &incoming_tx,
+                )?;
+            }
+
+            let mut store = crate::store::Store::from_tmp()?;
+            let tx = store.get_transaction()?;
+            let incoming_root_git_url: crate::common::GitUrl =
+                std::convert::TryFrom::try_from("https://localhost/root_incoming")?;
+            crate::store::index::merge(&incoming_root_git_url, Some(1), &incoming_tx, &tx)?;
+
+            let result: Vec<_> = get(&Fields::default(), &tx)?
+                .into_iter()
+                .map(|review| review.peer.git_url)
+                .collect();
+            assert_eq!(result, vec![incoming_root_git_url]);
+            Ok(())
+        }
     }
 }
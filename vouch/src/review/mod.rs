@@ -7,10 +7,15 @@ mod common;
 pub mod fs;
 pub mod index;
 pub mod official;
+pub mod template;
 pub mod tool;
+pub mod verify;
 pub mod workspace;
 
-pub use crate::review::common::{Review, Summary};
+pub use crate::review::common::{QualityScore, Review, Summary};
+
+/// Comment message length at which a comment is considered fully specific.
+static SPECIFIC_MESSAGE_LENGTH: usize = 200;
 
 pub struct ReviewAnalysis {
     pub count_fail_comments: i32,
@@ -38,8 +43,129 @@ pub fn analyse(review: &Review) -> Result<ReviewAnalysis> {
     })
 }
 
+/// Returns a review quality score between 0.0 and 1.0.
+///
+/// The score is the mean of: the fraction of workspace files with at least one comment,
+/// the average comment message length relative to a fully specific message, and the fraction of
+/// comments which select code rather than a whole file.
+pub fn quality_score(review: &Review, workspace_analysis: &workspace::Analysis) -> f64 {
+    if review.comments.is_empty() {
+        return 0.0;
+    }
+    let comment_count = review.comments.len() as f64;
+
+    let file_paths: std::collections::BTreeSet<_> = workspace_analysis
+        .iter()
+        .filter(|(_, path_analysis)| {
+            matches!(path_analysis.path_type, crate::common::fs::PathType::File)
+        })
+        .map(|(path, _)| path)
+        .collect();
+    let commented_file_count = review
+        .comments
+        .iter()
+        .map(|comment| &comment.path)
+        .filter(|path| file_paths.contains(path))
+        .collect::<std::collections::BTreeSet<_>>()
+        .len();
+    let coverage = if file_paths.is_empty() {
+        0.0
+    } else {
+        commented_file_count as f64 / file_paths.len() as f64
+    };
+
+    let total_message_length: usize = review
+        .comments
+        .iter()
+        .map(|comment| comment.message.trim().chars().count())
+        .sum();
+    let specificity =
+        (total_message_length as f64 / comment_count / SPECIFIC_MESSAGE_LENGTH as f64).min(1.0);
+
+    let selection_count = review
+        .comments
+        .iter()
+        .filter(|comment| comment.selection.is_some())
+        .count();
+    let selection = selection_count as f64 / comment_count;
+
+    (coverage + specificity + selection) / 3.0
+}
+
+/// Returns the overall review summary derived from review comments.
+pub fn get_summary(review: &Review) -> Result<Summary> {
+    let analysis = analyse(&review)?;
+    Ok(if analysis.count_fail_comments > 0 {
+        Summary::Fail
+    } else if analysis.count_warn_comments > 0 {
+        Summary::Warn
+    } else {
+        Summary::Pass
+    })
+}
+
 pub fn store(review: &Review, tx: &StoreTransaction) -> Result<()> {
     index::update(&review, &tx)?;
     fs::add(&review)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quality_score_combines_coverage_specificity_and_selection() {
+        let selection = Some(comment::common::Selection {
+            start: comment::common::Position {
+                line: 0,
+                character: 0,
+            },
+            end: comment::common::Position {
+                line: 1,
+                character: 0,
+            },
+        });
+        let mut review = Review {
+            id: 0,
+            peer: crate::peer::Peer::default(),
+            package: crate::package::Package {
+                id: 0,
+                name: "d3".to_string(),
+                version: "4.10.0".to_string(),
+                registries: std::collections::BTreeSet::new(),
+                artifact_hash: String::new(),
+            },
+            comments: std::collections::BTreeSet::new(),
+            version_constraint: None,
+            total_line_count: None,
+            quality_score: None,
+        };
+        let workspace_analysis = maplit::btreemap! {
+            std::path::PathBuf::from("build") => workspace::PathAnalysis {
+                path_type: crate::common::fs::PathType::Directory,
+                line_count: 10,
+            },
+            std::path::PathBuf::from("build/index.js") => workspace::PathAnalysis {
+                path_type: crate::common::fs::PathType::File,
+                line_count: 10,
+            },
+            std::path::PathBuf::from("package.json") => workspace::PathAnalysis {
+                path_type: crate::common::fs::PathType::File,
+                line_count: 20,
+            },
+        };
+        assert_eq!(quality_score(&review, &workspace_analysis), 0.0);
+
+        review.comments.insert(comment::Comment {
+            id: 0,
+            summary: Summary::Pass,
+            path: std::path::PathBuf::from("build/index.js"),
+            message: "x".repeat(SPECIFIC_MESSAGE_LENGTH),
+            selection,
+        });
+        // Coverage 0.5, specificity 1.0, selection 1.0.
+        let result = quality_score(&review, &workspace_analysis);
+        assert!((result - 2.5 / 3.0).abs() < 1e-9);
+    }
+}
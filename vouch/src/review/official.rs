@@ -0,0 +1,192 @@
+//! Reviews published by the central Vouch API.
+
+use anyhow::{format_err, Result};
+use std::collections::HashMap;
+
+/// Package key: (registry host name, package name, package version).
+pub type PackageKey = (String, String, String);
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize)]
+pub struct OfficialReview {
+    pub summary: crate::review::Summary,
+    pub reviewer_count: usize,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct RequestPackage<'a> {
+    registry: &'a str,
+    name: &'a str,
+    version: &'a str,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ResponseReview {
+    registry: String,
+    name: String,
+    version: String,
+    #[serde(flatten)]
+    review: OfficialReview,
+}
+
+/// Retrieve official reviews for the given dependencies.
+///
+/// No request is made if the API key is not set. Dependencies without a known version are
+/// not queried.
+pub fn get(
+    all_dependencies: &Vec<&vouch_lib::extension::PackageDependencies>,
+    api_key: &str,
+    api_base_url: &str,
+) -> Result<HashMap<PackageKey, OfficialReview>> {
+    if api_key.is_empty() {
+        log::debug!("API key not set. Skipping official reviews query.");
+        return Ok(HashMap::new());
+    }
+
+    let mut packages = vec![];
+    for package_dependencies in all_dependencies {
+        for dependency in &package_dependencies.dependencies {
+            if let Ok(version) = &dependency.version {
+                packages.push(RequestPackage {
+                    registry: &package_dependencies.registry_host_name,
+                    name: &dependency.name,
+                    version: &version,
+                });
+            }
+        }
+    }
+    if packages.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let url = get_reviews_url(&api_base_url)?;
+    let body = serde_json::json!({ "packages": packages });
+    let span = tracing::info_span!("official_reviews_get", url = url.as_str());
+    let _enter = span.enter();
+    let response = reqwest::blocking::Client::new()
+        .post(url.clone())
+        .bearer_auth(&api_key)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(serde_json::to_string(&body)?)
+        .send()
+        .map_err(|error| format_err!("Failed to connect to Vouch API {}: {}", url, error))?;
+
+    match response.status() {
+        reqwest::StatusCode::UNAUTHORIZED => {
+            return Err(format_err!(
+                "Vouch API rejected the API key. See config field: core.api-key"
+            ));
+        }
+        reqwest::StatusCode::FORBIDDEN => {
+            return Err(format_err!("Vouch API request quota exceeded."));
+        }
+        reqwest::StatusCode::SERVICE_UNAVAILABLE => {
+            return Err(format_err!(
+                "Vouch API is temporarily unavailable. Try again later."
+            ));
+        }
+        status if !status.is_success() => {
+            return Err(format_err!(
+                "Failed to retrieve official reviews {}: HTTP status {}",
+                url,
+                status
+            ));
+        }
+        _ => {}
+    }
+    parse_response(&response.text()?)
+}
+
+fn get_reviews_url(api_base_url: &str) -> Result<url::Url> {
+    // A trailing slash ensures that the joined path is appended to the base path.
+    let api_base_url = format!("{}/", api_base_url.trim_end_matches('/'));
+    Ok(url::Url::parse(&api_base_url)
+        .map_err(|error| format_err!("Invalid API base URL {}: {}", api_base_url, error))?
+        .join("reviews")?)
+}
+
+fn parse_response(response: &str) -> Result<HashMap<PackageKey, OfficialReview>> {
+    #[derive(serde::Deserialize)]
+    struct Response {
+        reviews: Vec<ResponseReview>,
+    }
+    let response: Response = serde_json::from_str(&response)
+        .map_err(|error| format_err!("Failed to parse Vouch API response: {}", error))?;
+    Ok(response
+        .reviews
+        .into_iter()
+        .map(|review| {
+            (
+                (review.registry, review.name, review.version),
+                review.review,
+            )
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_dependencies() -> vouch_lib::extension::PackageDependencies {
+        vouch_lib::extension::PackageDependencies {
+            package_version: Ok("1.0.0".to_string()),
+            registry_host_name: "pypi.org".to_string(),
+            dependencies: vec![vouch_lib::extension::Dependency {
+                name: "numpy".to_string(),
+                version: Ok("1.18.5".to_string()),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_get_parses_reviews_keyed_on_package() -> Result<()> {
+        let reviews = mockito::mock("POST", "/official/v1/reviews")
+            .match_header("authorization", "Bearer key")
+            .with_status(200)
+            .with_body(
+                r#"{"reviews": [{
+                    "registry": "pypi.org",
+                    "name": "numpy",
+                    "version": "1.18.5",
+                    "summary": "pass",
+                    "reviewer_count": 3
+                }]}"#,
+            )
+            .expect(1)
+            .create();
+
+        let dependencies = get_dependencies();
+        let api_base_url = format!("{}/official/v1", mockito::server_url());
+        let result = get(&vec![&dependencies], "key", &api_base_url)?;
+
+        reviews.assert();
+        let expected = maplit::hashmap! {
+            ("pypi.org".to_string(), "numpy".to_string(), "1.18.5".to_string()) => OfficialReview {
+                summary: crate::review::Summary::Pass,
+                reviewer_count: 3,
+                note: None,
+            },
+        };
+        assert_eq!(result, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_reports_invalid_api_key() -> Result<()> {
+        let unauthorized = mockito::mock("POST", "/unauthorized/v1/reviews")
+            .with_status(401)
+            .expect(1)
+            .create();
+
+        let dependencies = get_dependencies();
+        let api_base_url = format!("{}/unauthorized/v1", mockito::server_url());
+        let result = get(&vec![&dependencies], "key", &api_base_url);
+
+        unauthorized.assert();
+        let error = result.err().ok_or(format_err!("Expected error."))?;
+        assert!(error.to_string().contains("core.api-key"));
+        Ok(())
+    }
+}
@@ -0,0 +1,173 @@
+//! Starter content for new active review files.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeSet;
+
+use crate::common;
+use crate::review;
+
+/// Review template settings. Loaded from the review template file in the config directory.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Template {
+    /// Message of the placeholder comment added for each top-level directory.
+    #[serde(
+        rename = "directory-message",
+        default = "get_default_directory_message"
+    )]
+    pub directory_message: String,
+
+    /// Messages of package wide comments which prompt the reviewer to consider common concerns.
+    #[serde(default = "get_default_concerns")]
+    pub concerns: Vec<String>,
+
+    /// List top-level files and their line counts in the review description.
+    #[serde(rename = "list-files", default = "get_default_list_files")]
+    pub list_files: bool,
+}
+
+impl Default for Template {
+    fn default() -> Self {
+        Self {
+            directory_message: get_default_directory_message(),
+            concerns: get_default_concerns(),
+            list_files: get_default_list_files(),
+        }
+    }
+}
+
+fn get_default_directory_message() -> String {
+    "Review directory contents.".to_string()
+}
+
+fn get_default_concerns() -> Vec<String> {
+    vec![
+        "Network usage: Does the package make network requests? Are they expected?".to_string(),
+        "File system access: Does the package read or write files outside of its own directory?"
+            .to_string(),
+        "Crypto operations: Does the package use cryptography? Are the algorithms and libraries \
+        appropriate?"
+            .to_string(),
+    ]
+}
+
+fn get_default_list_files() -> bool {
+    true
+}
+
+impl Template {
+    /// Load the review template file. Returns the default template if the file does not exist.
+    pub fn load() -> Result<Self> {
+        let paths = common::fs::ConfigPaths::new()?;
+        if !paths.review_template_file.is_file() {
+            return Ok(Self::default());
+        }
+        log::debug!(
+            "Loading review template: {}",
+            paths.review_template_file.display()
+        );
+        let file = std::fs::File::open(&paths.review_template_file)?;
+        let reader = std::io::BufReader::new(file);
+        Ok(serde_json::from_reader(reader).context(format!(
+            "Failed to parse review template: {}",
+            paths.review_template_file.display()
+        ))?)
+    }
+}
+
+/// Returns the active review description for a templated review.
+///
+/// The description includes the package registry page and, optionally, top-level file line counts.
+pub fn get_description(
+    review: &review::Review,
+    maintainers: &Vec<String>,
+    workspace_analysis: &review::workspace::Analysis,
+    template: &Template,
+) -> String {
+    let mut description = review::active::get_description(&review, &maintainers);
+    for registry in &review.package.registries {
+        description.push_str(&format!("\nRegistry: {}", registry.human_url));
+    }
+    if template.list_files {
+        let files: Vec<_> = get_top_level_paths(&workspace_analysis, common::fs::PathType::File)
+            .into_iter()
+            .map(|(path, line_count)| format!("  {} ({} lines)", path.display(), line_count))
+            .collect();
+        if !files.is_empty() {
+            description.push_str(&format!("\nFiles:\n{}", files.join("\n")));
+        }
+    }
+    description
+}
+
+/// Returns placeholder comments for a templated review.
+///
+/// One comment is given per top-level directory, and one package wide comment per concern.
+pub fn get_comments(
+    workspace_analysis: &review::workspace::Analysis,
+    template: &Template,
+) -> BTreeSet<review::comment::Comment> {
+    let directory_comments =
+        get_top_level_paths(&workspace_analysis, common::fs::PathType::Directory)
+            .into_iter()
+            .map(|(path, _line_count)| get_comment(&path, &template.directory_message));
+    let concern_comments = template
+        .concerns
+        .iter()
+        .map(|concern| get_comment(&std::path::PathBuf::new(), &concern));
+    directory_comments.chain(concern_comments).collect()
+}
+
+fn get_comment(path: &std::path::PathBuf, message: &str) -> review::comment::Comment {
+    review::comment::Comment {
+        id: 0,
+        summary: review::Summary::Todo,
+        path: path.clone(),
+        message: message.to_string(),
+        selection: None,
+    }
+}
+
+/// Returns top-level workspace paths of the given type and their line counts.
+fn get_top_level_paths(
+    workspace_analysis: &review::workspace::Analysis,
+    path_type: common::fs::PathType,
+) -> Vec<(std::path::PathBuf, usize)> {
+    workspace_analysis
+        .iter()
+        .filter(|(path, path_analysis)| {
+            path.components().count() == 1 && path_analysis.path_type == path_type
+        })
+        .map(|(path, path_analysis)| (path.clone(), path_analysis.line_count))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_comments_adds_top_level_directories_and_concerns() {
+        let path_analysis = |path_type, line_count| review::workspace::PathAnalysis {
+            path_type,
+            line_count,
+        };
+        let workspace_analysis = maplit::btreemap! {
+            std::path::PathBuf::new() => path_analysis(common::fs::PathType::Directory, 64),
+            std::path::PathBuf::from("setup.py") => path_analysis(common::fs::PathType::File, 22),
+            std::path::PathBuf::from("src") => path_analysis(common::fs::PathType::Directory, 42),
+            std::path::PathBuf::from("src/lib") => path_analysis(common::fs::PathType::Directory, 5),
+        };
+        let template = Template {
+            directory_message: "Check directory.".to_string(),
+            concerns: vec!["Network usage.".to_string()],
+            list_files: true,
+        };
+
+        let result = get_comments(&workspace_analysis, &template);
+        let expected = maplit::btreeset! {
+            get_comment(&std::path::PathBuf::from("src"), "Check directory."),
+            get_comment(&std::path::PathBuf::new(), "Network usage."),
+        };
+        assert_eq!(result, expected);
+    }
+}
@@ -0,0 +1,371 @@
+use anyhow::{format_err, Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+use crate::common::config::Config;
+use crate::review;
+
+/// Branch against which review changes are created.
+static TARGET_BRANCH: &str = "master";
+
+/// Prefix which Gerrit prepends to JSON responses to prevent XSSI.
+static JSON_RESPONSE_PREFIX: &str = ")]}'";
+
+/// Check that the configured Gerrit instance is reachable with the configured credentials.
+pub fn setup(config: &Config) -> Result<()> {
+    let base_url = get_base_url(config)?;
+    if config.review_tool.gerrit_project.is_empty() {
+        return Err(format_err!(
+            "Gerrit project not set. See config field: review-tool.gerrit-project"
+        ));
+    }
+    if config.review_tool.gerrit_username.is_empty()
+        || config.review_tool.gerrit_http_password.is_empty()
+    {
+        return Err(format_err!(
+            "Gerrit credentials not set. See config fields: \
+            review-tool.gerrit-username, review-tool.gerrit-http-password"
+        ));
+    }
+
+    let version_url = get_api_url(&base_url, "config/server/version")?;
+    let response =
+        get(&version_url, config).context(format!("Failed to connect to Gerrit: {}", base_url))?;
+    if !response.status().is_success() {
+        return Err(format_err!(
+            "Failed to connect to Gerrit API {}: HTTP status {}",
+            version_url,
+            response.status()
+        ));
+    }
+    log::debug!("Gerrit API reachable: {}", base_url);
+    Ok(())
+}
+
+/// Push the workspace to Gerrit as a change and import published review comments.
+///
+/// Each package workspace maps to a single change. Pushing again adds a patch set to the
+/// existing change. Comments are added to the active review file in the given reviews directory.
+pub fn run(
+    workspace_directory: &std::path::PathBuf,
+    reviews_directory: &std::path::PathBuf,
+    config: &Config,
+) -> Result<()> {
+    let base_url = get_base_url(config)?;
+    let change_id = get_change_id(workspace_directory, &config.review_tool.gerrit_project)?;
+    push_change(workspace_directory, &base_url, config, &change_id)?;
+
+    let change_url = base_url.join(&format!("q/{}", change_id))?;
+    println!("Opening Gerrit change: {}", change_url);
+    if let Err(error) = open::that(change_url.as_str()) {
+        log::warn!("Failed to open browser: {}", error);
+    }
+
+    while !dialoguer::Confirm::new()
+        .with_prompt("Have review comments been published in Gerrit?")
+        .interact()?
+    {}
+
+    let comments = get_comments(&base_url, &change_id, config)?;
+    let active_review_file = review::active::find(reviews_directory).ok_or(format_err!(
+        "Failed to find active review file in: {}",
+        reviews_directory.display()
+    ))?;
+    review::active::add_comments(&active_review_file, &comments)?;
+    Ok(())
+}
+
+fn get_base_url(config: &Config) -> Result<url::Url> {
+    if config.review_tool.gerrit_url.is_empty() {
+        return Err(format_err!(
+            "Gerrit URL not set. See config field: review-tool.gerrit-url"
+        ));
+    }
+    // A trailing slash ensures that joined paths are appended to the base path.
+    let base_url = format!("{}/", config.review_tool.gerrit_url.trim_end_matches('/'));
+    Ok(url::Url::parse(&base_url)?)
+}
+
+/// Returns the authenticated REST API URL for the given endpoint.
+fn get_api_url(base_url: &url::Url, endpoint: &str) -> Result<url::Url> {
+    Ok(base_url.join(&format!("a/{}", endpoint))?)
+}
+
+/// Send a GET request to the Gerrit REST API using the configured credentials.
+fn get(url: &url::Url, config: &Config) -> Result<reqwest::blocking::Response> {
+    Ok(reqwest::blocking::Client::new()
+        .get(url.clone())
+        .basic_auth(
+            &config.review_tool.gerrit_username,
+            Some(&config.review_tool.gerrit_http_password),
+        )
+        .send()?)
+}
+
+/// Returns the Gerrit Change-Id for a package workspace.
+///
+/// The Change-Id is derived from the workspace path and project so that repeated reviews of the
+/// same package update the existing change.
+fn get_change_id(workspace_directory: &std::path::Path, project: &str) -> Result<String> {
+    let workspace_directory = workspace_directory.to_str().ok_or(format_err!(
+        "Failed to parse path: {}",
+        workspace_directory.display()
+    ))?;
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(project.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(workspace_directory.as_bytes());
+    Ok(format!("I{}", &hasher.finalize().to_hex().as_str()[..40]))
+}
+
+/// Commit the workspace contents on top of the target branch and push for review.
+///
+/// A temporary repository is used so that the workspace itself is not modified.
+fn push_change(
+    workspace_directory: &std::path::PathBuf,
+    base_url: &url::Url,
+    config: &Config,
+    change_id: &str,
+) -> Result<()> {
+    let tmp_dir = tempdir::TempDir::new("vouch_gerrit")?;
+    let tmp_directory = tmp_dir.path().to_path_buf();
+    let git_directory = tmp_directory.join(".git");
+    let git_directory = git_directory.to_str().ok_or(format_err!(
+        "Failed to parse path: {}",
+        git_directory.display()
+    ))?;
+    let work_tree = workspace_directory.to_str().ok_or(format_err!(
+        "Failed to parse path: {}",
+        workspace_directory.display()
+    ))?;
+    let project_url = base_url.join(&config.review_tool.gerrit_project)?;
+    let commit_message = format!(
+        "Vouch review: {}\n\nChange-Id: {}",
+        workspace_directory
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default(),
+        change_id
+    );
+    let target_ref = format!("HEAD:refs/for/{}", TARGET_BRANCH);
+
+    let git_args = ["--git-dir", git_directory, "--work-tree", work_tree];
+    run_git(vec!["init", "--quiet"], &tmp_directory)?;
+    for args in [
+        &["fetch", "--quiet", project_url.as_str(), TARGET_BRANCH][..],
+        &["reset", "--quiet", "FETCH_HEAD"],
+        &["add", "--all", "--", ".", ":(exclude).vscode"],
+        &["commit", "--quiet", "-m", &commit_message],
+    ] {
+        run_git(
+            git_args.iter().chain(args.iter()).cloned().collect(),
+            workspace_directory,
+        )?;
+    }
+
+    let push_args: Vec<_> = git_args
+        .iter()
+        .chain(["push", "--quiet", project_url.as_str(), &target_ref].iter())
+        .cloned()
+        .collect();
+    let output = std::process::Command::new("git")
+        .args(&push_args)
+        .current_dir(workspace_directory)
+        .output()?;
+    if !output.status.success() {
+        // Gerrit rejects a push which matches the current patch set of the change.
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("no new changes") {
+            log::debug!("Gerrit change is up-to-date: {}", change_id);
+            return Ok(());
+        }
+        return Err(format_err!(
+            "Git command failed: git {}\n{}",
+            push_args.join(" "),
+            stderr
+        ));
+    }
+    Ok(())
+}
+
+fn run_git(args: Vec<&str>, working_directory: &std::path::PathBuf) -> Result<()> {
+    let status = std::process::Command::new("git")
+        .args(&args)
+        .current_dir(working_directory)
+        .status()?;
+    if !status.success() {
+        return Err(format_err!("Git command failed: git {}", args.join(" ")));
+    }
+    Ok(())
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GerritComment {
+    line: Option<i64>,
+    range: Option<GerritRange>,
+    message: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GerritRange {
+    start_line: i64,
+    start_character: i64,
+    end_line: i64,
+    end_character: i64,
+}
+
+/// Retrieve published comments for a change.
+fn get_comments(
+    base_url: &url::Url,
+    change_id: &str,
+    config: &Config,
+) -> Result<BTreeSet<review::comment::Comment>> {
+    let comments_url = get_api_url(base_url, &format!("changes/{}/comments", change_id))?;
+    let response = get(&comments_url, config)?;
+    if !response.status().is_success() {
+        return Err(format_err!(
+            "Failed to retrieve Gerrit comments {}: HTTP status {}",
+            comments_url,
+            response.status()
+        ));
+    }
+    parse_comments(&response.text()?)
+}
+
+/// Parse a Gerrit comments response into review comments.
+///
+/// A comment summary is taken from a message prefix such as "fail: ". Comments without a
+/// summary prefix are given the info summary. Gerrit line numbers start from one.
+fn parse_comments(response: &str) -> Result<BTreeSet<review::comment::Comment>> {
+    let response = response.trim_start_matches(JSON_RESPONSE_PREFIX);
+    let file_comments: BTreeMap<String, Vec<GerritComment>> = serde_json::from_str(response)?;
+
+    let mut comments = BTreeSet::new();
+    for (path, gerrit_comments) in file_comments {
+        // Special paths such as /COMMIT_MSG do not correspond to package files.
+        if path.starts_with('/') {
+            continue;
+        }
+        for gerrit_comment in gerrit_comments {
+            let (summary, message) = parse_summary(&gerrit_comment.message);
+            let selection = match (&gerrit_comment.range, gerrit_comment.line) {
+                (Some(range), _) => Some(review::comment::common::Selection {
+                    start: review::comment::common::Position {
+                        line: range.start_line - 1,
+                        character: range.start_character,
+                    },
+                    end: review::comment::common::Position {
+                        line: range.end_line - 1,
+                        character: range.end_character,
+                    },
+                }),
+                (None, Some(line)) => Some(review::comment::common::Selection {
+                    start: review::comment::common::Position {
+                        line: line - 1,
+                        character: 0,
+                    },
+                    end: review::comment::common::Position {
+                        line: line - 1,
+                        character: 0,
+                    },
+                }),
+                (None, None) => None,
+            };
+            comments.insert(review::comment::Comment {
+                id: 0,
+                summary,
+                path: std::path::PathBuf::from(&path),
+                message,
+                selection,
+            });
+        }
+    }
+    Ok(comments)
+}
+
+fn parse_summary(message: &str) -> (review::Summary, String) {
+    let mut parts = message.splitn(2, ':');
+    if let (Some(prefix), Some(remainder)) = (parts.next(), parts.next()) {
+        if let Ok(summary) = review::Summary::from_str(&prefix.trim().to_lowercase()) {
+            return (summary, remainder.trim().to_string());
+        }
+    }
+    (review::Summary::Info, message.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_url_uses_authenticated_path() -> Result<()> {
+        let mut config = Config::default();
+        config.review_tool.gerrit_url = "https://gerrit.example.com/r".to_string();
+        let base_url = get_base_url(&config)?;
+
+        let result = get_api_url(&base_url, "changes/I123/comments")?;
+        assert_eq!(
+            result.as_str(),
+            "https://gerrit.example.com/r/a/changes/I123/comments"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_change_id_is_stable_per_workspace() -> Result<()> {
+        let workspace_directory = std::path::PathBuf::from("/reviews/.ongoing/d3/4.10.0/d3-4.10.0");
+        let other_workspace_directory =
+            std::path::PathBuf::from("/reviews/.ongoing/d3/5.0.0/d3-5.0.0");
+
+        let change_id = get_change_id(&workspace_directory, "reviews")?;
+        assert_eq!(change_id.len(), 41);
+        assert!(change_id.starts_with('I'));
+        assert_eq!(change_id, get_change_id(&workspace_directory, "reviews")?);
+        assert_ne!(
+            change_id,
+            get_change_id(&other_workspace_directory, "reviews")?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_comments_reads_summary_and_selection() -> Result<()> {
+        let response = r#")]}'
+        {
+            "/COMMIT_MSG": [{"line": 1, "message": "Ignored."}],
+            "setup.py": [
+                {"line": 12, "message": "fail: Downloads code at install time."},
+                {
+                    "range": {"start_line": 3, "start_character": 4, "end_line": 5, "end_character": 6},
+                    "message": "Unusual import."
+                }
+            ]
+        }"#;
+
+        let result = parse_comments(response)?;
+        let expected = maplit::btreeset! {
+            review::comment::Comment {
+                id: 0,
+                summary: review::Summary::Fail,
+                path: std::path::PathBuf::from("setup.py"),
+                message: "Downloads code at install time.".to_string(),
+                selection: Some(review::comment::common::Selection {
+                    start: review::comment::common::Position { line: 11, character: 0 },
+                    end: review::comment::common::Position { line: 11, character: 0 },
+                }),
+            },
+            review::comment::Comment {
+                id: 0,
+                summary: review::Summary::Info,
+                path: std::path::PathBuf::from("setup.py"),
+                message: "Unusual import.".to_string(),
+                selection: Some(review::comment::common::Selection {
+                    start: review::comment::common::Position { line: 2, character: 4 },
+                    end: review::comment::common::Position { line: 4, character: 6 },
+                }),
+            },
+        };
+        assert_eq!(result, expected);
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use anyhow::{format_err, Result};
+mod gerrit;
 mod vscode;
 
 use crate::common;
@@ -8,13 +9,17 @@ pub fn check_install(config: &mut common::config::Config) -> Result<()> {
     if config.review_tool.install_check {
         return Ok(());
     }
-    if config.review_tool.name != "vscode" {
-        return Err(format_err!(
-            "Reviewing currently requires vscode. Unsupported review tool: {}",
-            config.review_tool.name
-        ));
+    match config.review_tool.name.as_str() {
+        "vscode" => vscode::setup()?,
+        "gerrit" => gerrit::setup(&config)?,
+        _ => {
+            return Err(format_err!(
+                "Unsupported review tool: {}\n\
+                Supported review tools: vscode, gerrit",
+                config.review_tool.name
+            ))
+        }
     }
-    vscode::setup()?;
 
     config.review_tool.install_check = true;
     config.dump()?;
@@ -32,7 +37,13 @@ pub fn run(
     );
 
     log::debug!("Running review tool.");
-    vscode::run(&workspace_directory)?;
+    match config.review_tool.name.as_str() {
+        "gerrit" => {
+            let reviews_directory = vscode::setup_reviews_directory(&workspace_directory)?;
+            gerrit::run(&workspace_directory, &reviews_directory, &config)?;
+        }
+        _ => vscode::run(&workspace_directory)?,
+    }
     log::debug!("Review tool exit complete.");
     Ok(())
 }
@@ -42,5 +53,11 @@ pub fn ensure_reviews_directory(
     workspace_directory: &std::path::PathBuf,
 ) -> Result<std::path::PathBuf> {
     let review_directory = vscode::setup_reviews_directory(&workspace_directory)?;
+    vscode::setup_workspace_settings(&workspace_directory)?;
     Ok(review_directory)
 }
+
+/// Returns the reviews directory path within the workspace without creating it.
+pub fn get_reviews_directory(workspace_directory: &std::path::PathBuf) -> std::path::PathBuf {
+    vscode::get_reviews_directory(&workspace_directory)
+}
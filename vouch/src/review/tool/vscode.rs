@@ -1,10 +1,11 @@
 use anyhow::{format_err, Context, Result};
+use std::io::Write;
 
 /// Setup reviews directory within workspace.
 pub fn setup_reviews_directory(
     workspace_directory: &std::path::PathBuf,
 ) -> Result<std::path::PathBuf> {
-    let vscode_review_directory = workspace_directory.join(".vscode").join("reviews");
+    let vscode_review_directory = get_reviews_directory(&workspace_directory);
     std::fs::create_dir_all(&vscode_review_directory).context(format!(
         "Can't create directory: {}",
         vscode_review_directory.display()
@@ -12,6 +13,116 @@ pub fn setup_reviews_directory(
     Ok(vscode_review_directory)
 }
 
+/// Returns the reviews directory path within the workspace. The directory may not exist.
+pub fn get_reviews_directory(workspace_directory: &std::path::PathBuf) -> std::path::PathBuf {
+    workspace_directory.join(".vscode").join("reviews")
+}
+
+/// Write Vouch specific VSCode settings and snippets into the workspace.
+///
+/// Existing workspace settings are merged with Vouch settings.
+pub fn setup_workspace_settings(workspace_directory: &std::path::PathBuf) -> Result<()> {
+    let vscode_directory = workspace_directory.join(".vscode");
+    std::fs::create_dir_all(&vscode_directory).context(format!(
+        "Can't create directory: {}",
+        vscode_directory.display()
+    ))?;
+
+    let settings_path = vscode_directory.join("settings.json");
+    let mut settings = if settings_path.is_file() {
+        match serde_json::from_str(&std::fs::read_to_string(&settings_path)?) {
+            Ok(settings) => settings,
+            Err(error) => {
+                log::warn!(
+                    "Not modifying unparsable VSCode settings file {}: {}",
+                    settings_path.display(),
+                    error
+                );
+                return Ok(());
+            }
+        }
+    } else {
+        serde_json::json!({})
+    };
+    merge_json(&mut settings, &get_settings());
+    write_atomic(&settings_path, &serde_json::to_string_pretty(&settings)?)?;
+
+    let snippets_path = vscode_directory.join("vouch.code-snippets");
+    write_atomic(
+        &snippets_path,
+        &serde_json::to_string_pretty(&get_snippets())?,
+    )?;
+    Ok(())
+}
+
+fn get_settings() -> serde_json::Value {
+    serde_json::json!({
+        "files.associations": {
+            "*.review": "json"
+        },
+        "files.exclude": {
+            "**/archive-*.zip": true,
+            "**/archive-*.tar.gz": true,
+            "**/archive-*.tgz": true
+        },
+        "[json]": {
+            "editor.wordWrap": "on"
+        }
+    })
+}
+
+/// Returns snippets for each review comment summary.
+fn get_snippets() -> serde_json::Value {
+    let mut snippets = serde_json::Map::new();
+    for summary in &["pass", "warn", "fail", "todo"] {
+        snippets.insert(
+            format!("Vouch {} comment", summary),
+            serde_json::json!({
+                "scope": "json",
+                "prefix": format!("vouch-{}", summary),
+                "body": format!("\"summary\": \"{}\",", summary),
+                "description": format!("Vouch review comment summary: {}", summary)
+            }),
+        );
+    }
+    serde_json::Value::Object(snippets)
+}
+
+/// Recursively merge JSON objects. Incoming values take precedence.
+fn merge_json(target: &mut serde_json::Value, incoming: &serde_json::Value) {
+    match (target, incoming) {
+        (serde_json::Value::Object(target), serde_json::Value::Object(incoming)) => {
+            for (key, value) in incoming {
+                merge_json(
+                    target.entry(key.clone()).or_insert(serde_json::Value::Null),
+                    &value,
+                );
+            }
+        }
+        (target, incoming) => {
+            *target = incoming.clone();
+        }
+    }
+}
+
+/// Write file contents via a temporary file which is then renamed.
+fn write_atomic(path: &std::path::PathBuf, contents: &str) -> Result<()> {
+    let tmp_path = path.with_extension("vouch-tmp");
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .truncate(true)
+        .create(true)
+        .open(&tmp_path)
+        .context(format!(
+            "Can't open/create file for writing: {}",
+            tmp_path.display()
+        ))?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    std::fs::rename(&tmp_path, &path)?;
+    Ok(())
+}
+
 pub fn run(workspace_directory: &std::path::PathBuf) -> Result<()> {
     let mut child = std::process::Command::new("code")
         .args(vec![
@@ -57,3 +168,25 @@ pub fn setup() -> Result<()> {
 
     Err(format_err!("Failed to install vscode vouch extension."))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_json_keeps_existing_settings() {
+        let mut target = serde_json::json!({
+            "editor.tabSize": 2,
+            "files.exclude": {"**/.git": true}
+        });
+        merge_json(
+            &mut target,
+            &serde_json::json!({"files.exclude": {"**/archive-*.zip": true}}),
+        );
+        let expected = serde_json::json!({
+            "editor.tabSize": 2,
+            "files.exclude": {"**/.git": true, "**/archive-*.zip": true}
+        });
+        assert_eq!(target, expected);
+    }
+}
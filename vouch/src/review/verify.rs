@@ -0,0 +1,352 @@
+//! Consistency checks between committed review files and the index.
+
+use anyhow::{format_err, Result};
+use std::collections::BTreeSet;
+
+use crate::common::StoreTransaction;
+use crate::package;
+use crate::peer;
+use crate::registry;
+use crate::review;
+
+/// A field whose value differs between a review file and the index.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Discrepancy {
+    pub field: String,
+    pub file_value: String,
+    pub index_value: String,
+}
+
+/// Verification result for a single review file.
+#[derive(Debug, Clone)]
+pub struct FileVerification {
+    /// Review file path relative to the peer's reviews directory.
+    pub relative_path: std::path::PathBuf,
+    pub file_review: Option<review::Review>,
+    pub index_review: Option<review::Review>,
+
+    /// Problems which can not be repaired using the review file.
+    pub errors: Vec<String>,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+impl FileVerification {
+    pub fn is_pass(&self) -> bool {
+        self.errors.is_empty() && self.discrepancies.is_empty()
+    }
+
+    pub fn is_repairable(&self) -> bool {
+        self.errors.is_empty() && !self.discrepancies.is_empty()
+    }
+}
+
+/// Verifies each of the peer's review files against the index.
+pub fn verify(peer: &peer::Peer, tx: &StoreTransaction) -> Result<Vec<FileVerification>> {
+    let peer_branch = peer::index::get_peer_branch(&peer, &tx)?;
+    let root_paths = crate::common::fs::DataPaths::new()?;
+    let paths = peer::fs::get_peer_data_paths(&peer_branch, &root_paths.root_directory)?;
+
+    let mut verifications = Vec::new();
+    for file_path in review::fs::get_review_file_paths(&paths.reviews_directory)? {
+        let relative_path = file_path
+            .strip_prefix(&paths.reviews_directory)?
+            .to_path_buf();
+        verifications.push(verify_file(&file_path, relative_path, &peer, &tx)?);
+    }
+    Ok(verifications)
+}
+
+fn verify_file(
+    file_path: &std::path::PathBuf,
+    relative_path: std::path::PathBuf,
+    peer: &peer::Peer,
+    tx: &StoreTransaction,
+) -> Result<FileVerification> {
+    let mut verification = FileVerification {
+        relative_path,
+        file_review: None,
+        index_review: None,
+        errors: Vec::new(),
+        discrepancies: Vec::new(),
+    };
+
+    let file_review = match review::fs::read(&file_path) {
+        Ok(file_review) => file_review,
+        Err(error) => {
+            verification.errors.push(format!("{:#}", error));
+            return Ok(verification);
+        }
+    };
+    let registry_host_name = match file_review.package.registries.iter().next() {
+        Some(registry) => registry.host_name.clone(),
+        None => {
+            verification
+                .errors
+                .push("Review file package has no registries.".to_string());
+            return Ok(verification);
+        }
+    };
+
+    match review::fs::get_review_file_relative_path(
+        &file_review.package.name,
+        &file_review.package.version,
+        &registry_host_name,
+    ) {
+        Ok(expected_path) if expected_path != verification.relative_path => {
+            verification.errors.push(format!(
+                "Review file location does not match its package. Expected: {}",
+                expected_path.display()
+            ));
+        }
+        Ok(_) => {}
+        Err(error) => verification.errors.push(error.to_string()),
+    }
+
+    let index_review = get_index_review(&file_review, &registry_host_name, &peer, &tx)?;
+    match &index_review {
+        Some(index_review) => {
+            verification.discrepancies = get_discrepancies(&file_review, &index_review);
+        }
+        None => verification.discrepancies.push(Discrepancy {
+            field: "review".to_string(),
+            file_value: "present".to_string(),
+            index_value: "missing".to_string(),
+        }),
+    }
+    verification.file_review = Some(file_review);
+    verification.index_review = index_review;
+    Ok(verification)
+}
+
+/// Returns the peer's indexed review for the review file's package.
+fn get_index_review(
+    file_review: &review::Review,
+    registry_host_name: &str,
+    peer: &peer::Peer,
+    tx: &StoreTransaction,
+) -> Result<Option<review::Review>> {
+    let reviews = review::index::get(
+        &review::index::Fields {
+            package_name: Some(&file_review.package.name),
+            package_version: Some(&file_review.package.version),
+            peer: Some(&peer),
+            ..Default::default()
+        },
+        &tx,
+    )?;
+    Ok(reviews.into_iter().find(|review| {
+        review.package.version == file_review.package.version
+            && review
+                .package
+                .registries
+                .iter()
+                .any(|registry| registry.host_name == registry_host_name)
+    }))
+}
+
+fn get_discrepancies(
+    file_review: &review::Review,
+    index_review: &review::Review,
+) -> Vec<Discrepancy> {
+    let fields = vec![
+        (
+            "package name",
+            file_review.package.name.clone(),
+            index_review.package.name.clone(),
+        ),
+        (
+            "package version",
+            file_review.package.version.clone(),
+            index_review.package.version.clone(),
+        ),
+        (
+            "version constraint",
+            file_review.version_constraint.clone().unwrap_or_default(),
+            index_review.version_constraint.clone().unwrap_or_default(),
+        ),
+        (
+            "comment count",
+            file_review.comments.len().to_string(),
+            index_review.comments.len().to_string(),
+        ),
+        (
+            "comment summaries",
+            get_comment_summaries(&file_review),
+            get_comment_summaries(&index_review),
+        ),
+    ];
+    fields
+        .into_iter()
+        .filter(|(_, file_value, index_value)| file_value != index_value)
+        .map(|(field, file_value, index_value)| Discrepancy {
+            field: field.to_string(),
+            file_value,
+            index_value,
+        })
+        .collect()
+}
+
+/// Returns the sorted comment summaries of a review as a comma separated string.
+fn get_comment_summaries(review: &review::Review) -> String {
+    let mut summaries: Vec<_> = review
+        .comments
+        .iter()
+        .map(|comment| comment.summary.clone())
+        .collect();
+    summaries.sort();
+    summaries
+        .iter()
+        .map(|summary| summary.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Update the index to match the review file.
+pub fn repair(
+    verification: &FileVerification,
+    peer: &peer::Peer,
+    tx: &StoreTransaction,
+) -> Result<()> {
+    if !verification.is_repairable() {
+        return Err(format_err!(
+            "Review file can not be used for repair: {}",
+            verification.relative_path.display()
+        ));
+    }
+    let file_review = verification.file_review.as_ref().ok_or(format_err!(
+        "Review file not loaded: {}",
+        verification.relative_path.display()
+    ))?;
+
+    let mut comments = BTreeSet::new();
+    for comment in &file_review.comments {
+        comments.insert(review::comment::index::get_or_insert(
+            &comment.path,
+            &comment.summary,
+            &comment.message,
+            &comment.selection,
+            &tx,
+        )?);
+    }
+
+    match &verification.index_review {
+        Some(index_review) => {
+            let mut index_review = index_review.clone();
+            index_review.comments = comments;
+            index_review.version_constraint = file_review.version_constraint.clone();
+            review::index::update(&index_review, &tx)?;
+        }
+        None => {
+            let package = ensure_package(&file_review.package, &tx)?;
+            review::index::insert(
+                &comments,
+                &peer,
+                &package,
+                &file_review.version_constraint,
+                &tx,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the indexed package matching the given package, inserting it if missing.
+fn ensure_package(package: &package::Package, tx: &StoreTransaction) -> Result<package::Package> {
+    let registry_host_names = package
+        .registries
+        .iter()
+        .map(|registry| registry.host_name.as_str())
+        .collect();
+    let existing_package = package::index::get(
+        &package::index::Fields {
+            package_name: Some(&package.name),
+            package_version: Some(&package.version),
+            registry_host_names: Some(registry_host_names),
+            ..Default::default()
+        },
+        &tx,
+    )?
+    .into_iter()
+    .next();
+    if let Some(existing_package) = existing_package {
+        return Ok(existing_package);
+    }
+
+    let mut registries = BTreeSet::new();
+    for registry in &package.registries {
+        registries.insert(registry::index::get_or_insert(
+            &registry.host_name,
+            &registry.human_url,
+            &registry.artifact_url,
+            &tx,
+        )?);
+    }
+    package::index::insert(
+        &package.name,
+        &package.version,
+        &registries,
+        &package.artifact_hash,
+        &tx,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_comment(summary: review::Summary, message: &str) -> review::comment::Comment {
+        review::comment::Comment {
+            id: 0,
+            summary,
+            path: std::path::PathBuf::from("setup.py"),
+            message: message.to_string(),
+            selection: None,
+        }
+    }
+
+    #[test]
+    fn test_discrepancies_report_differing_comments() -> Result<()> {
+        let mut file_review = review::Review {
+            id: 0,
+            peer: peer::Peer::default(),
+            package: package::Package {
+                id: 1,
+                name: "numpy".to_string(),
+                version: "1.18.5".to_string(),
+                registries: BTreeSet::new(),
+                artifact_hash: "hash".to_string(),
+            },
+            comments: BTreeSet::new(),
+            version_constraint: None,
+            total_line_count: None,
+            quality_score: None,
+        };
+        file_review
+            .comments
+            .insert(get_comment(review::Summary::Fail, "Unsafe eval."));
+        file_review
+            .comments
+            .insert(get_comment(review::Summary::Pass, "Looks fine."));
+
+        let mut index_review = file_review.clone();
+        assert!(get_discrepancies(&file_review, &index_review).is_empty());
+
+        index_review.comments =
+            maplit::btreeset! {get_comment(review::Summary::Pass, "Looks fine.")};
+        let result = get_discrepancies(&file_review, &index_review);
+        let expected = vec![
+            Discrepancy {
+                field: "comment count".to_string(),
+                file_value: "2".to_string(),
+                index_value: "1".to_string(),
+            },
+            Discrepancy {
+                field: "comment summaries".to_string(),
+                file_value: "fail, pass".to_string(),
+                index_value: "pass".to_string(),
+            },
+        ];
+        assert_eq!(result, expected);
+        Ok(())
+    }
+}
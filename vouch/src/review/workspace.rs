@@ -6,16 +6,52 @@ use crate::common::{self, fs::archive::ArchiveType};
 use crate::review;
 
 static MANIFEST_FILE_NAME: &str = "manifest.json";
+static MANIFEST_VERSION: u32 = 1;
 
 // TODO: Make paths relative.
 #[derive(
     Debug, Clone, Default, Ord, PartialOrd, Eq, PartialEq, serde::Serialize, serde::Deserialize,
 )]
 pub struct Manifest {
+    /// Manifest format version. Absent (zero) in manifests written before versioning.
+    #[serde(default)]
+    pub manifest_version: u32,
     pub workspace_path: std::path::PathBuf,
     pub manifest_path: std::path::PathBuf,
     pub artifact_path: std::path::PathBuf,
     pub artifact_hash: String,
+    /// Hash of the extracted workspace directory. Absent in older manifests.
+    #[serde(default)]
+    pub workspace_hash: String,
+    /// Artifact checksum published by the registry, if known.
+    #[serde(default)]
+    pub registry_checksum: Option<ArtifactChecksum>,
+}
+
+/// Artifact checksum computed using a registry specific hash algorithm.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ArtifactChecksum {
+    pub algorithm: vouch_lib::extension::HashAlgorithm,
+    pub hash: String,
+}
+
+impl ArtifactChecksum {
+    /// Returns the registry checksum given in registry package metadata, if any.
+    pub fn from_registry_metadata(
+        registry_metadata: &vouch_lib::extension::RegistryPackageMetadata,
+    ) -> Option<Self> {
+        registry_metadata
+            .artifact_hash
+            .as_ref()
+            .map(|hash| ArtifactChecksum {
+                algorithm: registry_metadata.hash_algorithm,
+                hash: hash.to_lowercase(),
+            })
+    }
+
+    fn matches(&self, path: &std::path::PathBuf) -> Result<bool> {
+        Ok(common::fs::hash_with_algorithm(&path, self.algorithm)? == self.hash)
+    }
 }
 
 /// Create unique archive file name.
@@ -34,19 +70,91 @@ fn archive_file_name(archive_type: common::fs::archive::ArchiveType) -> Result<S
 /// Ensure review workspace setup is complete.
 ///
 /// Download and unpack package for review.
-/// If ongoing workspace exists, return manifest.
+/// If ongoing workspace exists, return manifest. An existing workspace which fails
+/// integrity validation is only replaced if redownload is true. The active review file of a
+/// replaced workspace is kept.
+/// The downloaded artifact is verified against the registry checksum if one is given, and by the
+/// registry's extension if one is given.
 pub fn ensure(
     package_name: &str,
     package_version: &str,
     registry_host_name: &str,
     artifact_url: &url::Url,
+    registry_checksum: &Option<ArtifactChecksum>,
+    extension: Option<&dyn vouch_lib::extension::Extension>,
+    redownload: bool,
+    max_size_mb: Option<u64>,
 ) -> Result<Manifest> {
+    let span = tracing::info_span!(
+        "workspace_ensure",
+        package_name,
+        package_version,
+        registry_host_name
+    );
+    let _enter = span.enter();
     if let Some(workspace_manifest) =
         get_existing(&package_name, &package_version, &registry_host_name)?
     {
-        return Ok(workspace_manifest);
+        if validate_workspace_integrity(&workspace_manifest)? {
+            return Ok(workspace_manifest);
+        }
+        if !redownload {
+            log::warn!(
+                "Workspace does not match recorded hash: {}",
+                workspace_manifest.workspace_path.display()
+            );
+            println!(
+                "WARNING: Review workspace has been modified since download: {path}\n\
+                Use --redownload to delete and re-download the workspace.",
+                path = workspace_manifest.workspace_path.display()
+            );
+            return Ok(workspace_manifest);
+        }
+        println!("Re-downloading modified review workspace.");
+        let active_review_backup = backup_active_review_file(&workspace_manifest)?;
+        remove(&workspace_manifest)?;
+
+        let result = download(
+            &package_name,
+            &package_version,
+            &registry_host_name,
+            &artifact_url,
+            &registry_checksum,
+            extension,
+            max_size_mb,
+        );
+        if let Some(backup_path) = active_review_backup {
+            match &result {
+                Ok(workspace_manifest) => {
+                    restore_active_review_file(&backup_path, &workspace_manifest.workspace_path)?
+                }
+                Err(_) => println!("Ongoing review file kept at: {}", backup_path.display()),
+            }
+        }
+        return result;
     }
 
+    download(
+        &package_name,
+        &package_version,
+        &registry_host_name,
+        &artifact_url,
+        &registry_checksum,
+        extension,
+        max_size_mb,
+    )
+}
+
+/// Download and unpack package into a new review workspace.
+fn download(
+    package_name: &str,
+    package_version: &str,
+    registry_host_name: &str,
+    artifact_url: &url::Url,
+    registry_checksum: &Option<ArtifactChecksum>,
+    extension: Option<&dyn vouch_lib::extension::Extension>,
+    max_size_mb: Option<u64>,
+) -> Result<Manifest> {
     let archive_type =
         common::fs::archive::ArchiveType::try_from(&std::path::PathBuf::from(artifact_url.path()))?;
     if archive_type == ArchiveType::Unknown {
@@ -56,11 +164,35 @@ pub fn ensure(
         ));
     }
 
+    if let Some(max_size_mb) = max_size_mb {
+        check_archive_size(&artifact_url, max_size_mb)?;
+    }
+
     let package_unique_directory =
         setup_unique_package_directory(&package_name, &package_version, &registry_host_name)?;
     let archive_path = package_unique_directory.join(archive_file_name(archive_type)?);
 
     common::fs::archive::download(&artifact_url, &archive_path)?;
+    if let Some(registry_checksum) = registry_checksum {
+        if !registry_checksum.matches(&archive_path)? {
+            std::fs::remove_file(&archive_path)?;
+            return Err(format_err!(
+                "Downloaded artifact does not match registry {algorithm:?} checksum: {url}",
+                algorithm = registry_checksum.algorithm,
+                url = artifact_url
+            ));
+        }
+        if let Some(extension) = extension {
+            if !extension.verify_archive_hash(&archive_path, &registry_checksum.hash)? {
+                std::fs::remove_file(&archive_path)?;
+                return Err(format_err!(
+                    "Downloaded artifact failed {name} extension hash verification: {url}",
+                    name = extension.name(),
+                    url = artifact_url
+                ));
+            }
+        }
+    }
     let (artifact_hash, _) = common::fs::hash(&archive_path)?;
 
     let workspace_directory =
@@ -74,16 +206,129 @@ pub fn ensure(
         &package_version,
     )?;
 
+    let workspace_hash = get_workspace_hash(&workspace_directory)?;
+
     let workspace_manifest = Manifest {
+        manifest_version: MANIFEST_VERSION,
         workspace_path: workspace_directory,
         manifest_path: get_manifest_path(&package_unique_directory),
         artifact_path: archive_path,
         artifact_hash: artifact_hash,
+        workspace_hash,
+        registry_checksum: registry_checksum.clone(),
     };
     write_manifest(&workspace_manifest)?;
     Ok(workspace_manifest)
 }
 
+/// Fail if the package archive is known to exceed the given size limit.
+///
+/// The archive size is read from the Content-Length header of a HEAD request.
+/// The check is skipped if the registry does not report a size.
+fn check_archive_size(artifact_url: &url::Url, max_size_mb: u64) -> Result<()> {
+    let size = match common::fs::archive::get_content_length(&artifact_url) {
+        Some(size) => size,
+        None => {
+            log::debug!("Archive size unknown, skipping size limit check.");
+            return Ok(());
+        }
+    };
+    let max_size = max_size_mb.saturating_mul(1024 * 1024);
+    if size > max_size {
+        return Err(format_err!(
+            "Package archive is {size:.1}MB, exceeding configured limit of {limit}MB. \
+            Use --ignore-size-limit to proceed.",
+            size = size as f64 / (1024.0 * 1024.0),
+            limit = max_size_mb
+        ));
+    }
+    Ok(())
+}
+
+/// Check that workspace contents match the hashes recorded in the manifest.
+///
+/// The artifact archive is compared if it still exists, using the registry checksum algorithm
+/// where known. The extracted workspace is compared if a workspace hash was recorded. Returns
+/// true if there is nothing to compare.
+pub fn validate_workspace_integrity(manifest: &Manifest) -> Result<bool> {
+    if !manifest.workspace_path.is_dir() {
+        return Ok(false);
+    }
+    if manifest.artifact_path.is_file() {
+        // Prefer the registry's checksum algorithm where known.
+        if let Some(registry_checksum) = &manifest.registry_checksum {
+            if !registry_checksum.matches(&manifest.artifact_path)? {
+                return Ok(false);
+            }
+        } else if !manifest.artifact_hash.is_empty() {
+            let (artifact_hash, _) = common::fs::hash(&manifest.artifact_path)?;
+            if artifact_hash != manifest.artifact_hash {
+                return Ok(false);
+            }
+        }
+    }
+    if !manifest.workspace_hash.is_empty() {
+        return Ok(get_workspace_hash(&manifest.workspace_path)? == manifest.workspace_hash);
+    }
+    Ok(true)
+}
+
+/// Returns the workspace content hash.
+///
+/// Review tool files which Vouch writes into the workspace are excluded.
+fn get_workspace_hash(workspace_directory: &std::path::PathBuf) -> Result<String> {
+    let vscode_directory = std::path::PathBuf::from(".vscode");
+    common::fs::hash_directory(
+        &workspace_directory,
+        &vec![
+            vscode_directory.join("reviews"),
+            vscode_directory.join("settings.json"),
+            vscode_directory.join("vouch.code-snippets"),
+        ],
+    )
+}
+
+/// Move the workspace's active review file out of the workspace.
+///
+/// The file is moved into the package directory which contains the workspace.
+/// Returns the moved file path, or None if there is no active review file.
+fn backup_active_review_file(manifest: &Manifest) -> Result<Option<std::path::PathBuf>> {
+    let reviews_directory = super::tool::get_reviews_directory(&manifest.workspace_path);
+    let active_review_file = match super::active::find(&reviews_directory) {
+        Some(active_review_file) => active_review_file,
+        None => return Ok(None),
+    };
+    let package_unique_directory = manifest.workspace_path.parent().ok_or(format_err!(
+        "Failed to find workspace parent directory: {}",
+        manifest.workspace_path.display()
+    ))?;
+    let file_name = active_review_file.file_name().ok_or(format_err!(
+        "Failed to find review file name: {}",
+        active_review_file.display()
+    ))?;
+    let backup_path = package_unique_directory.join(file_name);
+    std::fs::rename(&active_review_file, &backup_path)?;
+    Ok(Some(backup_path))
+}
+
+/// Move an active review file back into the given workspace.
+fn restore_active_review_file(
+    backup_path: &std::path::PathBuf,
+    workspace_directory: &std::path::PathBuf,
+) -> Result<()> {
+    let reviews_directory = super::tool::get_reviews_directory(&workspace_directory);
+    std::fs::create_dir_all(&reviews_directory).context(format!(
+        "Can't create directory: {}",
+        reviews_directory.display()
+    ))?;
+    let file_name = backup_path.file_name().ok_or(format_err!(
+        "Failed to find review file name: {}",
+        backup_path.display()
+    ))?;
+    std::fs::rename(&backup_path, reviews_directory.join(file_name))?;
+    Ok(())
+}
+
 fn get_manifest_path(package_unique_directory: &std::path::PathBuf) -> std::path::PathBuf {
     package_unique_directory.join(MANIFEST_FILE_NAME)
 }
@@ -98,11 +343,16 @@ fn write_manifest(workspace_manifest: &Manifest) -> Result<()> {
         .write(true)
         .append(false)
         .create(true)
+        .truncate(true)
         .open(&path)
         .context(format!(
             "Can't open/create file for writing: {}",
             path.display()
         ))?;
+    let workspace_manifest = Manifest {
+        manifest_version: MANIFEST_VERSION,
+        ..workspace_manifest.clone()
+    };
     file.write_all(serde_json::to_string_pretty(&workspace_manifest)?.as_bytes())?;
     Ok(())
 }
@@ -110,7 +360,53 @@ fn write_manifest(workspace_manifest: &Manifest) -> Result<()> {
 fn read_manifest(path: &std::path::PathBuf) -> Result<Manifest> {
     let file = std::fs::File::open(path)?;
     let reader = std::io::BufReader::new(file);
-    Ok(serde_yaml::from_reader(reader)?)
+    let workspace_manifest: Manifest = serde_yaml::from_reader(reader)?;
+    if workspace_manifest.manifest_version == MANIFEST_VERSION {
+        return Ok(workspace_manifest);
+    }
+
+    let workspace_manifest = migrate_manifest(workspace_manifest)?;
+    write_manifest(&workspace_manifest)?;
+    Ok(workspace_manifest)
+}
+
+/// Migrate workspace manifest to the current format version.
+fn migrate_manifest(workspace_manifest: Manifest) -> Result<Manifest> {
+    log::debug!(
+        "Migrating workspace manifest from version {} to {}: {}",
+        workspace_manifest.manifest_version,
+        MANIFEST_VERSION,
+        workspace_manifest.manifest_path.display()
+    );
+    match workspace_manifest.manifest_version {
+        0 => migrate_manifest_v0(workspace_manifest),
+        version => Err(format_err!(
+            "Unsupported workspace manifest version {} (expected {}): {}",
+            version,
+            MANIFEST_VERSION,
+            workspace_manifest.manifest_path.display()
+        )),
+    }
+}
+
+/// Migrate unversioned workspace manifest.
+///
+/// The artifact archive is usually removed after extraction. The artifact hash is only
+/// recomputed if it is missing and the archive is still present.
+fn migrate_manifest_v0(workspace_manifest: Manifest) -> Result<Manifest> {
+    let mut artifact_hash = workspace_manifest.artifact_hash.clone();
+    if artifact_hash.is_empty()
+        && workspace_manifest.workspace_path.is_dir()
+        && workspace_manifest.artifact_path.is_file()
+    {
+        let (hash, _) = common::fs::hash(&workspace_manifest.artifact_path)?;
+        artifact_hash = hash;
+    }
+    Ok(Manifest {
+        manifest_version: MANIFEST_VERSION,
+        artifact_hash,
+        ..workspace_manifest
+    })
 }
 
 /// Returns optional path to existing review workspace directory.
@@ -129,6 +425,49 @@ pub fn get_existing(
     }
 }
 
+/// Returns the registry host name, package name and package version of every ongoing review workspace.
+pub fn get_all_existing() -> Result<Vec<(String, String, String)>> {
+    let data_paths = common::fs::DataPaths::new()?;
+
+    let mut workspaces = vec![];
+    for registry_host_name in get_subdirectory_names(&data_paths.ongoing_reviews_directory)? {
+        let registry_directory = data_paths
+            .ongoing_reviews_directory
+            .join(&registry_host_name);
+        for package_name in get_subdirectory_names(&registry_directory)? {
+            for package_version in get_subdirectory_names(&registry_directory.join(&package_name))?
+            {
+                if get_existing(&package_name, &package_version, &registry_host_name)?.is_some() {
+                    workspaces.push((
+                        registry_host_name.clone(),
+                        package_name.clone(),
+                        package_version,
+                    ));
+                }
+            }
+        }
+    }
+    Ok(workspaces)
+}
+
+fn get_subdirectory_names(directory: &std::path::PathBuf) -> Result<Vec<String>> {
+    if !directory.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let mut names = vec![];
+    for entry in std::fs::read_dir(&directory)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        if let Some(name) = entry.file_name().to_str() {
+            names.push(name.to_string());
+        }
+    }
+    Ok(names)
+}
+
 fn get_unique_package_directory(
     package_name: &str,
     package_version: &str,
@@ -266,6 +605,15 @@ pub fn analyse(workspace_directory: &std::path::PathBuf) -> Result<Analysis> {
     Ok(analysis)
 }
 
+/// Returns the sum of file line counts in a workspace analysis.
+pub fn get_total_line_count(analysis: &Analysis) -> usize {
+    analysis
+        .values()
+        .filter(|path_analysis| matches!(path_analysis.path_type, common::fs::PathType::File))
+        .map(|path_analysis| path_analysis.line_count)
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -287,6 +635,111 @@ mod tests {
         assert_eq!(result, expected);
         Ok(())
     }
+
+    #[test]
+    fn test_total_line_count_excludes_directories() {
+        let analysis = maplit::btreemap! {
+            std::path::PathBuf::from("build") => PathAnalysis {
+                path_type: common::fs::PathType::Directory,
+                line_count: 42,
+            },
+            std::path::PathBuf::from("build/file_2.js") => PathAnalysis {
+                path_type: common::fs::PathType::File,
+                line_count: 37,
+            },
+            std::path::PathBuf::from("file_1.js") => PathAnalysis {
+                path_type: common::fs::PathType::File,
+                line_count: 22,
+            },
+        };
+        assert_eq!(get_total_line_count(&analysis), 59);
+    }
+
+    #[test]
+    fn test_read_unversioned_manifest() -> Result<()> {
+        let tmp_dir = tempdir::TempDir::new("vouch_test_read_unversioned_manifest")?;
+        let manifest_path = tmp_dir.path().join(MANIFEST_FILE_NAME);
+        let contents = serde_json::json!({
+            "workspace_path": tmp_dir.path().join("d3-4.10.0"),
+            "manifest_path": manifest_path,
+            "artifact_path": tmp_dir.path().join("archive.tgz"),
+            "artifact_hash": "",
+        });
+        std::fs::write(&manifest_path, serde_json::to_string_pretty(&contents)?)?;
+
+        let result = read_manifest(&manifest_path)?;
+        assert_eq!(result.manifest_version, MANIFEST_VERSION);
+        assert_eq!(read_manifest(&manifest_path)?, result);
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_workspace_integrity_detects_modification() -> Result<()> {
+        let tmp_dir = tempdir::TempDir::new("vouch_test_validate_workspace_integrity")?;
+        let workspace_path = tmp_dir.path().join("d3-4.10.0");
+        std::fs::create_dir_all(workspace_path.join("build"))?;
+        std::fs::write(workspace_path.join("build").join("d3.js"), "original")?;
+
+        let manifest = Manifest {
+            manifest_version: MANIFEST_VERSION,
+            workspace_path: workspace_path.clone(),
+            manifest_path: tmp_dir.path().join(MANIFEST_FILE_NAME),
+            artifact_path: tmp_dir.path().join("archive.tgz"),
+            artifact_hash: "".to_string(),
+            workspace_hash: get_workspace_hash(&workspace_path)?,
+            registry_checksum: None,
+        };
+
+        // Review tool files are excluded.
+        std::fs::create_dir_all(workspace_path.join(".vscode").join("reviews"))?;
+        std::fs::write(workspace_path.join(".vscode").join("settings.json"), "{}")?;
+        std::fs::write(
+            workspace_path
+                .join(".vscode")
+                .join("reviews")
+                .join("local.review"),
+            "{}",
+        )?;
+        assert!(validate_workspace_integrity(&manifest)?);
+
+        // Other files within the VSCode directory are package contents.
+        std::fs::write(workspace_path.join(".vscode").join("tasks.json"), "{}")?;
+        assert!(!validate_workspace_integrity(&manifest)?);
+        std::fs::remove_file(workspace_path.join(".vscode").join("tasks.json"))?;
+
+        std::fs::write(workspace_path.join("build").join("d3.js"), "modified")?;
+        assert!(!validate_workspace_integrity(&manifest)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_active_review_file_backup_and_restore() -> Result<()> {
+        let tmp_dir = tempdir::TempDir::new("vouch_test_active_review_file_backup_and_restore")?;
+        let workspace_path = tmp_dir.path().join("d3-4.10.0");
+        let reviews_directory = super::super::tool::get_reviews_directory(&workspace_path);
+        std::fs::create_dir_all(&reviews_directory)?;
+        std::fs::write(reviews_directory.join("local.review"), "comments")?;
+
+        let manifest = Manifest {
+            manifest_version: MANIFEST_VERSION,
+            workspace_path: workspace_path.clone(),
+            manifest_path: tmp_dir.path().join(MANIFEST_FILE_NAME),
+            artifact_path: tmp_dir.path().join("archive.tgz"),
+            artifact_hash: "".to_string(),
+            workspace_hash: "".to_string(),
+            registry_checksum: None,
+        };
+        let backup_path = backup_active_review_file(&manifest)?.unwrap();
+        std::fs::remove_dir_all(&workspace_path)?;
+
+        restore_active_review_file(&backup_path, &workspace_path)?;
+        assert_eq!(
+            std::fs::read_to_string(reviews_directory.join("local.review"))?,
+            "comments"
+        );
+        assert!(!backup_path.exists());
+        Ok(())
+    }
 }
 
 pub fn remove(workspace_manifest: &Manifest) -> Result<()> {
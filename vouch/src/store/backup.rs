@@ -0,0 +1,308 @@
+//! Backup and restore of the local data directory.
+
+use anyhow::{format_err, Context, Result};
+use std::io::Read;
+
+use crate::common::fs::DataPaths;
+
+static MANIFEST_FILE_NAME: &str = "backup-manifest.json";
+
+/// Backup archive metadata. Used to check compatibility on restore.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Manifest {
+    #[serde(rename = "vouch-version")]
+    pub vouch_version: String,
+}
+
+/// Returns the default backup archive file name for the current date.
+pub fn get_default_file_name() -> String {
+    format!(
+        "vouch-backup-{}.tar.gz",
+        chrono::Local::now().naive_local().date()
+    )
+}
+
+/// Create a .tar.gz backup archive of the data directory.
+///
+/// Git directories are included so that the restored stores remain git repositories.
+pub fn create(output_path: &std::path::PathBuf) -> Result<()> {
+    let paths = DataPaths::new()?;
+    create_archive(&paths.root_directory, &output_path)
+}
+
+fn create_archive(
+    root_directory: &std::path::PathBuf,
+    output_path: &std::path::PathBuf,
+) -> Result<()> {
+    if !root_directory.is_dir() {
+        return Err(format_err!(
+            "Data directory not found: {}",
+            root_directory.display()
+        ));
+    }
+
+    let file = std::fs::File::create(&output_path).context(format!(
+        "Can't create backup file: {}",
+        output_path.display()
+    ))?;
+    let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+
+    let manifest = serde_json::to_string_pretty(&Manifest {
+        vouch_version: env!("CARGO_PKG_VERSION").to_string(),
+    })?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, MANIFEST_FILE_NAME, manifest.as_bytes())?;
+
+    append_directory(&mut builder, &root_directory, &std::path::PathBuf::new())?;
+    builder.into_inner()?.finish()?;
+    Ok(())
+}
+
+/// Recursively append directory contents to archive.
+fn append_directory<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    directory: &std::path::PathBuf,
+    relative_directory: &std::path::PathBuf,
+) -> Result<()> {
+    for entry in std::fs::read_dir(&directory)? {
+        let entry = entry?;
+        let path = entry.path();
+        let relative_path = relative_directory.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            builder.append_dir(&relative_path, &path)?;
+            append_directory(builder, &path, &relative_path)?;
+        } else {
+            builder.append_path_with_name(&path, &relative_path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Restore a backup archive into the data directory.
+///
+/// Fails if the data directory is not empty unless forced. If forced, the existing data directory
+/// is replaced once the archive has been fully extracted. Returns the backup manifest.
+pub fn restore(backup_path: &std::path::PathBuf, force: bool) -> Result<Manifest> {
+    let paths = DataPaths::new()?;
+    restore_archive(&paths.root_directory, &backup_path, force)
+}
+
+fn restore_archive(
+    root_directory: &std::path::PathBuf,
+    backup_path: &std::path::PathBuf,
+    force: bool,
+) -> Result<Manifest> {
+    if !force && is_non_empty_directory(&root_directory)? {
+        return Err(format_err!(
+            "Data directory is not empty: {}\n\
+            Use --force to overwrite.",
+            root_directory.display()
+        ));
+    }
+    let manifest = read_manifest(&backup_path)?;
+
+    if root_directory.is_dir()
+        && backup_path
+            .canonicalize()?
+            .starts_with(root_directory.canonicalize()?)
+    {
+        return Err(format_err!(
+            "Backup file is inside the data directory and would be removed on restore: {}\n\
+            Move it elsewhere first.",
+            backup_path.display()
+        ));
+    }
+
+    // Extract next to the data directory so that it can be swapped in with a rename.
+    let parent_directory = root_directory.parent().ok_or(format_err!(
+        "Data directory has no parent directory: {}",
+        root_directory.display()
+    ))?;
+    std::fs::create_dir_all(&parent_directory)?;
+    let staging_directory = tempdir::TempDir::new_in(&parent_directory, "vouch_restore")?;
+
+    let file = std::fs::File::open(&backup_path)?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    archive.unpack(staging_directory.path()).context(format!(
+        "Can't extract backup file: {}",
+        backup_path.display()
+    ))?;
+    std::fs::remove_file(staging_directory.path().join(MANIFEST_FILE_NAME))?;
+
+    replace_directory(&root_directory, &staging_directory.into_path())?;
+    Ok(manifest)
+}
+
+/// Replace a directory with a directory on the same filesystem.
+///
+/// The existing directory is moved aside first, and moved back if the replacement can't be moved
+/// into place. It is removed once the replacement is in place.
+fn replace_directory(
+    directory: &std::path::PathBuf,
+    replacement: &std::path::PathBuf,
+) -> Result<()> {
+    if !directory.exists() {
+        std::fs::rename(&replacement, &directory)?;
+        return Ok(());
+    }
+
+    let directory_name = directory
+        .file_name()
+        .ok_or(format_err!(
+            "Invalid directory path: {}",
+            directory.display()
+        ))?
+        .to_string_lossy();
+    let previous_directory = directory.with_file_name(format!(
+        ".{}-previous-{}",
+        directory_name,
+        uuid::Uuid::new_v4()
+    ));
+    std::fs::rename(&directory, &previous_directory).context(format!(
+        "Can't move data directory aside: {}",
+        directory.display()
+    ))?;
+    if let Err(error) = std::fs::rename(&replacement, &directory) {
+        std::fs::rename(&previous_directory, &directory)?;
+        std::fs::remove_dir_all(&replacement)?;
+        return Err(error).context(format!(
+            "Can't move restored data into place: {}",
+            directory.display()
+        ));
+    }
+    std::fs::remove_dir_all(&previous_directory)?;
+    Ok(())
+}
+
+fn read_manifest(backup_path: &std::path::PathBuf) -> Result<Manifest> {
+    let file = std::fs::File::open(&backup_path)
+        .context(format!("Can't open backup file: {}", backup_path.display()))?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.path()? == std::path::Path::new(MANIFEST_FILE_NAME) {
+            let mut contents = String::new();
+            entry.read_to_string(&mut contents)?;
+            return Ok(serde_json::from_str(&contents)?);
+        }
+    }
+    Err(format_err!(
+        "Backup manifest not found in archive: {}",
+        backup_path.display()
+    ))
+}
+
+fn is_non_empty_directory(path: &std::path::PathBuf) -> Result<bool> {
+    if !path.is_dir() {
+        return Ok(false);
+    }
+    Ok(std::fs::read_dir(&path)?.next().is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_restore_round_trip() -> Result<()> {
+        let tmp_dir = tempdir::TempDir::new("vouch_test_backup_restore_round_trip")?;
+        let source_directory = tmp_dir.path().join("source");
+        std::fs::create_dir_all(source_directory.join(".git"))?;
+        std::fs::create_dir_all(source_directory.join("peers").join("example"))?;
+        std::fs::write(source_directory.join(".git").join("HEAD"), "ref")?;
+        std::fs::write(source_directory.join("index.db"), "index")?;
+        std::fs::write(
+            source_directory.join("peers").join("example").join(".git"),
+            "gitdir",
+        )?;
+
+        let backup_path = tmp_dir.path().join("backup.tar.gz");
+        create_archive(&source_directory, &backup_path)?;
+
+        let target_directory = tmp_dir.path().join("target");
+        std::fs::create_dir_all(&target_directory)?;
+        std::fs::write(target_directory.join("stale.txt"), "stale")?;
+        assert!(restore_archive(&target_directory, &backup_path, false).is_err());
+
+        let manifest = restore_archive(&target_directory, &backup_path, true)?;
+        assert_eq!(manifest.vouch_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(
+            std::fs::read_to_string(target_directory.join(".git").join("HEAD"))?,
+            "ref"
+        );
+        assert_eq!(
+            std::fs::read_to_string(target_directory.join("index.db"))?,
+            "index"
+        );
+        assert!(target_directory
+            .join("peers")
+            .join("example")
+            .join(".git")
+            .is_file());
+        assert!(!target_directory.join("stale.txt").exists());
+        assert!(!target_directory.join(MANIFEST_FILE_NAME).exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_failed_restore_keeps_existing_data() -> Result<()> {
+        let tmp_dir = tempdir::TempDir::new("vouch_test_failed_restore_keeps_existing_data")?;
+        let source_directory = tmp_dir.path().join("source");
+        std::fs::create_dir_all(&source_directory)?;
+        // Poorly compressible contents so that truncating the archive cuts into the file data.
+        let mut state: u32 = 1;
+        let contents: Vec<u8> = (0..65536)
+            .map(|_| {
+                state = state.wrapping_mul(1103515245).wrapping_add(12345);
+                (state >> 16) as u8
+            })
+            .collect();
+        std::fs::write(source_directory.join("index.db"), &contents)?;
+
+        let backup_path = tmp_dir.path().join("backup.tar.gz");
+        create_archive(&source_directory, &backup_path)?;
+        let archive = std::fs::read(&backup_path)?;
+        std::fs::write(&backup_path, &archive[..archive.len() / 2])?;
+
+        let target_directory = tmp_dir.path().join("target");
+        std::fs::create_dir_all(&target_directory)?;
+        std::fs::write(target_directory.join("index.db"), "original")?;
+
+        assert!(restore_archive(&target_directory, &backup_path, true).is_err());
+        assert_eq!(
+            std::fs::read_to_string(target_directory.join("index.db"))?,
+            "original"
+        );
+        let remaining: Vec<_> = std::fs::read_dir(tmp_dir.path())?
+            .map(|entry| entry.map(|entry| entry.file_name()))
+            .collect::<std::io::Result<_>>()?;
+        assert_eq!(remaining.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_rejects_backup_inside_data_directory() -> Result<()> {
+        let tmp_dir =
+            tempdir::TempDir::new("vouch_test_restore_rejects_backup_inside_data_directory")?;
+        let source_directory = tmp_dir.path().join("source");
+        std::fs::create_dir_all(&source_directory)?;
+        std::fs::write(source_directory.join("index.db"), "index")?;
+
+        let backup_path = tmp_dir.path().join("backup.tar.gz");
+        create_archive(&source_directory, &backup_path)?;
+        let inner_backup_path = source_directory.join("backup.tar.gz");
+        std::fs::rename(&backup_path, &inner_backup_path)?;
+
+        assert!(restore_archive(&source_directory, &inner_backup_path, true).is_err());
+        assert!(inner_backup_path.is_file());
+        assert_eq!(
+            std::fs::read_to_string(source_directory.join("index.db"))?,
+            "index"
+        );
+        Ok(())
+    }
+}
@@ -3,6 +3,7 @@ use anyhow::Result;
 use crate::review;
 use rusqlite;
 
+use crate::common::index::ID;
 use crate::common::StoreTransaction;
 use crate::package;
 use crate::peer;
@@ -19,14 +20,14 @@ impl Index {
     /// Load root index.
     pub fn from_root() -> Result<Self> {
         Ok(Self {
-            db: peer::fs::get_root_database()?,
+            db: enable_foreign_keys(peer::fs::get_root_database()?)?,
         })
     }
 
     /// Load the index of a given peer.
     pub fn from_peer(peer_branch: &Vec<peer::Peer>) -> Result<Self> {
         Ok(Self {
-            db: peer::fs::get_peer_database(&peer_branch)?,
+            db: enable_foreign_keys(peer::fs::get_peer_database(&peer_branch)?)?,
         })
     }
 
@@ -34,37 +35,191 @@ impl Index {
     #[allow(dead_code)]
     pub fn in_memory() -> Result<Self> {
         Ok(Self {
-            db: rusqlite::Connection::open_in_memory()?,
+            db: enable_foreign_keys(rusqlite::Connection::open_in_memory()?)?,
         })
     }
 }
 
+/// Enforce foreign key constraints. SQLite ignores them by default.
+///
+/// The pragma has no effect within a transaction, so it is set when the connection is opened.
+fn enable_foreign_keys(db: rusqlite::Connection) -> Result<rusqlite::Connection> {
+    db.execute_batch("PRAGMA foreign_keys = ON")?;
+    Ok(db)
+}
+
+/// Merge an incoming peer index into the index.
+///
+/// If given, peers and reviews deeper than the maximum depth within the incoming peer tree are
+/// not merged. The incoming root peer is at depth 1.
 pub fn merge(
     incoming_root_git_url: &crate::common::GitUrl,
+    max_depth: Option<usize>,
     incoming_tx: &StoreTransaction,
     tx: &StoreTransaction,
 ) -> Result<()> {
     registry::index::merge(&incoming_tx, &tx)?;
-    peer::index::merge(&incoming_root_git_url, &incoming_tx, &tx)?;
+    peer::index::merge(&incoming_root_git_url, max_depth, &incoming_tx, &tx)?;
     package::index::merge(&incoming_tx, &tx)?;
-    review::index::merge(&incoming_root_git_url, &incoming_tx, &tx)?;
+    review::index::merge(&incoming_root_git_url, max_depth, &incoming_tx, &tx)?;
 
     // TODO: Remove unused rows after inserting reviews. Add index::clean.
     Ok(())
 }
 
+/// Index schema migrations as (version, SQL) pairs. Versions must be unique and increasing.
+static MIGRATIONS: &[(u32, &str)] = &[
+    (1, "ALTER TABLE review ADD COLUMN version_constraint TEXT"),
+    (2, "ALTER TABLE peer ADD COLUMN max_depth INTEGER"),
+];
+
 /// Setup database schema. Insert root peer.
 pub fn setup(tx: &StoreTransaction) -> Result<()> {
     peer::index::setup(&tx)?;
     registry::index::setup(&tx)?;
     package::index::setup(&tx)?;
     review::index::setup(&tx)?;
+    crate::common::index::migrate(&tx, MIGRATIONS)?;
+    peer::index::ensure_root(&tx)?;
     Ok(())
 }
 
+/// Check cross-table references which are not covered by foreign key constraints.
+///
+/// Returns a message for each violation found. Empty if all references are valid.
+pub fn health_check(tx: &StoreTransaction) -> Result<Vec<String>> {
+    let package_ids = get_ids("package", &tx)?;
+    let peer_ids = get_ids("peer", &tx)?;
+    let comment_ids = get_ids("comment", &tx)?;
+    let registry_ids = get_ids("registry", &tx)?;
+
+    let mut messages = vec![];
+
+    let mut statement = tx
+        .index_tx()
+        .prepare("SELECT id, peer_id, package_id, comment_ids FROM review")?;
+    let mut rows = statement.query(rusqlite::NO_PARAMS)?;
+    while let Some(row) = rows.next()? {
+        let review_id: ID = row.get(0)?;
+        let peer_id: ID = row.get(1)?;
+        if !peer_ids.contains(&peer_id) {
+            messages.push(format!(
+                "Review {} references missing peer {}.",
+                review_id, peer_id
+            ));
+        }
+        let package_id: ID = row.get(2)?;
+        if !package_ids.contains(&package_id) {
+            messages.push(format!(
+                "Review {} references missing package {}.",
+                review_id, package_id
+            ));
+        }
+        if let Some(ids) = row.get::<_, Option<Vec<u8>>>(3)? {
+            let ids: Vec<ID> = bincode::deserialize(&ids)?;
+            for id in ids.iter().filter(|id| !comment_ids.contains(id)) {
+                messages.push(format!(
+                    "Review {} references missing comment {}.",
+                    review_id, id
+                ));
+            }
+        }
+    }
+
+    let mut statement = tx
+        .index_tx()
+        .prepare("SELECT id, registry_ids FROM package")?;
+    let mut rows = statement.query(rusqlite::NO_PARAMS)?;
+    while let Some(row) = rows.next()? {
+        let package_id: ID = row.get(0)?;
+        let ids: Vec<ID> = bincode::deserialize(&row.get::<_, Vec<u8>>(1)?)?;
+        for id in ids.iter().filter(|id| !registry_ids.contains(id)) {
+            messages.push(format!(
+                "Package {} references missing registry {}.",
+                package_id, id
+            ));
+        }
+    }
+    Ok(messages)
+}
+
+/// Returns all row IDs of an index table.
+fn get_ids(table: &str, tx: &StoreTransaction) -> Result<std::collections::HashSet<ID>> {
+    let mut statement = tx
+        .index_tx()
+        .prepare(&format!("SELECT id FROM {}", table))?;
+    let rows = statement.query_map(rusqlite::NO_PARAMS, |row| row.get(0))?;
+    let mut ids = std::collections::HashSet::new();
+    for id in rows {
+        ids.insert(id?);
+    }
+    Ok(ids)
+}
+
 pub fn setup_in_memory(index: &mut Index) -> Result<()> {
     let tx = StoreTransaction::new(index.db.transaction()?)?;
     setup(&tx)?;
     tx.commit_index()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_setup_applies_migrations() -> Result<()> {
+        let mut db = rusqlite::Connection::open_in_memory()?;
+        let tx = StoreTransaction::new(db.transaction()?)?;
+        setup(&tx)?;
+        // Repeated setup must not reapply migrations.
+        setup(&tx)?;
+
+        let user_version: u32 =
+            tx.index_tx()
+                .query_row("PRAGMA user_version", rusqlite::NO_PARAMS, |row| row.get(0))?;
+        assert_eq!(user_version, 2);
+        for (table, column) in &[("review", "version_constraint"), ("peer", "max_depth")] {
+            let column_count: i64 = tx.index_tx().query_row(
+                &format!(
+                    "SELECT COUNT(*) FROM pragma_table_info('{}') WHERE name = '{}'",
+                    table, column
+                ),
+                rusqlite::NO_PARAMS,
+                |row| row.get(0),
+            )?;
+            assert_eq!(column_count, 1);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_health_check_reports_missing_references() -> Result<()> {
+        let mut db = rusqlite::Connection::open_in_memory()?;
+        // Allow inserting rows with invalid references.
+        db.execute_batch("PRAGMA foreign_keys = OFF")?;
+        let tx = StoreTransaction::new(db.transaction()?)?;
+        setup(&tx)?;
+        assert!(health_check(&tx)?.is_empty());
+
+        tx.index_tx().execute_named(
+            "INSERT INTO package (name, version, registry_ids, artifact_hash)
+            VALUES ('d3', '1.0.0', :registry_ids, 'hash')",
+            &[(":registry_ids", &bincode::serialize(&vec![7 as ID])?)],
+        )?;
+        tx.index_tx().execute_named(
+            "INSERT INTO review (peer_id, package_id, comment_ids)
+            VALUES (1, 9, :comment_ids)",
+            &[(":comment_ids", &bincode::serialize(&vec![3 as ID])?)],
+        )?;
+
+        let result = health_check(&tx)?;
+        let expected = vec![
+            "Review 1 references missing package 9.".to_string(),
+            "Review 1 references missing comment 3.".to_string(),
+            "Package 1 references missing registry 7.".to_string(),
+        ];
+        assert_eq!(result, expected);
+        Ok(())
+    }
+}
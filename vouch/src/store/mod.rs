@@ -2,7 +2,9 @@ use crate::common::StoreTransaction;
 use crate::peer;
 use anyhow::Result;
 
+pub mod backup;
 pub mod index;
+pub mod status;
 
 pub struct Store {
     index: index::Index,
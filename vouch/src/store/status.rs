@@ -0,0 +1,116 @@
+//! Local data directory size and health metrics.
+
+use anyhow::Result;
+
+use crate::common::fs::DataPaths;
+use crate::common::StoreTransaction;
+use crate::review;
+
+/// Index tables which are counted in the store status.
+static INDEX_TABLES: &[&str] = &["peer", "package", "review", "comment", "registry"];
+
+/// Store size and health metrics.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize)]
+pub struct Status {
+    /// Total size of the data directory in bytes.
+    #[serde(rename = "data-directory-size")]
+    pub data_directory_size: u64,
+
+    /// Size of the index database file in bytes.
+    #[serde(rename = "index-file-size")]
+    pub index_file_size: u64,
+
+    /// Number of rows per index table.
+    #[serde(rename = "table-row-counts")]
+    pub table_row_counts: std::collections::BTreeMap<String, usize>,
+
+    /// Number of ongoing review workspaces.
+    #[serde(rename = "ongoing-workspaces")]
+    pub ongoing_workspaces: usize,
+
+    /// Number of peer git submodules.
+    #[serde(rename = "peer-submodules")]
+    pub peer_submodules: usize,
+
+    /// Index database integrity check errors. Empty if the index is healthy.
+    #[serde(rename = "integrity-errors")]
+    pub integrity_errors: Vec<String>,
+
+    /// Cross-table reference violations. Empty if all references are valid.
+    #[serde(rename = "reference-errors")]
+    pub reference_errors: Vec<String>,
+}
+
+/// Collect store status metrics.
+pub fn get(tx: &StoreTransaction) -> Result<Status> {
+    let paths = DataPaths::new()?;
+
+    let mut table_row_counts = std::collections::BTreeMap::new();
+    for table in INDEX_TABLES {
+        let count: i64 = tx.index_tx().query_row(
+            &format!("SELECT COUNT(*) FROM {}", table),
+            rusqlite::NO_PARAMS,
+            |row| row.get(0),
+        )?;
+        table_row_counts.insert(table.to_string(), count as usize);
+    }
+
+    let submodule_status =
+        crate::common::fs::git_output(vec!["submodule", "status"], &paths.root_directory)?;
+
+    Ok(Status {
+        data_directory_size: get_directory_size(&paths.root_directory)?,
+        index_file_size: std::fs::metadata(&paths.index_file)?.len(),
+        table_row_counts,
+        ongoing_workspaces: review::workspace::get_all_existing()?.len(),
+        peer_submodules: submodule_status
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .count(),
+        integrity_errors: get_integrity_errors(&tx)?,
+        reference_errors: super::index::health_check(&tx)?,
+    })
+}
+
+/// Returns the total size in bytes of all files within a directory.
+fn get_directory_size(directory: &std::path::PathBuf) -> Result<u64> {
+    let mut size = 0;
+    for entry in walkdir::WalkDir::new(&directory) {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            size += entry.metadata()?.len();
+        }
+    }
+    Ok(size)
+}
+
+/// Run the SQLite integrity check. Returns the reported errors, if any.
+fn get_integrity_errors(tx: &StoreTransaction) -> Result<Vec<String>> {
+    let mut statement = tx.index_tx().prepare("PRAGMA integrity_check")?;
+    let rows = statement.query_map(rusqlite::NO_PARAMS, |row| row.get::<_, String>(0))?;
+    let mut messages = vec![];
+    for row in rows {
+        messages.push(row?);
+    }
+    Ok(messages
+        .into_iter()
+        .filter(|message| message != "ok")
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_directory_size() -> Result<()> {
+        let tmp_dir = tempdir::TempDir::new("vouch_test_store_status")?;
+        let directory = tmp_dir.path().to_path_buf();
+        std::fs::write(directory.join("a.txt"), "abc")?;
+        std::fs::create_dir(directory.join("sub"))?;
+        std::fs::write(directory.join("sub").join("b.txt"), "defgh")?;
+
+        assert_eq!(get_directory_size(&directory)?, 8);
+        Ok(())
+    }
+}